@@ -9,18 +9,24 @@
 #![deny(rustdoc::all)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
+pub mod barrier;
 pub mod condition;
 pub mod coproc;
+pub mod hint;
 pub mod register;
 pub mod set_flags;
 pub mod shift;
+pub mod special_register;
 pub mod wrapper_types;
 
-pub use condition::{Condition, ITCondition};
+pub use barrier::BarrierOption;
+pub use condition::{Apsr, Condition, Flag, FlagExpr, ITCondition};
 pub use coproc::CoProcessor;
+pub use hint::Hint;
 pub use register::{Register, RegisterList};
 pub use set_flags::SetFlags;
 pub use shift::{ImmShift, Shift};
+pub use special_register::{ApsrMask, SpecialRegister};
 pub use wrapper_types::*;
 
 #[derive(Debug, Clone)]
@@ -37,6 +43,30 @@ pub enum ArchError {
     InvalidField(String),
 }
 
+impl ArchError {
+    /// Returns a stable identifier for the kind of error.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidCondition => "A0001",
+            Self::InvalidRegister(_) => "A0002",
+            Self::InvalidField(_) => "A0003",
+        }
+    }
+}
+
+impl std::fmt::Display for ArchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] ", self.code())?;
+        match self {
+            Self::InvalidCondition => write!(f, "invalid condition"),
+            Self::InvalidRegister(register) => write!(f, "invalid register {register}"),
+            Self::InvalidField(field) => write!(f, "invalid field, {field}"),
+        }
+    }
+}
+
+impl std::error::Error for ArchError {}
+
 /// Masks out a set of bits from the number
 pub(crate) trait Mask {
     /// Masks out bits start -> end from the number
@@ -0,0 +1,122 @@
+//! Defines the [`option`](BarrierOption) field of the memory barriers.
+
+use std::fmt::Display;
+
+use crate::ArchError;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Enumerates the domains and access types that a barrier applies to.
+///
+/// ARMv7-M only defines [`Sy`](Self::Sy), all other values are treated as
+/// `Sy` by the processor but are kept so that they can be printed.
+pub enum BarrierOption {
+    /// Full system, reads and writes.
+    Sy,
+    /// Full system, writes.
+    St,
+    /// Full system, reads.
+    Ld,
+    /// Inner shareable, reads and writes.
+    Ish,
+    /// Inner shareable, writes.
+    IshSt,
+    /// Inner shareable, reads.
+    IshLd,
+    /// Non shareable, reads and writes.
+    Nsh,
+    /// Non shareable, writes.
+    NshSt,
+    /// Non shareable, reads.
+    NshLd,
+    /// Outer shareable, reads and writes.
+    Osh,
+    /// Outer shareable, writes.
+    OshSt,
+    /// Outer shareable, reads.
+    OshLd,
+    /// A reserved value.
+    Reserved(u8),
+}
+
+impl TryFrom<u8> for BarrierOption {
+    type Error = ArchError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0b1111 => Self::Sy,
+            0b1110 => Self::St,
+            0b1101 => Self::Ld,
+            0b1011 => Self::Ish,
+            0b1010 => Self::IshSt,
+            0b1001 => Self::IshLd,
+            0b0111 => Self::Nsh,
+            0b0110 => Self::NshSt,
+            0b0101 => Self::NshLd,
+            0b0011 => Self::Osh,
+            0b0010 => Self::OshSt,
+            0b0001 => Self::OshLd,
+            0b0000 | 0b0100 | 0b1000 | 0b1100 => Self::Reserved(value),
+            _ => return Err(ArchError::InvalidField(format!("BarrierOption {value}"))),
+        })
+    }
+}
+
+impl From<BarrierOption> for u8 {
+    fn from(value: BarrierOption) -> Self {
+        match value {
+            BarrierOption::Sy => 0b1111,
+            BarrierOption::St => 0b1110,
+            BarrierOption::Ld => 0b1101,
+            BarrierOption::Ish => 0b1011,
+            BarrierOption::IshSt => 0b1010,
+            BarrierOption::IshLd => 0b1001,
+            BarrierOption::Nsh => 0b0111,
+            BarrierOption::NshSt => 0b0110,
+            BarrierOption::NshLd => 0b0101,
+            BarrierOption::Osh => 0b0011,
+            BarrierOption::OshSt => 0b0010,
+            BarrierOption::OshLd => 0b0001,
+            BarrierOption::Reserved(value) => value,
+        }
+    }
+}
+
+impl Display for BarrierOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Sy => "sy",
+            Self::St => "st",
+            Self::Ld => "ld",
+            Self::Ish => "ish",
+            Self::IshSt => "ishst",
+            Self::IshLd => "ishld",
+            Self::Nsh => "nsh",
+            Self::NshSt => "nshst",
+            Self::NshLd => "nshld",
+            Self::Osh => "osh",
+            Self::OshSt => "oshst",
+            Self::OshLd => "oshld",
+            Self::Reserved(value) => return write!(f, "#{value:#x}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BarrierOption;
+
+    #[test]
+    fn test_round_trip() {
+        for option in 0..16u8 {
+            assert_eq!(u8::from(BarrierOption::try_from(option).unwrap()), option);
+        }
+        assert!(BarrierOption::try_from(16).is_err());
+        assert_eq!(BarrierOption::try_from(0b1111).unwrap(), BarrierOption::Sy);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(BarrierOption::IshSt.to_string(), "ishst");
+        assert_eq!(BarrierOption::Reserved(0b1100).to_string(), "#0xc");
+    }
+}
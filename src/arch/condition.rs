@@ -6,9 +6,9 @@ use crate::ArchError;
 #[derive(Debug, Clone, PartialEq)]
 /// Derived from section A7.3
 pub enum Condition {
-    /// Exactly equal to, z == 1
+    /// Exactly equal to, Z == 1
     Eq,
-    /// Not equal to, z == 0
+    /// Not equal to, Z == 0
     Ne,
     /// Carry set, C == 1
     Cs,
@@ -16,15 +16,15 @@ pub enum Condition {
     Cc,
     /// Minus, negative N == 1
     Mi,
-    /// Plus, positive or zero, N >= 0
+    /// Plus, positive or zero, N == 0
     Pl,
     /// Overflow, V  == 1
     Vs,
     /// Not Overflow, V == 0
     Vc,
-    /// Unsigned higher, C == 1 && z == 0
+    /// Unsigned higher, C == 1 && Z == 0
     Hi,
-    /// Unsigned lower, C == 0 && z == 1
+    /// Unsigned lower or same, C == 0 || Z == 1
     Ls,
     /// Signed greater or equal, N == V
     Ge,
@@ -32,7 +32,7 @@ pub enum Condition {
     Lt,
     /// Signed greater than, Z == 0 && N == V
     Gt,
-    /// Signed less than or equal, Z == 1 && N!=V
+    /// Signed less than or equal, Z == 1 || N != V
     Le,
     /// Unconditional
     None,
@@ -52,8 +52,157 @@ pub struct ITCondition {
     pub conditions: Vec<Condition>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+/// The condition flags of the application program status register.
+pub struct Apsr {
+    /// Negative flag.
+    pub n: bool,
+    /// Zero flag.
+    pub z: bool,
+    /// Carry flag.
+    pub c: bool,
+    /// Overflow flag.
+    pub v: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The condition flags that a [`Condition`] can depend on.
+pub enum Flag {
+    /// Negative flag.
+    N,
+    /// Zero flag.
+    Z,
+    /// Carry flag.
+    C,
+    /// Overflow flag.
+    V,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A boolean formula over the [`Flag`]s.
+///
+/// This is the symbolic form of a [`Condition`], see
+/// [`Condition::formula`].
+pub enum FlagExpr {
+    /// Always true.
+    True,
+    /// The value of the flag.
+    Flag(Flag),
+    /// Logical negation.
+    Not(Box<FlagExpr>),
+    /// Logical and.
+    And(Box<FlagExpr>, Box<FlagExpr>),
+    /// Logical or.
+    Or(Box<FlagExpr>, Box<FlagExpr>),
+    /// True if both sides evaluate to the same value.
+    Eq(Box<FlagExpr>, Box<FlagExpr>),
+}
+
+impl Apsr {
+    /// Reads the value of the `flag`.
+    pub fn get(&self, flag: Flag) -> bool {
+        match flag {
+            Flag::N => self.n,
+            Flag::Z => self.z,
+            Flag::C => self.c,
+            Flag::V => self.v,
+        }
+    }
+}
+
+impl FlagExpr {
+    fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    fn and(self, other: Self) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    fn or(self, other: Self) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    fn eq(self, other: Self) -> Self {
+        Self::Eq(Box::new(self), Box::new(other))
+    }
+
+    /// Evaluates the formula for the given flags.
+    pub fn evaluate(&self, apsr: &Apsr) -> bool {
+        match self {
+            Self::True => true,
+            Self::Flag(flag) => apsr.get(*flag),
+            Self::Not(expr) => !expr.evaluate(apsr),
+            Self::And(lhs, rhs) => lhs.evaluate(apsr) && rhs.evaluate(apsr),
+            Self::Or(lhs, rhs) => lhs.evaluate(apsr) || rhs.evaluate(apsr),
+            Self::Eq(lhs, rhs) => lhs.evaluate(apsr) == rhs.evaluate(apsr),
+        }
+    }
+}
+
 impl Condition {
-    fn invert(&self) -> Self {
+    /// Evaluates the condition for the given flag values.
+    ///
+    /// This follows the `ConditionPassed` pseudo code in section A7.3.
+    pub fn evaluate(&self, n: bool, z: bool, c: bool, v: bool) -> bool {
+        match self {
+            Self::Eq => z,
+            Self::Ne => !z,
+            Self::Cs => c,
+            Self::Cc => !c,
+            Self::Mi => n,
+            Self::Pl => !n,
+            Self::Vs => v,
+            Self::Vc => !v,
+            Self::Hi => c && !z,
+            Self::Ls => !c || z,
+            Self::Ge => n == v,
+            Self::Lt => n != v,
+            Self::Gt => !z && n == v,
+            Self::Le => z || n != v,
+            Self::None => true,
+        }
+    }
+
+    /// Evaluates the condition against the flags in the [`Apsr`].
+    pub fn evaluate_apsr(&self, apsr: &Apsr) -> bool {
+        self.evaluate(apsr.n, apsr.z, apsr.c, apsr.v)
+    }
+
+    /// Returns the boolean formula over the flags that this condition
+    /// represents.
+    ///
+    /// This is useful for symbolic executors that need to branch on the
+    /// flags rather than evaluate them.
+    pub fn formula(&self) -> FlagExpr {
+        let n = FlagExpr::Flag(Flag::N);
+        let z = FlagExpr::Flag(Flag::Z);
+        let c = FlagExpr::Flag(Flag::C);
+        let v = FlagExpr::Flag(Flag::V);
+        match self {
+            Self::Eq => z,
+            Self::Ne => z.not(),
+            Self::Cs => c,
+            Self::Cc => c.not(),
+            Self::Mi => n,
+            Self::Pl => n.not(),
+            Self::Vs => v,
+            Self::Vc => v.not(),
+            Self::Hi => c.and(z.not()),
+            Self::Ls => c.not().or(z),
+            Self::Ge => n.eq(v),
+            Self::Lt => n.eq(v).not(),
+            Self::Gt => z.not().and(n.eq(v)),
+            Self::Le => z.or(n.eq(v).not()),
+            Self::None => FlagExpr::True,
+        }
+    }
+
+    /// Returns the condition that holds exactly when `self` does not.
+    ///
+    /// Note that [`Condition::None`] is its own inverse as there is no
+    /// never condition in the Armv7-m ISA.
+    pub fn invert(&self) -> Self {
         match self {
             Self::Eq => Self::Ne,
             Self::Ne => Self::Eq,
@@ -74,6 +223,23 @@ impl Condition {
     }
 }
 
+impl ITCondition {
+    /// Evaluates the condition for the `index`th instruction in the IT block.
+    ///
+    /// Returns None if the block does not contain that many instructions.
+    pub fn evaluate(&self, index: usize, n: bool, z: bool, c: bool, v: bool) -> Option<bool> {
+        Some(self.conditions.get(index)?.evaluate(n, z, c, v))
+    }
+
+    /// Evaluates the condition for the `index`th instruction in the IT block
+    /// against the flags in the [`Apsr`].
+    ///
+    /// Returns None if the block does not contain that many instructions.
+    pub fn evaluate_apsr(&self, index: usize, apsr: &Apsr) -> Option<bool> {
+        Some(self.conditions.get(index)?.evaluate_apsr(apsr))
+    }
+}
+
 impl From<(Condition, u8)> for ITCondition {
     fn from(value: (Condition, u8)) -> Self {
         let mask = value.1;
@@ -185,3 +351,116 @@ impl TryFrom<u16> for Condition {
         Self::try_from(value as u8)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Apsr, Condition, ITCondition};
+
+    const CONDITIONS: [Condition; 15] = [
+        Condition::Eq,
+        Condition::Ne,
+        Condition::Cs,
+        Condition::Cc,
+        Condition::Mi,
+        Condition::Pl,
+        Condition::Vs,
+        Condition::Vc,
+        Condition::Hi,
+        Condition::Ls,
+        Condition::Ge,
+        Condition::Lt,
+        Condition::Gt,
+        Condition::Le,
+        Condition::None,
+    ];
+
+    fn all_flags() -> impl Iterator<Item = Apsr> {
+        (0..16u8).map(|bits| Apsr {
+            n: bits & 0b1000 != 0,
+            z: bits & 0b100 != 0,
+            c: bits & 0b10 != 0,
+            v: bits & 0b1 != 0,
+        })
+    }
+
+    /// The `ConditionPassed` pseudo code from section A7.3.
+    fn reference(code: u8, apsr: &Apsr) -> bool {
+        let result = match code >> 1 {
+            0b000 => apsr.z,
+            0b001 => apsr.c,
+            0b010 => apsr.n,
+            0b011 => apsr.v,
+            0b100 => apsr.c && !apsr.z,
+            0b101 => apsr.n == apsr.v,
+            0b110 => apsr.n == apsr.v && !apsr.z,
+            0b111 => true,
+            _ => unreachable!(),
+        };
+        if code & 0b1 == 1 && code != 0b1111 {
+            !result
+        } else {
+            result
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        for cond in CONDITIONS {
+            let code: u8 = cond.clone().into();
+            for apsr in all_flags() {
+                assert_eq!(
+                    cond.evaluate(apsr.n, apsr.z, apsr.c, apsr.v),
+                    reference(code, &apsr),
+                    "{cond:?} {apsr:?}"
+                );
+                assert_eq!(cond.evaluate_apsr(&apsr), reference(code, &apsr));
+            }
+        }
+    }
+
+    #[test]
+    fn test_formula() {
+        for cond in CONDITIONS {
+            let formula = cond.formula();
+            for apsr in all_flags() {
+                assert_eq!(
+                    formula.evaluate(&apsr),
+                    cond.evaluate_apsr(&apsr),
+                    "{cond:?} {apsr:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        for cond in CONDITIONS
+            .into_iter()
+            .filter(|cond| *cond != Condition::None)
+        {
+            for apsr in all_flags() {
+                assert_eq!(
+                    cond.invert().evaluate_apsr(&apsr),
+                    !cond.evaluate_apsr(&apsr)
+                );
+            }
+            assert_eq!(cond.invert().invert(), cond);
+        }
+        assert_eq!(Condition::None.invert(), Condition::None);
+    }
+
+    #[test]
+    fn test_it_evaluate() {
+        // ITTE EQ
+        let it = ITCondition::from((Condition::Eq, 0b0110));
+        let apsr = Apsr {
+            z: true,
+            ..Default::default()
+        };
+        assert_eq!(it.evaluate_apsr(0, &apsr), Some(true));
+        assert_eq!(it.evaluate_apsr(1, &apsr), Some(true));
+        assert_eq!(it.evaluate_apsr(2, &apsr), Some(false));
+        assert_eq!(it.evaluate(2, false, false, false, false), Some(true));
+        assert_eq!(it.evaluate_apsr(3, &apsr), None);
+    }
+}
@@ -0,0 +1,90 @@
+//! Defines the [`hints`](Hint), the instructions in the NOP compatible hint
+//! space.
+
+use std::fmt::Display;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Enumerates the hints.
+///
+/// Hints that are not allocated execute as a NOP, the hint number is kept so
+/// that hints from later versions of the architecture are not lost.
+pub enum Hint {
+    /// No operation.
+    Nop,
+    /// Signals that the thread is waiting.
+    Yield,
+    /// Wait for event.
+    Wfe,
+    /// Wait for interrupt.
+    Wfi,
+    /// Send event.
+    Sev,
+    /// Hint to the debug system with its 4 bit option.
+    Dbg(u8),
+    /// A hint that is not allocated, with its hint number.
+    Unallocated(u8),
+}
+
+impl From<u8> for Hint {
+    /// Decodes the hint number, the `op2` field of the 32 bit encoding or the
+    /// `opA` field of the 16 bit encoding.
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Nop,
+            1 => Self::Yield,
+            2 => Self::Wfe,
+            3 => Self::Wfi,
+            4 => Self::Sev,
+            0b1111_0000.. => Self::Dbg(value & 0b1111),
+            _ => Self::Unallocated(value),
+        }
+    }
+}
+
+impl From<Hint> for u8 {
+    fn from(value: Hint) -> Self {
+        match value {
+            Hint::Nop => 0,
+            Hint::Yield => 1,
+            Hint::Wfe => 2,
+            Hint::Wfi => 3,
+            Hint::Sev => 4,
+            Hint::Dbg(option) => 0b1111_0000 | (option & 0b1111),
+            Hint::Unallocated(value) => value,
+        }
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nop => write!(f, "nop"),
+            Self::Yield => write!(f, "yield"),
+            Self::Wfe => write!(f, "wfe"),
+            Self::Wfi => write!(f, "wfi"),
+            Self::Sev => write!(f, "sev"),
+            Self::Dbg(option) => write!(f, "dbg #{option}"),
+            Self::Unallocated(value) => write!(f, "hint #{value}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Hint;
+
+    #[test]
+    fn test_round_trip() {
+        for value in 0..=u8::MAX {
+            assert_eq!(u8::from(Hint::from(value)), value);
+        }
+        assert_eq!(Hint::from(0xf3), Hint::Dbg(3));
+        assert_eq!(Hint::from(0x14), Hint::Unallocated(0x14));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Hint::Dbg(5).to_string(), "dbg #5");
+        assert_eq!(Hint::Unallocated(20).to_string(), "hint #20");
+    }
+}
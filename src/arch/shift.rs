@@ -29,13 +29,78 @@ pub struct ImmShift {
     pub shift_t: Shift,
 }
 
+impl Shift {
+    /// Applies the shift to `value` and computes the carry out.
+    ///
+    /// This is the `Shift_C` function from section A7.4.2, where `amount` is
+    /// the number of bits to shift by. A shift by zero leaves the value and
+    /// the carry untouched. [`Shift::Rrx`] always rotates by one bit through
+    /// the carry flag, regardless of `amount`.
+    pub fn apply(&self, value: u32, amount: u32, carry_in: bool) -> (u32, bool) {
+        if amount == 0 {
+            return (value, carry_in);
+        }
+        match self {
+            Self::Lsl => {
+                let result = value.checked_shl(amount).unwrap_or(0);
+                let carry = match amount {
+                    1..=32 => (value >> (32 - amount)) & 0b1 == 1,
+                    _ => false,
+                };
+                (result, carry)
+            }
+            Self::Lsr => {
+                let result = value.checked_shr(amount).unwrap_or(0);
+                let carry = match amount {
+                    1..=32 => (value >> (amount - 1)) & 0b1 == 1,
+                    _ => false,
+                };
+                (result, carry)
+            }
+            Self::Asr => {
+                let amount = amount.min(32);
+                let result = ((value as i32) >> (amount.min(31))) as u32;
+                let carry = ((value as i32) >> (amount - 1)) & 0b1 == 1;
+                (result, carry)
+            }
+            Self::Ror => {
+                let result = value.rotate_right(amount % 32);
+                (result, result >> 31 == 1)
+            }
+            Self::Rrx => {
+                let result = ((carry_in as u32) << 31) | (value >> 1);
+                (result, value & 0b1 == 1)
+            }
+        }
+    }
+
+    /// Applies the shift to `value` using the shift amount stored in a
+    /// register.
+    ///
+    /// This is used by the register shifted instructions, such as
+    /// [`AsrRegister`](crate::operation::AsrRegister), which only use the
+    /// bottom byte of the register to determine the shift amount.
+    pub fn apply_by_register(&self, value: u32, amount: u32, carry_in: bool) -> (u32, bool) {
+        self.apply(value, amount & 0xff, carry_in)
+    }
+}
+
+impl ImmShift {
+    /// Applies the shift to `value` and computes the carry out.
+    ///
+    /// See [`Shift::apply`] for further details.
+    pub fn apply(&self, value: u32, carry_in: bool) -> (u32, bool) {
+        self.shift_t.apply(value, self.shift_n as u32, carry_in)
+    }
+}
+
 impl TryFrom<u8> for Shift {
     type Error = ArchError;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
             0 => Ok(Self::Lsl),
-            1 => Ok(Self::Asr),
+            1 => Ok(Self::Lsr),
             2 => Ok(Self::Asr),
             3 => Ok(Self::Ror),
             _ => Err(ArchError::InvalidField(format!(
@@ -53,7 +118,7 @@ impl From<(Shift, u8)> for ImmShift {
                 shift_n: 32,
             },
             (Shift::Asr, 0) => Self {
-                shift_t: Shift::Lsr,
+                shift_t: Shift::Asr,
                 shift_n: 32,
             },
             (Shift::Ror, 0) => Self {
@@ -65,3 +130,126 @@ impl From<(Shift, u8)> for ImmShift {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ImmShift, Shift};
+
+    const VALUES: [u32; 8] = [
+        0,
+        1,
+        0x8000_0000,
+        0xFFFF_FFFF,
+        0x8000_0001,
+        0x1234_5678,
+        0xDEAD_BEEF,
+        0x7FFF_FFFF,
+    ];
+
+    /// `Shift_C` from section A7.4.2, computed on a 64 bit extended value.
+    fn reference(value: u32, shift_t: &Shift, amount: u32, carry_in: bool) -> (u32, bool) {
+        if amount == 0 {
+            return (value, carry_in);
+        }
+        let bit = |x: u64, i: u32| i < 64 && (x >> i) & 0b1 == 1;
+        match shift_t {
+            Shift::Lsl => {
+                let extended = (value as u128) << amount.min(64);
+                (extended as u32, (extended >> 32) & 0b1 == 1)
+            }
+            Shift::Lsr => {
+                let extended = value as u64;
+                let result = if amount >= 32 { 0 } else { value >> amount };
+                (result, bit(extended, amount - 1))
+            }
+            Shift::Asr => {
+                let extended = value as i32 as i64;
+                let result = (extended >> amount.min(63)) as u32;
+                (result, bit(extended as u64, (amount - 1).min(63)))
+            }
+            Shift::Ror => {
+                let doubled = ((value as u64) << 32) | value as u64;
+                let result = (doubled >> (amount % 32)) as u32;
+                (result, result >> 31 == 1)
+            }
+            Shift::Rrx => ((carry_in as u32) << 31 | value >> 1, value & 0b1 == 1),
+        }
+    }
+
+    #[test]
+    fn test_decode_imm_shift() {
+        let decode = |ty: u8, imm5: u8| ImmShift::from((Shift::try_from(ty).unwrap(), imm5));
+        assert_eq!(decode(0, 0), ImmShift {
+            shift_t: Shift::Lsl,
+            shift_n: 0
+        });
+        for imm5 in 1..32 {
+            assert_eq!(decode(0, imm5).shift_t, Shift::Lsl);
+            assert_eq!(decode(1, imm5).shift_t, Shift::Lsr);
+            assert_eq!(decode(2, imm5).shift_t, Shift::Asr);
+            assert_eq!(decode(3, imm5).shift_t, Shift::Ror);
+            for ty in 0..4 {
+                assert_eq!(decode(ty, imm5).shift_n, imm5);
+            }
+        }
+        assert_eq!(decode(1, 0), ImmShift {
+            shift_t: Shift::Lsr,
+            shift_n: 32
+        });
+        assert_eq!(decode(2, 0), ImmShift {
+            shift_t: Shift::Asr,
+            shift_n: 32
+        });
+        assert_eq!(decode(3, 0), ImmShift {
+            shift_t: Shift::Rrx,
+            shift_n: 1
+        });
+        assert!(Shift::try_from(4u8).is_err());
+    }
+
+    #[test]
+    fn test_apply_imm_shift() {
+        for ty in 0..4u8 {
+            for imm5 in 0..32u8 {
+                let shift = ImmShift::from((Shift::try_from(ty).unwrap(), imm5));
+                for value in VALUES {
+                    for carry in [false, true] {
+                        assert_eq!(
+                            shift.apply(value, carry),
+                            reference(value, &shift.shift_t, shift.shift_n as u32, carry),
+                            "{shift:?} {value:#x} {carry}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_by_register() {
+        for shift in [Shift::Lsl, Shift::Lsr, Shift::Asr, Shift::Ror] {
+            for amount in 0..=255u32 {
+                for value in VALUES {
+                    for carry in [false, true] {
+                        assert_eq!(
+                            shift.apply_by_register(value, amount, carry),
+                            reference(value, &shift, amount, carry),
+                            "{shift:?} {value:#x} {amount} {carry}"
+                        );
+                    }
+                }
+            }
+            // Only the bottom byte is used as the shift amount.
+            assert_eq!(
+                shift.apply_by_register(0xDEAD_BEEF, 0x100, true),
+                (0xDEAD_BEEF, true)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rrx() {
+        assert_eq!(Shift::Rrx.apply(0b11, 1, true), (0x8000_0001, true));
+        assert_eq!(Shift::Rrx.apply(0b10, 1, false), (0b1, false));
+    }
+}
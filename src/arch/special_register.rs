@@ -0,0 +1,216 @@
+//! Defines the [`special registers`](SpecialRegister) that are accessed with
+//! `mrs` and `msr`.
+
+use std::fmt::Display;
+
+use crate::ArchError;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Enumerates the special registers, as selected by the SYSm field.
+///
+/// The limit registers and the non-secure aliases are only available in
+/// ARMv8-M. Reserved values are kept so that they can be printed, accessing
+/// them is UNPREDICTABLE.
+pub enum SpecialRegister {
+    /// The application program status register.
+    Apsr,
+    /// The APSR and IPSR.
+    Iapsr,
+    /// The APSR and EPSR.
+    Eapsr,
+    /// The APSR, IPSR and EPSR.
+    Xpsr,
+    /// The interrupt program status register.
+    Ipsr,
+    /// The execution program status register.
+    Epsr,
+    /// The IPSR and EPSR.
+    Iepsr,
+    /// The main stack pointer.
+    Msp,
+    /// The process stack pointer.
+    Psp,
+    /// The main stack pointer limit.
+    MspLim,
+    /// The process stack pointer limit.
+    PspLim,
+    /// Masks all exceptions with configurable priority.
+    Primask,
+    /// Masks exceptions with a lower priority than the value.
+    Basepri,
+    /// Writes BASEPRI only if it raises the priority.
+    BasepriMax,
+    /// Masks all exceptions except NMI.
+    Faultmask,
+    /// Selects the privilege and the stack pointer of thread mode.
+    Control,
+    /// The non-secure main stack pointer.
+    MspNs,
+    /// The non-secure process stack pointer.
+    PspNs,
+    /// A reserved SYSm value.
+    Reserved(u8),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// The flags of the APSR that are written by `msr`.
+pub struct ApsrMask {
+    /// Writes the N, Z, C, V and Q flags.
+    pub nzcvq: bool,
+    /// Writes the GE flags.
+    pub g: bool,
+}
+
+impl SpecialRegister {
+    /// Returns true if the register includes the APSR flags.
+    pub const fn has_flags(&self) -> bool {
+        matches!(self, Self::Apsr | Self::Iapsr | Self::Eapsr | Self::Xpsr)
+    }
+
+    /// Returns true if writing the register changes the privilege or the
+    /// stack pointer.
+    pub const fn is_stack_or_privilege(&self) -> bool {
+        matches!(
+            self,
+            Self::Msp | Self::Psp | Self::Control | Self::MspNs | Self::PspNs
+        )
+    }
+}
+
+impl From<u8> for SpecialRegister {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Apsr,
+            1 => Self::Iapsr,
+            2 => Self::Eapsr,
+            3 => Self::Xpsr,
+            5 => Self::Ipsr,
+            6 => Self::Epsr,
+            7 => Self::Iepsr,
+            8 => Self::Msp,
+            9 => Self::Psp,
+            0b1010 => Self::MspLim,
+            0b1011 => Self::PspLim,
+            0b10000 => Self::Primask,
+            0b10001 => Self::Basepri,
+            0b10010 => Self::BasepriMax,
+            0b10011 => Self::Faultmask,
+            0b10100 => Self::Control,
+            0b10001000 => Self::MspNs,
+            0b10001001 => Self::PspNs,
+            _ => Self::Reserved(value),
+        }
+    }
+}
+
+impl From<SpecialRegister> for u8 {
+    fn from(value: SpecialRegister) -> Self {
+        match value {
+            SpecialRegister::Apsr => 0,
+            SpecialRegister::Iapsr => 1,
+            SpecialRegister::Eapsr => 2,
+            SpecialRegister::Xpsr => 3,
+            SpecialRegister::Ipsr => 5,
+            SpecialRegister::Epsr => 6,
+            SpecialRegister::Iepsr => 7,
+            SpecialRegister::Msp => 8,
+            SpecialRegister::Psp => 9,
+            SpecialRegister::MspLim => 0b1010,
+            SpecialRegister::PspLim => 0b1011,
+            SpecialRegister::Primask => 0b10000,
+            SpecialRegister::Basepri => 0b10001,
+            SpecialRegister::BasepriMax => 0b10010,
+            SpecialRegister::Faultmask => 0b10011,
+            SpecialRegister::Control => 0b10100,
+            SpecialRegister::MspNs => 0b10001000,
+            SpecialRegister::PspNs => 0b10001001,
+            SpecialRegister::Reserved(value) => value,
+        }
+    }
+}
+
+impl Display for SpecialRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Apsr => "APSR",
+            Self::Iapsr => "IAPSR",
+            Self::Eapsr => "EAPSR",
+            Self::Xpsr => "XPSR",
+            Self::Ipsr => "IPSR",
+            Self::Epsr => "EPSR",
+            Self::Iepsr => "IEPSR",
+            Self::Msp => "MSP",
+            Self::Psp => "PSP",
+            Self::MspLim => "MSPLIM",
+            Self::PspLim => "PSPLIM",
+            Self::Primask => "PRIMASK",
+            Self::Basepri => "BASEPRI",
+            Self::BasepriMax => "BASEPRI_MAX",
+            Self::Faultmask => "FAULTMASK",
+            Self::Control => "CONTROL",
+            Self::MspNs => "MSP_NS",
+            Self::PspNs => "PSP_NS",
+            Self::Reserved(value) => return write!(f, "#{value:#x}"),
+        })
+    }
+}
+
+impl TryFrom<u8> for ApsrMask {
+    type Error = ArchError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value > 0b11 {
+            return Err(ArchError::InvalidField(format!("ApsrMask {value}")));
+        }
+        Ok(Self {
+            nzcvq: value & 0b10 != 0,
+            g: value & 0b01 != 0,
+        })
+    }
+}
+
+impl From<ApsrMask> for u8 {
+    fn from(value: ApsrMask) -> Self {
+        ((value.nzcvq as u8) << 1) | value.g as u8
+    }
+}
+
+impl Display for ApsrMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.nzcvq {
+            f.write_str("nzcvq")?;
+        }
+        if self.g {
+            f.write_str("g")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApsrMask, SpecialRegister};
+
+    #[test]
+    fn test_round_trip() {
+        for sysm in 0..=u8::MAX {
+            assert_eq!(u8::from(SpecialRegister::from(sysm)), sysm);
+        }
+        assert_eq!(SpecialRegister::from(4), SpecialRegister::Reserved(4));
+        assert_eq!(
+            SpecialRegister::from(0b10101),
+            SpecialRegister::Reserved(0b10101)
+        );
+        assert_eq!(SpecialRegister::from(0x89), SpecialRegister::PspNs);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SpecialRegister::BasepriMax.to_string(), "BASEPRI_MAX");
+        assert_eq!(SpecialRegister::Reserved(0x81).to_string(), "#0x81");
+        let mask = ApsrMask::try_from(0b11).unwrap();
+        assert_eq!(mask.to_string(), "nzcvqg");
+        assert_eq!(u8::from(mask), 0b11);
+        assert!(ApsrMask::try_from(0b100).is_err());
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     instruction,
     operation,
     prelude::{ImmShift, SetFlags, Shift},
+    ErrorKind,
     Parse,
     ParseError,
     ToOperation,
@@ -16,64 +17,64 @@ use crate::{
 instruction!(
     size u16; A5_2 contains
     // Logical left shift, might have to revisit the imm5 field later
-    Lsl : {
+    Lsl T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rm          : Register  : 3 -> 5 try_into,
         imm as u8   : u8        : 6 -> 10
     },
     // Logical right shift
-    Lsr : {
+    Lsr T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rm          : Register  : 3 -> 5 try_into,
         imm as u8   : u8        : 6 -> 10
     },
     // Arithmetic right shift
-    Asr : {
+    Asr T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rm          : Register  : 3 -> 5 try_into,
         imm5 as u8  : u8        : 6 -> 10
     },
     // Add register
-    Add : {
+    Add T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rn          : Register  : 3 -> 5 try_into,
         rm          : Register  : 6 -> 8 try_into
     },
     // Sub register
-    Sub : {
+    Sub T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rn          : Register  : 3 -> 5 try_into,
         rm          : Register  : 6 -> 8 try_into
     },
     // Add immediate
-    AddImmediate3 : {
+    AddImmediate3 T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rn          : Register  : 3 -> 5 try_into,
         imm as u8   : u8        : 6 -> 8
     },
     // Subtract immediate
-    SubImmediate3 : {
+    SubImmediate3 T1 : {
         rd          : Register  : 0 -> 2 try_into,
         rn          : Register  : 3 -> 5 try_into,
         imm as u8   : u8        : 6 -> 8
     },
     // Move immediate
-    Mov : {
+    Mov T1 : {
         rd          : Register  : 8 -> 10 try_into,
         imm as u8   : u8        : 0 -> 7
     },
     // Compare immediate
-    Cmp : {
+    Cmp T1 : {
         rn          : Register  : 8 -> 10 try_into,
         imm as u8   : u8        : 0 -> 7
     },
     // Add immediate 8 bit
-    AddImmediate8 : {
+    AddImmediate8 T2 : {
         rdn         : Register  : 8 -> 10 try_into,
         imm as u8   : u8        : 0 -> 7
     },
     // Sub immediate 8 bit
-    SubImmediate8 : {
+    SubImmediate8 T2 : {
         rdn         : Register  : 8 -> 10 try_into,
         imm as u8   : u8        : 0 -> 7
     }
@@ -88,7 +89,7 @@ impl Parse for A5_2 {
     {
         let word: u16 = match iter.peek::<1>() {
             Some(val) => Ok(val),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let opcode = word.mask::<9, 13>();
         match opcode >> 2 {
@@ -109,7 +110,7 @@ impl Parse for A5_2 {
             0b01101 => Ok(Self::Sub(Sub::parse(iter)?)),
             0b01110 => Ok(Self::AddImmediate3(AddImmediate3::parse(iter)?)),
             0b01111 => Ok(Self::SubImmediate3(SubImmediate3::parse(iter)?)),
-            _ => Err(ParseError::Invalid16Bit("A5_2")),
+            _ => Err(ErrorKind::Invalid16Bit.in_table("A5.2")),
         }
     }
 }
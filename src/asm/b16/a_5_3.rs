@@ -1,7 +1,16 @@
 use paste::paste;
 
 use super::Mask;
-use crate::{arch, arch::Register, instruction, operation, Parse, ParseError, ToOperation};
+use crate::{
+    arch,
+    arch::Register,
+    instruction,
+    operation,
+    ErrorKind,
+    Parse,
+    ParseError,
+    ToOperation,
+};
 macro_rules! instruction_5_3 {
     ($(
         $opcode:literal@$id:ident : {
@@ -14,7 +23,7 @@ macro_rules! instruction_5_3 {
         instruction!(
             size u16;  A5_3 contains
             $(
-                $id : {
+                $id T1 : {
                     $(
                         $field_id as u8: $type : $start -> $end $($expr)?
                     ),+
@@ -30,11 +39,11 @@ macro_rules! instruction_5_3 {
 
                 let first_byte = match iter.peek::<1>() as Option<u8> {
                     Some(b) => Ok(b),
-                    None => Err(ParseError::Invalid16Bit("A5_3")),
+                    None => Err(ErrorKind::Invalid16Bit.in_table("A5.3")),
                 }?;
                 let second_byte = match iter.peek::<2>() as Option<u8> {
                     Some(b) => Ok(b),
-                    None => Err(ParseError::Invalid16Bit("A5_3")),
+                    None => Err(ErrorKind::Invalid16Bit.in_table("A5.3")),
                 }?;
                 let op = ((first_byte&0b11)<<2)|(second_byte>>6);
                 match op{
@@ -42,7 +51,7 @@ macro_rules! instruction_5_3 {
                         $opcode => {Ok(Self::$id($id::parse(iter)?))}
 
                     )+
-                        _       => {Err(ParseError::Invalid16Bit("A5_3"))}
+                        _       => {Err(ErrorKind::Invalid16Bit.in_table("A5.3"))}
 
                 }
 
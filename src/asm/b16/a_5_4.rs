@@ -6,6 +6,7 @@ use crate::{
     combine,
     instruction,
     operation,
+    ErrorKind,
     Parse,
     ParseError,
     Stream,
@@ -13,25 +14,25 @@ use crate::{
 };
 instruction!(
     size u16;  A5_4 contains
-    Add : {
+    Add T2 : {
         rdn as u8 : u8      : 0->2,
         rm as u8 : Register : 3->6 try_into,
         dn as u8 : u8       : 7->7
     },
-    Cmp : {
+    Cmp T2 : {
         rn as u8 : u8       : 0->2,
         rm as u8 : Register : 3->6 try_into,
         n as u8  : u8       : 7->7
     },
-    Mov : {
+    Mov T1 : {
         rd as u8 : u8       : 0->2,
         rm as u8 : Register : 3->6 try_into,
         d as u8  :u8        : 7->7
     },
-    Bx  : {
+    Bx T1  : {
         rm as u8 : Register : 3->6 try_into
     },
-    Blx : {
+    Blx T1 : {
         rm as u8 : Register : 3->6 try_into
     }
 );
@@ -45,12 +46,12 @@ impl Parse for A5_4 {
     {
         let first_byte = match iter.peek::<1>() as Option<u8> {
             Some(b) => Ok(b),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let second_byte = match iter.peek::<2>() as Option<u8> {
             Some(b) => Ok(b),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let op = ((first_byte & 0b11) << 2) | (second_byte >> 6);
@@ -58,11 +59,9 @@ impl Parse for A5_4 {
             return Ok(Self::Add(Add::parse(iter)?));
         }
 
-        if op == 0b0100 {
-            return Err(ParseError::Unpredictable);
-        }
-
-        if op == 0b0101 || op & 0b1110 == 0b0110 {
+        // Comparing two low registers, 0b0100, is UNPREDICTABLE but is
+        // decoded as any other compare, see `Reason::of`.
+        if op & 0b1100 == 0b0100 {
             return Ok(Self::Cmp(Cmp::parse(iter)?));
         }
 
@@ -78,7 +77,7 @@ impl Parse for A5_4 {
             return Ok(Self::Blx(Blx::parse(iter)?));
         }
 
-        Err(ParseError::Invalid16Bit("A5_4"))
+        Err(ErrorKind::Invalid16Bit.in_table("A5.4"))
     }
 }
 
@@ -1,89 +1,98 @@
 use paste::paste;
 
 use super::Mask;
-use crate::{arch::Register, instruction, operation, Parse, ParseError, Stream, ToOperation};
+use crate::{
+    arch::Register,
+    instruction,
+    operation,
+    ErrorKind,
+    Parse,
+    ParseError,
+    Stream,
+    ToOperation,
+};
 
 instruction!(
     size u16;  A5_5 contains
-    Str : {
+    Str T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Strh : {
+    Strh T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Strb : {
+    Strb T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Ldrsb : {
+    Ldrsb T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Ldr : {
+    Ldr T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Ldrh : {
+    Ldrh T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Ldrb : {
+    Ldrb T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    Ldrsh : {
+    Ldrsh T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         rm as u8 : Register : 6->8 try_into
     },
-    StrI : {
+    StrI T1 : {
         rt as u8 : Register : 0 -> 2 try_into,
         rn as u8 : Register : 3 -> 5 try_into,
         imm5 as u8 : u8         : 6 -> 10
         // imm8 as u8 : u8     : 0->7 ,
         // rt as u8 : Register : 8->10 try_into
     },
-    LdrI : {
+    LdrI T1 : {
         rt as u8 : Register : 0 -> 2 try_into,
         rn as u8 : Register : 3 -> 5 try_into,
         imm5 as u8 : u8         : 6 -> 10
     },
-    StrbI : {
+    StrbI T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         imm5 as u8 : u8     : 6->10
     },
-    LdrbI : {
+    LdrbI T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         imm5 as u8 : u8     : 6->10
     },
-    StrhI : {
+    StrhI T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         imm5 as u8 : u8     : 6->10
     },
-    LdrhI : {
+    LdrhI T1 : {
         rt as u8 : Register : 0->2 try_into,
         rn as u8 : Register : 3->5 try_into,
         imm5 as u8 : u8     : 6->10
     },
     // Relative
-    StrRI : {
+    StrRI T2 : {
         imm8 as u8 : u8     : 0->7 ,
         rt as u8 : Register : 8->10 try_into
     },
     // Relative
-    LdrRI : {
+    LdrRI T2 : {
         imm8 as u8 : u8     : 0->7 ,
         rt as u8 : Register : 8->10 try_into
     }
@@ -99,7 +108,7 @@ impl Parse for A5_5 {
     {
         let word = match iter.peek::<1>() as Option<u16> {
             Some(u) => Ok(u),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let op1 = word.mask::<12, 15>();
@@ -147,7 +156,7 @@ impl Parse for A5_5 {
                 Self::LdrRI(LdrRI::parse(iter)?)
             });
         }
-        Err(ParseError::Invalid16Bit("A5_5"))
+        Err(ErrorKind::Invalid16Bit.in_table("A5.5"))
     }
 }
 
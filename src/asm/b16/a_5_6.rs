@@ -6,6 +6,7 @@ use crate::{
     combine,
     instruction,
     operation,
+    ErrorKind,
     Parse,
     ParseError,
     ToOperation,
@@ -13,64 +14,64 @@ use crate::{
 
 instruction!(
     size u16;  A5_6 contains
-    Cps : {
+    Cps T1 : {
         f as u8 :u8    : 0->0,
         i as u8 :u8    : 1->1,
         im as u8 :u8   : 4->4
     },
-    AddImmediateToSP : {
+    AddImmediateToSP T2 : {
         imm7 as u8 :u8 : 0->6
     },
-    SubImmediateFromSp : {
+    SubImmediateFromSp T1 : {
         imm7 as u8 :u8 : 0->6
     },
-    Cbz  : {
+    Cbz T1  : {
         rn as u8 : Register : 0 ->  2   try_into,
         imm5 as u8 : u8     : 3 ->  7,
         op   as u8 : u8     : 11 -> 11
     },
-    Sxth : {
+    Sxth T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Sxtb : {
+    Sxtb T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Uxth : {
+    Uxth T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Uxtb : {
+    Uxtb T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Cbnz  : {
+    Cbnz T1  : {
         rn as u8 : Register : 0 ->  2   try_into,
         imm5 as u8 : u8     : 3 ->  7,
         op   as u8 : u8     : 11 -> 11
     },
-    Push : {
+    Push T1 : {
         register_list :RegisterList     : 0->7 try_into,
         m as u8:u8                      : 8->8
     },
-    Rev : {
+    Rev T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Rev16 : {
+    Rev16 T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Revsh : {
+    Revsh T1 : {
         rd as u8 : Register : 0 ->  2   try_into,
         rm as u8 : Register : 3 ->  5   try_into
     },
-    Pop   : {
+    Pop T1   : {
         register_list : u16 : 0->7,
         p as u16: u16                  : 8->8
     },
-    Bkpt  : {
+    Bkpt T1  : {
         imm8 as u8 : u8             : 0->7
     },
     -> A5_7
@@ -91,7 +92,7 @@ impl Parse for A5_6 {
     {
         let opcode = match iter.peek::<1>() as Option<u16> {
             Some(u) => Ok(u.mask::<5, 11>()),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         if opcode == 0b0110011 {
             p!(Cps from iter);
@@ -145,10 +146,12 @@ impl Parse for A5_6 {
             p!(Bkpt from iter);
         }
         if opcode & 0b1111000 == 0b1111000 {
-            return Ok(Self::SubtableA5_7(A5_7::parse(iter)?));
+            return Ok(Self::SubtableA5_7(
+                A5_7::parse(iter).map_err(|e| e.in_table("A5.7"))?,
+            ));
         }
 
-        Err(ParseError::Invalid16Bit("A5_6"))
+        Err(ErrorKind::Invalid16Bit.in_table("A5.6"))
     }
 }
 
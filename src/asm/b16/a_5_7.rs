@@ -7,15 +7,13 @@ use crate::{arch::Condition, instruction, operation, Parse, ParseError, ToOperat
 
 instruction!(
     size u16; A5_7 contains
-    It : {
+    It T1 : {
         mask        as u8    : u8    : 0 -> 3 ,
         firstcond    as u8   : Condition    : 4 -> 7 try_into
     },
-    Nop : {},
-    Yield : {},
-    Wfe : {},
-    Wfi : {},
-    Sev : {}
+    Hint T1 : {
+        hint        as u8    : u8    : 4 -> 7
+    }
 );
 
 impl Parse for A5_7 {
@@ -27,19 +25,12 @@ impl Parse for A5_7 {
     {
         let word: u16 = iter.next()?;
         let opb = word.mask::<0, 3>();
-        let opa = word.mask::<4, 7>();
 
         if opb != 0 {
             return Ok(Self::It(It::parse(iter)?));
         }
-        Ok(match opa {
-            0 => Self::Nop(Nop::parse(iter)?),
-            1 => Self::Yield(Yield::parse(iter)?),
-            2 => Self::Wfe(Wfe::parse(iter)?),
-            3 => Self::Wfi(Wfi::parse(iter)?),
-            4 => Self::Sev(Sev::parse(iter)?),
-            _ => return Err(ParseError::Invalid16Bit("A5_7")),
-        })
+        // All other values of opA are hints, opA is the hint number.
+        Ok(Self::Hint(Hint::parse(iter)?))
     }
 }
 
@@ -50,11 +41,7 @@ impl ToOperation for A5_7 {
                 .set_conds((it.firstcond, it.mask).into())
                 .complete()
                 .into(),
-            Self::Nop(_) => operation::Nop::builder().complete().into(),
-            Self::Yield(_) => operation::Yield::builder().complete().into(),
-            Self::Wfe(_) => operation::Wfe::builder().complete().into(),
-            Self::Wfi(_) => operation::Wfi::builder().complete().into(),
-            Self::Sev(_) => operation::Sev::builder().complete().into(),
+            Self::Hint(el) => crate::arch::Hint::from(el.hint).into(),
         }
     }
 }
@@ -73,7 +60,7 @@ mod test {
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
         let condition: Condition = Condition::try_from(0b0011u8).unwrap();
         let target: Operation = operation::It::builder()
-            .set_conds(ITCondition::try_from((condition, 0b0011)).unwrap())
+            .set_conds(ITCondition::from((condition, 0b0011)))
             .complete()
             .into();
         assert_eq!(instr, target)
@@ -123,4 +110,16 @@ mod test {
         let target: Operation = operation::Sev::builder().complete().into();
         assert_eq!(instr, target)
     }
+
+    #[test]
+    fn test_parse_unallocated_hint() {
+        let bin = [0b10111111u8, 0b01010000u8];
+        let mut stream = PeekableBuffer::from(bin.into_iter().rev());
+        let instr = Operation::parse(&mut stream).expect("Parser broken").1;
+        let target: Operation = operation::UnallocatedHint::builder()
+            .set_hint(0b0101)
+            .complete()
+            .into();
+        assert_eq!(instr, target)
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     arch::{wrapper_types::Imm8, Condition, Imm9, SignExtend},
     instruction,
     operation,
+    ErrorKind,
     Parse,
     ParseError,
     Stream,
@@ -13,11 +14,15 @@ use crate::{
 
 instruction!(
     size u16;  A5_8 contains
-    B : {
+    B T1 : {
         imm8 as u8 : Imm8 : 0->7 try_into,
         cond as u8 : Condition : 8->11 try_into
     },
-    Svc : {
+    Svc T1 : {
+        imm8 as u8 :u8 : 0->7
+    },
+    // Permanently undefined
+    Udf T1 : {
         imm8 as u8 :u8 : 0->7
     }
 );
@@ -31,13 +36,13 @@ impl Parse for A5_8 {
     {
         let opcode = match iter.peek::<1>() as Option<u8> {
             Some(u) => Ok(u & 0b1111),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         if opcode == 0b1111 {
             return Ok(Self::Svc(Svc::parse(iter)?));
         }
         if opcode == 0b1110 {
-            return Err(ParseError::Unpredictable);
+            return Ok(Self::Udf(Udf::parse(iter)?));
         }
         Ok(Self::B(B::parse(iter)?))
     }
@@ -58,6 +63,10 @@ impl ToOperation for A5_8 {
                     .into()
             }
             Self::Svc(el) => operation::Svc::builder().set_imm(el.imm8).complete().into(),
+            Self::Udf(el) => operation::Udf::builder()
+                .set_imm(el.imm8 as u32)
+                .complete()
+                .into(),
         }
     }
 }
@@ -81,4 +90,13 @@ mod test {
             .into();
         assert_eq!(instr, target)
     }
+
+    #[test]
+    fn test_parse_udf() {
+        let bin = [0b11011110u8, 0b00010010u8];
+        let mut stream = PeekableBuffer::from(bin.into_iter().rev());
+        let instr = Operation::parse(&mut stream).expect("Parser broken").1;
+        let target: Operation = operation::Udf::builder().set_imm(0x12).complete().into();
+        assert_eq!(instr, target)
+    }
 }
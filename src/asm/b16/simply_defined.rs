@@ -5,6 +5,8 @@ use crate::{
     arch::{Condition, Imm12, Register, RegisterList, SignExtend},
     instruction,
     operation,
+    Encoded,
+    EncodingId,
     Parse,
     ParseError,
     ToOperation,
@@ -38,6 +40,20 @@ instruction!(
     }
 );
 
+/// The simply defined instructions are decoded directly from table A5.1.
+macro_rules! encoded {
+    ($($id:ident $enc:literal),*) => {
+        $(
+            impl Encoded for $id {
+                fn encoding(&self) -> EncodingId {
+                    EncodingId::new("A5.1", stringify!($id), $enc)
+                }
+            }
+        )*
+    };
+}
+encoded!(Ldr 1, Adr 1, Add 1, Stm 1, Ldm 1, B 2);
+
 impl ToOperation for Ldr {
     fn encoding_specific_operations(self) -> crate::operation::Operation {
         operation::LdrLiteral::builder()
@@ -21,110 +21,126 @@ pub mod a5_30;
 
 use crate::{
     asm::{b32::a5_30::A5_30, Mask},
+    EncodingId,
+    ErrorKind,
     Parse,
-    ParseError,
     ToOperation,
 };
 
 /// A 32-bit wide instruction
 pub enum B32 {}
 
-impl Parse for B32 {
-    type Target = (usize, crate::operation::Operation);
-
-    fn parse<T: crate::Stream>(iter: &mut T) -> Result<Self::Target, ParseError>
-    where
-        Self: Sized,
-    {
-        let ret = match Self::parse_internal(iter) {
-            Ok(e) => e,
-            Err(e) => {
-                return Err(e);
-            }
-        };
-        let _: u32 = match iter.consume::<1>() {
-            Some(val) => val[0],
-            None => return Err(ParseError::IncompleteProgram),
-        };
-
-        Ok((32, ret))
-    }
-}
-
 /// A 32-bit wide instruction
 impl B32 {
-    fn parse_internal<T: crate::Stream>(
+    /// Decodes the instruction without consuming it from the stream.
+    pub(crate) fn parse_internal<T: crate::Stream>(
         iter: &mut T,
-    ) -> Result<crate::operation::Operation, crate::ParseError> {
+    ) -> Result<(EncodingId, crate::operation::Operation), crate::ParseError> {
         let word: u32 = match iter.peek::<1>() {
             Some(value) => value,
-            None => return Err(ParseError::IncompleteProgram),
+            None => return Err(ErrorKind::IncompleteProgram.in_table("A5.9")),
         };
         let op1 = word.mask::<{ 16 + 11 }, { 16 + 12 }>();
         let op2 = word.mask::<{ 16 + 4 }, { 16 + 10 }>();
         let op = word.mask::<15, 15>();
 
         if op1 > 3 {
-            return Err(ParseError::InternalError("Masking is broken op1 > 3"));
+            return Err(ErrorKind::InternalError("Masking is broken op1 > 3").in_table("A5.9"));
         }
         if op > 1 {
-            return Err(ParseError::InternalError("Masking is broken op > 1"));
+            return Err(ErrorKind::InternalError("Masking is broken op > 1").in_table("A5.9"));
         }
 
         if op1 == 1 {
             if ((op2 >> 2) & 0b11001) == 0b00000 {
-                return Ok(a5_16::A5_16::parse(iter)?.encoding_specific_operations());
+                return Ok(a5_16::A5_16::parse(iter)
+                    .map_err(|e| e.in_table("A5.16"))?
+                    .decoded());
             }
             if ((op2 >> 2) & 0b11001) == 0b00001 {
-                return Ok(a5_17::A5_17::parse(iter)?.encoding_specific_operations());
+                return Ok(a5_17::A5_17::parse(iter)
+                    .map_err(|e| e.in_table("A5.17"))?
+                    .decoded());
             }
             if (op2 >> 5) == 1 {
-                return Ok(a5_22::A5_22::parse(iter)?.encoding_specific_operations());
+                return Ok(a5_22::A5_22::parse(iter)
+                    .map_err(|e| e.in_table("A5.22"))?
+                    .decoded());
             }
             if (op2 >> 6) == 1 {
-                return Ok(a5_30::A5_30::parse(iter)?.encoding_specific_operations());
+                return Ok(a5_30::A5_30::parse(iter)
+                    .map_err(|e| e.in_table("A5.30"))?
+                    .decoded());
             }
-            return Err(ParseError::Invalid32Bit("Invalid op2"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.9"));
         }
         if op1 == 2 {
             if op == 0 {
                 if (op2 & 0b0100000) == 0 {
-                    return Ok(a5_10::A5_10::parse(iter)?.encoding_specific_operations());
+                    return Ok(a5_10::A5_10::parse(iter)
+                        .map_err(|e| e.in_table("A5.10"))?
+                        .decoded());
                 }
-                return Ok(a5_12::A5_12::parse(iter)?.encoding_specific_operations());
+                return Ok(a5_12::A5_12::parse(iter)
+                    .map_err(|e| e.in_table("A5.12"))?
+                    .decoded());
             }
-            return Ok(a5_13::A5_13::parse(iter)?.encoding_specific_operations());
+            return Ok(a5_13::A5_13::parse(iter)
+                .map_err(|e| e.in_table("A5.13"))?
+                .decoded());
         }
 
         if (op2 & 0b1110001) == 0b0000000 {
-            return Ok(a5_21::A5_21::parse(iter)?.encoding_specific_operations());
+            return Ok(a5_21::A5_21::parse(iter)
+                .map_err(|e| e.in_table("A5.21"))?
+                .decoded());
         }
 
         match op2 & 0b1100111 {
-            0b0000001 => return Ok(a5_20::A5_20::parse(iter)?.encoding_specific_operations()),
-            0b0000011 => return Ok(a5_19::A5_19::parse(iter)?.encoding_specific_operations()),
-            0b0000101 => return Ok(a5_18::A5_18::parse(iter)?.encoding_specific_operations()),
-            0b0000111 => return Err(ParseError::Undefined),
+            0b0000001 => {
+                return Ok(a5_20::A5_20::parse(iter)
+                    .map_err(|e| e.in_table("A5.20"))?
+                    .decoded())
+            }
+            0b0000011 => {
+                return Ok(a5_19::A5_19::parse(iter)
+                    .map_err(|e| e.in_table("A5.19"))?
+                    .decoded())
+            }
+            0b0000101 => {
+                return Ok(a5_18::A5_18::parse(iter)
+                    .map_err(|e| e.in_table("A5.18"))?
+                    .decoded())
+            }
+            0b0000111 => return Err(ErrorKind::Undefined.in_table("A5.9")),
             _ => {}
         }
 
         if op2 >> 4 == 2 {
-            return Ok(a5_24::A5_24::parse(iter)?.encoding_specific_operations());
+            return Ok(a5_24::A5_24::parse(iter)
+                .map_err(|e| e.in_table("A5.24"))?
+                .decoded());
         }
 
         if op2 >> 3 == 0b0110 {
-            return Ok(a5_28::A5_28::parse(iter)?.encoding_specific_operations());
+            return Ok(a5_28::A5_28::parse(iter)
+                .map_err(|e| e.in_table("A5.28"))?
+                .decoded());
         }
 
         if op2 >> 3 == 0b0111 {
-            return Ok(a5_29::A5_29::parse(iter)?.encoding_specific_operations());
+            return Ok(a5_29::A5_29::parse(iter)
+                .map_err(|e| e.in_table("A5.29"))?
+                .decoded());
         }
 
         if op2 >> 6 == 1 {
             // Co processor things
-            return Ok(A5_30::parse(iter)?.encoding_specific_operations());
+            return Ok(A5_30::parse(iter)
+                .map_err(|e| e.in_table("A5.30"))?
+                .decoded());
         }
 
-        Err(ParseError::Invalid32Bit(""))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.9"))
     }
 }
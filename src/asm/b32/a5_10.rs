@@ -5,13 +5,14 @@ use crate::{
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_10 contains
-    And : {
+    And T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -19,13 +20,13 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Tst : {
+    Tst T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8    : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Bic : {
+    Bic T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -33,7 +34,7 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Orr : {
+    Orr T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -41,14 +42,14 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Mov : {
+    Mov T2 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8    : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         s as u8     : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Orn : {
+    Orn T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -56,14 +57,14 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Mvn : {
+    Mvn T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Eor : {
+    Eor T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -71,13 +72,13 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Teq : {
+    Teq T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Add : {
+    Add T3 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -85,13 +86,13 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Cmn : {
+    Cmn T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Adc : {
+    Adc T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -99,7 +100,7 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Sbc : {
+    Sbc T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -107,7 +108,7 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Sub : {
+    Sub T3 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -115,13 +116,13 @@ instruction!(
         s as u8    : bool       : 20 -> 20 local_try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Cmp : {
+    Cmp T2 : {
         imm8 as u16 : u16        : 0 -> 7,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Rsb : {
+    Rsb T2 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
@@ -139,7 +140,7 @@ macro_rules! fields {
     ) => {
         let word : $width = match $iter.peek::<1>(){
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram)
+            None => Err(ErrorKind::IncompleteProgram)
         }?;
         $(
             let $id : $type = (word.mask::<$start,$end>())$(.$map()?)?;
@@ -202,7 +203,7 @@ impl Parse for A5_10 {
             0b1010 => Ok(Self::Adc(Adc::parse(iter)?)),
             0b1011 => Ok(Self::Sbc(Sbc::parse(iter)?)),
             0b1110 => Ok(Self::Rsb(Rsb::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_10")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.10")),
         }
     }
 }
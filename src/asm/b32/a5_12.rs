@@ -1,52 +1,58 @@
 use paste::paste;
 
 use crate::{
-    asm::{LocalTryInto, Mask},
+    asm::Mask,
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_12 contains
-    Add : {
+    Add T4 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Adr : {
+    AdrT2 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
-        i as u16    : u16        : 26 -> 26,
-        add as u8   : bool       : 21 -> 21 local_try_into
+        i as u16    : u16        : 26 -> 26
     },
-    Mov : {
+    AdrT3 : {
+        imm8 as u16 : u16        : 0 -> 7,
+        rd as u8   : Register   : 8 -> 11 try_into,
+        imm3 as u16 : u16        : 12 -> 14,
+        i as u16    : u16        : 26 -> 26
+    },
+    Mov T3 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8    : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         imm4 as u8  : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Sub : {
+    Sub T4 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         rn as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Movt : {
+    Movt T1 : {
         imm8 as u16 : u16        : 0 -> 7,
         rd as u8   : Register   : 8 -> 11 try_into,
         imm3 as u16 : u16        : 12 -> 14,
         imm4 as u8   : Register   : 16 -> 19 try_into,
         i as u16    : u16        : 26 -> 26
     },
-    Ssat : {
+    Ssat T1 : {
         sat_imm as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
@@ -54,32 +60,32 @@ instruction!(
         rn      as u8 : Register    : 16 -> 19 try_into,
         sh      as u8 : u8          : 21 -> 21
     },
-    Ssat16 : {
+    Ssat16 T1 : {
         sat_imm as u8 : u8          : 0 -> 4,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Sbfx : {
+    Sbfx T1 : {
         widthm1 as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
         imm3    as u8 : u8          : 12 -> 14,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Bfi : {
+    Bfi T1 : {
         msb     as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
         imm3    as u8 : u8          : 12 -> 14,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Bfc : {
+    Bfc T1 : {
         msb     as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
         imm3    as u8 : u8          : 12 -> 14
     },
-    Usat : {
+    Usat T1 : {
         sat_imm as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
@@ -87,12 +93,12 @@ instruction!(
         rn      as u8 : Register    : 16 -> 19 try_into,
         sh      as u8 : u8          : 21 -> 21
     },
-    Usat16 : {
+    Usat16 T1 : {
         sat_imm as u8 : u8          : 0 -> 4,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Ubfx : {
+    Ubfx T1 : {
         widthm1 as u8 : u8          : 0 -> 4,
         imm2    as u8 : u8          : 6 -> 7,
         rd      as u8 : Register    : 8 -> 11 try_into,
@@ -116,22 +122,22 @@ impl Parse for A5_12 {
         // NOTE! Only read half the word here to avoid adding to the mask
         let word: u16 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let rn = word.mask::<0, 3>();
         let op = word.mask::<4, 8>();
 
         let word: u16 = match iter.peek::<2>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let second_halfword_req = word.mask::<6, 7>() == 0 && word.mask::<12, 14>() == 0;
 
         match (op, rn, second_halfword_req) {
-            (0, 0b1111, _) => Ok(Self::Adr(Adr::parse(iter)?)),
+            (0, 0b1111, _) => Ok(Self::AdrT3(AdrT3::parse(iter)?)),
             (0, _, _) => Ok(Self::Add(Add::parse(iter)?)),
             (0b00100, _, _) => Ok(Self::Mov(Mov::parse(iter)?)),
-            (0b01010, 0b1111, _) => Ok(Self::Adr(Adr::parse(iter)?)),
+            (0b01010, 0b1111, _) => Ok(Self::AdrT2(AdrT2::parse(iter)?)),
             (0b01010, _, _) => Ok(Self::Sub(Sub::parse(iter)?)),
             (0b01100, _, _) => Ok(Self::Movt(Movt::parse(iter)?)),
             (0b10000, _, _) | (0b10010, _, false) => Ok(Self::Ssat(Ssat::parse(iter)?)),
@@ -142,7 +148,7 @@ impl Parse for A5_12 {
             (0b11000, _, _) | (0b11010, _, false) => Ok(Self::Usat(Usat::parse(iter)?)),
             (0b11010, _, true) => Ok(Self::Usat16(Usat16::parse(iter)?)),
             (0b11100, _, _) => Ok(Self::Ubfx(Ubfx::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_12")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.12")),
         }
     }
 }
@@ -177,11 +183,20 @@ impl ToOperation for A5_12 {
                     .complete()
                     .into()
             }
-            Self::Adr(el) => {
+            Self::AdrT2(el) => {
+                let imm: Imm12 = combine_wrapper!(el : {i:imm3,3:imm8,8,u32});
+                operation::AdrBuilder::new()
+                    .set_rd(el.rd)
+                    .set_add(false)
+                    .set_imm(imm.into())
+                    .complete()
+                    .into()
+            }
+            Self::AdrT3(el) => {
                 let imm: Imm12 = combine_wrapper!(el : {i:imm3,3:imm8,8,u32});
                 operation::AdrBuilder::new()
                     .set_rd(el.rd)
-                    .set_add(!el.add)
+                    .set_add(true)
                     .set_imm(imm.into())
                     .complete()
                     .into()
@@ -377,7 +392,7 @@ mod test {
         let mut stream = PeekableBuffer::from(bin.into_iter());
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
         let target: Operation = operation::MovImmediate::builder()
-            .set_imm(0b0100_1_001_10001001u32)
+            .set_imm(0b0100_1001_1000_1001_u32)
             .set_rd(Register::R1)
             .set_s(Some(false.into()))
             .set_carry(None)
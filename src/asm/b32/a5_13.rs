@@ -9,6 +9,7 @@ use crate::{
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
@@ -24,19 +25,19 @@ instruction!(
         cond    as u8   : Condition : 22 -> 25 try_into,
         s       as u8   : bool      : 26 -> 26 local_try_into
     },
-    Msr : {
+    Msr T1 : {
         sysm    as u8   : u8        : 0 -> 7,
-        mask    as u8   : Imm2      : 10 -> 11 try_into,
+        mask    as u8   : ApsrMask  : 10 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
     -> A5_14,
     -> A5_15,
-    Mrs : {
+    Mrs T1 : {
         sysm    as u8   : u8        : 0 -> 7,
         rd      as u8   : Register  : 8 -> 11 try_into
     },
     // Permanently undefined
-    Udf : {
+    Udf T2 : {
         imm12   as u16  : u16       : 0 -> 11,
         imm4    as u16  : u16       : 0 -> 3
     },
@@ -47,7 +48,7 @@ instruction!(
         imm10           : u32       : 16 -> 25,
         s       as u8   : bool      : 26 -> 26 local_try_into
     },
-    Bl : {
+    Bl T1 : {
         imm11           : u32       : 0 -> 10,
         j2      as u8   : bool      : 11 -> 11 local_try_into,
         j1      as u8   : bool      : 13 -> 13 local_try_into,
@@ -65,7 +66,7 @@ impl Parse for A5_13 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op1 = word.mask::<12, 14>();
         let op = word.mask::<20, 26>();
@@ -81,10 +82,14 @@ impl Parse for A5_13 {
                 return Ok(Self::Mrs(Mrs::parse(iter)?));
             };
             if op == 0b0111010 {
-                return Ok(Self::SubtableA5_14(A5_14::parse(iter)?));
+                return Ok(Self::SubtableA5_14(
+                    A5_14::parse(iter).map_err(|e| e.in_table("A5.14"))?,
+                ));
             }
             if op == 0b0111011 {
-                return Ok(Self::SubtableA5_15(A5_15::parse(iter)?));
+                return Ok(Self::SubtableA5_15(
+                    A5_15::parse(iter).map_err(|e| e.in_table("A5.15"))?,
+                ));
             }
         }
         if op1 == 0b10 {
@@ -92,15 +97,27 @@ impl Parse for A5_13 {
             return Ok(Self::Udf(Udf::parse(iter)?));
         }
         if op1 & 0b101 == 0b001 {
-            return Ok(Self::BT4(BT4::parse(iter)?));
+            let el = BT4::parse(iter)?;
+            branch_offset(el.s, el.j1, el.j2, el.imm10, el.imm11)?;
+            return Ok(Self::BT4(el));
         }
         if op1 & 0b101 == 0b101 {
-            return Ok(Self::Bl(Bl::parse(iter)?));
+            let el = Bl::parse(iter)?;
+            branch_offset(el.s, el.j1, el.j2, el.imm10, el.imm11)?;
+            return Ok(Self::Bl(el));
         }
-        Err(ParseError::Invalid32Bit("A5_13"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.13"))
     }
 }
 
+/// Computes the `S:I1:I2:imm10:imm11:0` offset shared by `B` T4 and `BL`.
+fn branch_offset(s: bool, j1: bool, j2: bool, imm10: u32, imm11: u32) -> Result<Imm25, ParseError> {
+    let (i1, i2) = (!(j1 ^ s), !(j2 ^ s));
+    combine!(s:i1,1:i2,1:imm10,10:imm11,11:0,1,u32)
+        .try_into()
+        .map_err(|e| ParseError::from(e).in_table("A5.13"))
+}
+
 impl ToOperation for A5_13 {
     fn encoding_specific_operations(self) -> crate::operation::Operation {
         match self {
@@ -117,12 +134,8 @@ impl ToOperation for A5_13 {
                     .into()
             }
             Self::BT4(el) => {
-                let (s, j2, j1, imm10, imm11) = (el.s, el.j2, el.j1, el.imm10, el.imm11);
-                let i1 = !(j1 ^ s);
-                let i2 = !(j2 ^ s);
-                let mut imm: Imm25 = combine!(s:i1,1:i2,1:imm10,10:imm11,11:0,1,u32)
-                    .try_into()
-                    .unwrap();
+                let mut imm = branch_offset(el.s, el.j1, el.j2, el.imm10, el.imm11)
+                    .expect("The offset is checked while parsing");
 
                 operation::BBuilder::new()
                     .set_condition(Condition::None)
@@ -133,26 +146,17 @@ impl ToOperation for A5_13 {
             Self::Msr(el) => operation::Msr::builder()
                 .set_rn(el.rn)
                 .set_mask(el.mask)
-                .set_sysm(el.sysm)
+                .set_sysm(el.sysm.into())
                 .complete()
                 .into(),
             Self::Mrs(el) => operation::Mrs::builder()
                 .set_rd(el.rd)
-                .set_sysm(el.sysm)
+                .set_sysm(el.sysm.into())
                 .complete()
                 .into(),
             Self::Bl(el) => {
-                let (s, j2, j1, imm10, imm11) = (el.s, el.j2, el.j1, el.imm10, el.imm11);
-                let (i1, i2) = (!(j1 ^ s), !(j2 ^ s));
-                let num = combine!(s:i1,1:i2,1:imm10,10:imm11,11:0,1,u32);
-
-                let mut imm: Imm25 = num
-                    .try_into()
-                    .map_err(|e| {
-                        println!("tried to fit {num} into Imm25");
-                        e
-                    })
-                    .unwrap();
+                let mut imm = branch_offset(el.s, el.j1, el.j2, el.imm10, el.imm11)
+                    .expect("The offset is checked while parsing");
 
                 operation::BlBuilder::new()
                     .set_imm(imm.sign_extend())
@@ -173,7 +177,7 @@ impl ToOperation for A5_13 {
 #[cfg(test)]
 mod test {
 
-    use crate::prelude::*;
+    use crate::{prelude::*, Policy, Reason};
 
     #[test]
     fn test_parse_b_t3() {
@@ -229,8 +233,8 @@ mod test {
 
         let target: Operation = operation::Msr::builder()
             .set_rn(Register::R2)
-            .set_mask(Imm2::try_from(0b10u8).expect("Malformed test invalid mask"))
-            .set_sysm(0b00000011u8)
+            .set_mask(ApsrMask::try_from(0b10u8).expect("Malformed test invalid mask"))
+            .set_sysm(SpecialRegister::Xpsr)
             .complete()
             .into();
         assert_eq!(instr, target)
@@ -240,19 +244,45 @@ mod test {
     fn test_parse_mrs() {
         let mut bin = vec![];
         bin.extend([0b11110011u8, 0b11101111u8].into_iter().rev());
-        bin.extend([0b10000010u8, 0b10000001u8].into_iter().rev());
+        bin.extend([0b10000010u8, 0b00001001u8].into_iter().rev());
 
         let mut stream = PeekableBuffer::from(bin.into_iter());
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
 
         let target: Operation = operation::Mrs::builder()
             .set_rd(Register::R2)
-            .set_sysm(0b10000001u8)
+            .set_sysm(SpecialRegister::Psp)
             .complete()
             .into();
         assert_eq!(instr, target)
     }
 
+    #[test]
+    fn test_parse_reserved_special_register() {
+        let mut bin = vec![];
+        bin.extend([0b11110011u8, 0b11101111u8].into_iter().rev());
+        bin.extend([0b10000010u8, 0b10000001u8].into_iter().rev());
+
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        let err = Operation::decode(&mut stream, Policy::Strict).expect_err("Reserved SYSm accepted");
+        assert!(matches!(
+            err.kind,
+            crate::ErrorKind::Unpredictable(Reason::SpecialRegister(0x81))
+        ));
+
+        // msr CONTROL with the GE flags masked.
+        let mut bin = vec![];
+        bin.extend([0b11110011u8, 0b10000010u8].into_iter().rev());
+        bin.extend([0b10000100u8, 0b00010100u8].into_iter().rev());
+
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        let err = Operation::decode(&mut stream, Policy::Strict).expect_err("Masked CONTROL accepted");
+        assert!(matches!(
+            err.kind,
+            crate::ErrorKind::Unpredictable(Reason::ApsrMask(_))
+        ));
+    }
+
     #[test]
     fn test_parse_bl() {
         let mut bin = vec![];
@@ -1,23 +1,13 @@
 //! Defines marker instructions
 //!
 //! These have one or no fields but might have side-effects
-use crate::{asm::Mask, prelude::*, ParseError, ToOperation};
+use crate::{asm::Mask, prelude::*, Encoded, EncodingId, ErrorKind, ParseError, ToOperation};
 
 /// Defines some maker instructions
 #[derive(Debug)]
 pub enum A5_14 {
-    /// No operation
-    Nop,
-    /// Yield
-    Yield,
-    /// Wait for event
-    Wfe,
-    /// Wait for interrupt
-    Wfi,
-    /// Send event
-    Sev,
-    /// Debug
-    Dbg(u8),
+    /// A hint, the unallocated hints are kept as is
+    Hint(Hint),
 }
 
 impl Parse for A5_14 {
@@ -29,46 +19,34 @@ impl Parse for A5_14 {
     {
         let word: u16 = match iter.peek::<2>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op1 = word.mask::<8, 10>();
-        let op2 = word.mask::<0, 8>();
+        let op2 = word.mask::<0, 7>();
 
         if op1 != 0 {
-            return Err(ParseError::Undefined);
+            return Err(ErrorKind::Undefined.into());
         }
-        match op2 {
-            0 => return Ok(Self::Nop),
-            1 => return Ok(Self::Yield),
-            2 => return Ok(Self::Wfe),
-            3 => return Ok(Self::Wfi),
-            4 => return Ok(Self::Sev),
-            _ => {}
-        }
-        if op2 >> 4 == 0b1111 {
-            let option: u8 = (op2 & 0b1111) as u8;
-            return Ok(Self::Dbg(option));
+        Ok(Self::Hint(Hint::from(op2 as u8)))
+    }
+}
+
+impl Encoded for A5_14 {
+    fn encoding(&self) -> EncodingId {
+        match self {
+            Self::Hint(Hint::Dbg(_)) => EncodingId::new("A5.14", "Hint", 1),
+            Self::Hint(_) => EncodingId::new("A5.14", "Hint", 2),
         }
-        Err(ParseError::Invalid32Bit("A5_14"))
     }
 }
 
 impl ToOperation for A5_14 {
     fn encoding_specific_operations(self) -> crate::operation::Operation {
         match self {
-            Self::Nop => operation::NopBuilder::new().complete().into(),
-            Self::Yield => operation::YieldBuilder::new().complete().into(),
-            Self::Wfe => operation::WfeBuilder::new().complete().into(),
-            Self::Wfi => operation::WfiBuilder::new().complete().into(),
-            Self::Sev => operation::SevBuilder::new().complete().into(),
-            Self::Dbg(el) => operation::DbgBuilder::new()
-                .set_option(el)
-                .complete()
-                .into(),
+            Self::Hint(hint) => hint.into(),
         }
     }
 }
-
 #[cfg(test)]
 mod test {
 
@@ -154,4 +132,20 @@ mod test {
             .into();
         assert_eq!(instr, target)
     }
+
+    #[test]
+    fn test_parse_unallocated_hint() {
+        let mut bin = vec![];
+        bin.extend([0b11110011u8, 0b10101111u8].into_iter().rev());
+        bin.extend([0b10000000u8, 0b00010100u8].into_iter().rev());
+
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        let instr = Operation::parse(&mut stream).expect("Parser broken").1;
+
+        let target: Operation = operation::UnallocatedHint::builder()
+            .set_hint(0b00010100)
+            .complete()
+            .into();
+        assert_eq!(instr, target)
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{asm::Mask, prelude::*, ParseError, ToOperation};
+use crate::{asm::Mask, prelude::*, Encoded, EncodingId, ErrorKind, ParseError, ToOperation};
 
 /// Defines some maker instructions
 #[derive(Debug)]
@@ -6,11 +6,11 @@ pub enum A5_15 {
     /// Clear exclusive
     Clrex,
     /// Data synchronization barrier
-    Dsb(u8),
+    Dsb(BarrierOption),
     /// Data memory barrier
-    Dmb(u8),
+    Dmb(BarrierOption),
     /// Instruction synchronization barrier
-    Isb(u8),
+    Isb(BarrierOption),
 }
 
 impl Parse for A5_15 {
@@ -22,20 +22,32 @@ impl Parse for A5_15 {
     {
         let word: u16 = match iter.peek::<2>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op = word.mask::<4, 7>();
-        let inner_op = word.mask::<0, 3>() as u8;
+        let option = BarrierOption::try_from(word.mask::<0, 3>() as u8)?;
         match op {
             0b10 => Ok(Self::Clrex),
-            0b100 => Ok(Self::Dsb(inner_op)),
-            0b101 => Ok(Self::Dmb(inner_op)),
-            0b110 => Ok(Self::Isb(inner_op)),
-            _ => Err(ParseError::Invalid32Bit("A5_14")),
+            0b100 => Ok(Self::Dsb(option)),
+            0b101 => Ok(Self::Dmb(option)),
+            0b110 => Ok(Self::Isb(option)),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.15")),
         }
     }
 }
 
+impl Encoded for A5_15 {
+    fn encoding(&self) -> EncodingId {
+        let name = match self {
+            Self::Clrex => "Clrex",
+            Self::Dsb(_) => "Dsb",
+            Self::Dmb(_) => "Dmb",
+            Self::Isb(_) => "Isb",
+        };
+        EncodingId::new("A5.15", name, 1)
+    }
+}
+
 impl ToOperation for A5_15 {
     fn encoding_specific_operations(self) -> crate::operation::Operation {
         match self {
@@ -49,7 +61,7 @@ impl ToOperation for A5_15 {
                 .complete()
                 .into(),
             Self::Isb(opt) => operation::IsbBuilder::new()
-                .set_option(Some(opt.try_into().unwrap()))
+                .set_option(Some(opt))
                 .complete()
                 .into(),
         }
@@ -84,7 +96,7 @@ mod test {
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
 
         let target: Operation = operation::Dsb::builder()
-            .set_option(Some(0b0010))
+            .set_option(Some(BarrierOption::OshSt))
             .complete()
             .into();
         assert_eq!(instr, target)
@@ -100,7 +112,7 @@ mod test {
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
 
         let target: Operation = operation::Dmb::builder()
-            .set_option(Some(0b0010))
+            .set_option(Some(BarrierOption::OshSt))
             .complete()
             .into();
         assert_eq!(instr, target)
@@ -116,9 +128,7 @@ mod test {
         let instr = Operation::parse(&mut stream).expect("Parser broken").1;
 
         let target: Operation = operation::Isb::builder()
-            .set_option(Some(
-                Imm4::try_from(0b0010u8).expect("Malformed test, imm too large"),
-            ))
+            .set_option(Some(BarrierOption::OshSt))
             .complete()
             .into();
         assert_eq!(instr, target)
@@ -5,41 +5,42 @@ use crate::{
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_16 contains
-    Stm : {
+    Stm T2 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into,
         rn  as u8               : Register          : 16 -> 19 try_into,
         w   as u8               : bool              : 21 -> 21 local_try_into
     },
-    Ldm : {
+    Ldm T2 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into,
         p   as u8               : bool              : 15 -> 15 local_try_into,
         rn  as u8               : Register          : 16 -> 19 try_into,
         w   as u8               : bool              : 21 -> 21 local_try_into
     },
-    Pop : {
+    Pop T2 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into,
         p   as u8               : bool              : 15 -> 15 local_try_into
     },
-    Stmdb : {
+    Stmdb T1 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into,
         rn  as u8               : Register          : 16 -> 19 try_into,
         w   as u8               : bool              : 21 -> 21 local_try_into
     },
-    Push : {
+    Push T2 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into
     },
-    Ldmdb : {
+    Ldmdb T1 : {
         register_list as u16    : u16               : 0 -> 12 ,
         m   as u8               : bool              : 14 -> 14 local_try_into,
         p   as u8               : bool              : 15 -> 15 local_try_into,
@@ -57,10 +58,10 @@ impl Parse for A5_16 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(val) => Ok(val),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op = word.mask::<23, 24>();
-        let l = (word.mask::<20, 20>() as u8).local_try_into()?;
+        let l: bool = (word.mask::<20, 20>() as u8).local_try_into()?;
         let w = word.mask::<21, 21>();
         let rn = word.mask::<16, 19>();
         let wrn = w << 4 | rn;
@@ -74,7 +75,7 @@ impl Parse for A5_16 {
             return Ok(Self::Ldm(Ldm::parse(iter)?));
         }
         if op != 2 {
-            return Err(ParseError::Invalid32Bit("A5_16"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.16"));
         }
         if l {
             return Ok(Self::Ldmdb(Ldmdb::parse(iter)?));
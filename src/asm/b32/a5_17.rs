@@ -4,24 +4,25 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_17 contains
-    Strex : {
+    Strex T1 : {
         imm as u8 : u8          : 0 -> 7,
         rd  as u8 : Register    : 8 -> 11 try_into,
         rt  as u8 : Register    : 12 -> 15 try_into,
         rn  as u8 : Register    : 16 -> 19 try_into
     },
-    Ldrex : {
+    Ldrex T1 : {
         imm as u8 : u8          : 0 -> 7,
         rt  as u8 : Register    : 12 -> 15 try_into,
         rn  as u8 : Register    : 16 -> 19 try_into
     },
-    Strd : {
+    Strd T1 : {
         imm as u8   : u8          : 0 -> 7,
         rt2  as u8  : Register    : 8 -> 11 try_into,
         rt  as u8   : Register    : 12 -> 15 try_into,
@@ -30,7 +31,7 @@ instruction!(
         u   as u8   : bool        : 23 -> 23 local_try_into,
         p   as u8   : bool        : 24 -> 24 local_try_into
     },
-    Ldrd : {
+    Ldrd T1 : {
         imm as u8   : u8          : 0 -> 7,
         rt2  as u8  : Register    : 8 -> 11 try_into,
         rt  as u8   : Register    : 12 -> 15 try_into,
@@ -39,27 +40,27 @@ instruction!(
         u   as u8   : bool        : 23 -> 23 local_try_into,
         p   as u8   : bool        : 24 -> 24 local_try_into
     },
-    Strexb : {
+    Strexb T1 : {
         rd  as u8   : Register    : 0 -> 3 try_into,
         rt  as u8   : Register    : 12 -> 15 try_into,
         rn  as u8   : Register    : 16 -> 19 try_into
     },
-    Strexh : {
+    Strexh T1 : {
         rd  as u8   : Register    : 0 -> 3 try_into,
         rt  as u8   : Register    : 12 -> 15 try_into,
         rn  as u8   : Register    : 16 -> 19 try_into
     },
-    Tbb : {
+    Tbb T1 : {
         rm as u8    : Register    : 0 -> 3 try_into,
         // Denotes if it is a halfword or a full word
         h  as u8    : bool        : 4 -> 4 local_try_into,
         rn as u8    : Register    : 16 -> 19 try_into
     },
-    Ldrexb : {
+    Ldrexb T1 : {
         rt as u8    : Register    : 12 -> 15 try_into,
         rn as u8    : Register    : 16 -> 19 try_into
     },
-    Ldrexh : {
+    Ldrexh T1 : {
         rt as u8    : Register    : 12 -> 15 try_into,
         rn as u8    : Register    : 16 -> 19 try_into
     }
@@ -74,7 +75,7 @@ impl Parse for A5_17 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(val) => Ok(val),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op3 = word.mask::<4, 7>();
         let op2 = word.mask::<20, 21>();
@@ -94,7 +95,7 @@ impl Parse for A5_17 {
             return Ok(Self::Ldrd(Ldrd::parse(iter)?));
         }
         if op1 != 0b01 {
-            return Err(ParseError::Invalid32Bit("A5_17"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.17"));
         }
         match (op2, op3) {
             (0, 0b100) => Ok(Self::Strexb(Strexb::parse(iter)?)),
@@ -102,7 +103,7 @@ impl Parse for A5_17 {
             (1, 0) | (1, 1) => Ok(Self::Tbb(Tbb::parse(iter)?)),
             (1, 0b100) => Ok(Self::Ldrexb(Ldrexb::parse(iter)?)),
             (1, 0b101) => Ok(Self::Ldrexh(Ldrexh::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_17")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.17")),
         }
     }
 }
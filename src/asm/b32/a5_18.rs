@@ -5,6 +5,7 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
@@ -24,18 +25,18 @@ instruction!(
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Ldrt : {
+    Ldrt T1 : {
         imm8    as u8   : u8        : 0 -> 7,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    LdrRegister : {
+    LdrRegister T2 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         imm2    as u8   : Imm2      : 4 -> 5 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    LdrLiteral : {
+    LdrLiteral T2 : {
         imm12   as u16  : Imm12     : 0 -> 11 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         u       as u8   : bool      : 23 -> 23 local_try_into
@@ -58,7 +59,7 @@ impl Parse for A5_18 {
             if op1 >> 1 == 0 {
                 return Ok(Self::LdrLiteral(LdrLiteral::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_18"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.18"));
         }
         if op1 == 1 {
             return Ok(Self::LdrImmediateT3(LdrImmediateT3::parse(iter)?));
@@ -74,7 +75,7 @@ impl Parse for A5_18 {
                 return Ok(Self::LdrRegister(LdrRegister::parse(iter)?));
             }
         }
-        Err(ParseError::Invalid32Bit("A5_18"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.18"))
     }
 }
 
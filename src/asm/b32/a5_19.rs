@@ -5,13 +5,14 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_19 contains
-    LdrhLiteral : {
+    LdrhLiteral T1 : {
         imm12   as u16  : Imm12     : 0 -> 11 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         u       as u8   : bool      : 23 -> 23 local_try_into
@@ -29,13 +30,13 @@ instruction!(
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    LdrhRegister : {
+    LdrhRegister T2 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         imm2    as u8   : Imm2      : 4 -> 5 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Ldrht : {
+    Ldrht T1 : {
         imm8    as u8   : u8        : 0 -> 7,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
@@ -53,18 +54,18 @@ instruction!(
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    LdrshLiteral : {
+    LdrshLiteral T1 : {
         imm12   as u16  : Imm12     : 0 -> 11 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         u       as u8   : bool      : 23 -> 23 local_try_into
     },
-    LdrshRegister : {
+    LdrshRegister T2 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         imm2    as u8   : Imm2      : 4 -> 5 try_into,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Ldrsht : {
+    Ldrsht T1 : {
         imm8    as u8   : u8        : 0 -> 7,
         rt      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
@@ -80,7 +81,7 @@ impl Parse for A5_19 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op2 = word.mask::<6, 11>();
         let rt = word.mask::<12, 15>();
@@ -89,7 +90,7 @@ impl Parse for A5_19 {
         let op1 = word.mask::<23, 24>();
 
         if rt == 0b1111 {
-            return Err(ParseError::Invalid32Bit("A5_19 or strangly encoded NOP"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.19"));
         }
         if rn == 0b1111 {
             // Two options, ldrh or Ldrsh
@@ -108,7 +109,7 @@ impl Parse for A5_19 {
             if op2 >> 2 == 0b1110 {
                 return Ok(Self::Ldrht(Ldrht::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_19"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.19"));
         }
         if op1 == 1 {
             return Ok(Self::LdrhImmediateT2(LdrhImmediateT2::parse(iter)?));
@@ -123,13 +124,13 @@ impl Parse for A5_19 {
             if op2 >> 2 == 0b1110 {
                 return Ok(Self::Ldrsht(Ldrsht::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_19"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.19"));
         }
         if op1 == 3 {
             return Ok(Self::LdrshImmediateT1(LdrshImmediateT1::parse(iter)?));
         }
         // This should be unreachable
-        Err(ParseError::Invalid32Bit("A5_19"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.19"))
     }
 }
 
@@ -5,13 +5,14 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_20 contains
-    LdrbLiteral : {
+    LdrbLiteral T1 : {
         imm12 as u16    : Imm12     : 0 -> 11 try_into,
         rt    as u8     : Register  : 12 -> 15 try_into,
         u     as u8     : bool      : 23 -> 23 local_try_into
@@ -29,18 +30,18 @@ instruction!(
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    Ldrbt : {
+    Ldrbt T1 : {
         imm8  as u8     : u8        : 0 -> 7,
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    LdrbRegister : {
+    LdrbRegister T2 : {
         rm    as u8     : Register  : 0 -> 3 try_into,
         imm2  as u8     : Imm2      : 4 -> 5 try_into,
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    LdrsbLiteral : {
+    LdrsbLiteral T1 : {
         imm12 as u16    : Imm12     : 0 -> 11 try_into,
         rt    as u8     : Register  : 12 -> 15 try_into,
         u     as u8     : bool      : 23 -> 23 local_try_into
@@ -58,18 +59,18 @@ instruction!(
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    Ldrsbt : {
+    Ldrsbt T1 : {
         imm8  as u8     : u8        : 0 -> 7,
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    LdrsbRegister : {
+    LdrsbRegister T2 : {
         rm    as u8     : Register  : 0 -> 3 try_into,
         imm2  as u8     : Imm2      : 4 -> 5 try_into,
         rt    as u8     : Register  : 12 -> 15 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    PldLiteral : {
+    PldLiteral T1 : {
         imm12 as u16    : Imm12     : 0 -> 11 try_into,
         u     as u8     : bool      : 23 -> 23 local_try_into
     },
@@ -81,7 +82,7 @@ instruction!(
         imm8  as u8     : u8        : 0 -> 7,
         rn    as u8     : Register  : 16 -> 19 try_into
     },
-    PldRegister : {
+    PldRegister T1 : {
         rm    as u8     : Register  : 0 -> 3 try_into,
         imm2  as u8     : Imm2      : 4 -> 5 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
@@ -98,7 +99,7 @@ instruction!(
         imm12 as u16    : Imm12     : 0 -> 11 try_into,
         u     as u8     : bool      : 23 -> 23 local_try_into
     },
-    PliRegister : {
+    PliRegister T1 : {
         rm    as u8     : Register  : 0 -> 3 try_into,
         imm2  as u8     : Imm2      : 4 -> 5 try_into,
         rn    as u8     : Register  : 16 -> 19 try_into
@@ -114,7 +115,7 @@ impl Parse for A5_20 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let op2 = word.mask::<6, 11>();
@@ -142,13 +143,14 @@ impl Parse for A5_20 {
                 if (op2 >> 2) == 0b1100 {
                     return Ok(Self::PldImmediateT2(PldImmediateT2::parse(iter)?));
                 }
+                // Loads in to the PC are UNPREDICTABLE, see `Reason::of`.
                 if (op2 >> 2) == 0b1110 {
-                    return Err(ParseError::Unpredictable);
+                    return Ok(Self::Ldrbt(Ldrbt::parse(iter)?));
                 }
                 if (op2 & 0b100100) == 0b100100 {
-                    return Err(ParseError::Unpredictable);
+                    return Ok(Self::LdrbImmediateT3(LdrbImmediateT3::parse(iter)?));
                 }
-                return Err(ParseError::Invalid32Bit("A5_20"));
+                return Err(ErrorKind::Invalid32Bit.in_table("A5.20"));
             }
             if op1 == 2 && op2 >> 2 == 0b1100 {
                 return Ok(Self::PliImmediateT2(PliImmediateT2::parse(iter)?));
@@ -156,7 +158,7 @@ impl Parse for A5_20 {
             if op1 == 2 && op2 == 0 {
                 return Ok(Self::PliRegister(PliRegister::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_20"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.20"));
         }
         // first half of table
         if rn == 0b1111 {
@@ -178,7 +180,7 @@ impl Parse for A5_20 {
             if op2 & 0b100100 == 0b100100 {
                 return Ok(Self::LdrbImmediateT3(LdrbImmediateT3::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_20"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.20"));
         }
         if op1 == 1 {
             return Ok(Self::LdrbImmediateT2(LdrbImmediateT2::parse(iter)?));
@@ -199,7 +201,7 @@ impl Parse for A5_20 {
         if (op2 & 0b100100) == 0b100100 {
             return Ok(Self::LdrsbImmediateT2(LdrsbImmediateT2::parse(iter)?));
         }
-        Err(ParseError::Invalid32Bit("A5_20"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.20"))
     }
 }
 
@@ -462,7 +464,7 @@ mod test {
 
         let target: Operation = operation::LdrsbLiteral::builder()
             .set_rt(Register::R3)
-            .set_imm(0b111_00010_1111)
+            .set_imm(0b1110_0010_1111)
             .set_add(true)
             .complete()
             .into();
@@ -5,6 +5,7 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
@@ -25,7 +26,7 @@ instruction!(
         rt      as u8       :   Register    : 12 -> 15 try_into,
         rn      as u8       :   Register    : 16 -> 19 try_into
     },
-    StrbReg : {
+    StrbReg T2 : {
         rm      as u8       :   Register    : 0 -> 3 try_into,
         imm     as u8       :   u8          : 4 -> 5,
         rt      as u8       :   Register    : 12 -> 15 try_into,
@@ -45,7 +46,7 @@ instruction!(
         rt      as u8       :   Register    : 12 -> 15 try_into,
         rn      as u8       :   Register    : 16 -> 19 try_into
     },
-    StrhReg : {
+    StrhReg T2 : {
         rm      as u8       :   Register    : 0 -> 3 try_into,
         imm     as u8       :   u8          : 4 -> 5,
         rt      as u8       :   Register    : 12 -> 15 try_into,
@@ -65,7 +66,7 @@ instruction!(
         rt      as u8       :   Register    : 12 -> 15 try_into,
         rn      as u8       :   Register    : 16 -> 19 try_into
     },
-    StrReg : {
+    StrReg T2 : {
         rm      as u8       :   Register    : 0 -> 3 try_into,
         imm     as u8       :   u8          : 4 -> 5,
         rt      as u8       :   Register    : 12 -> 15 try_into,
@@ -82,7 +83,7 @@ impl Parse for A5_21 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         // Only concerned with first bit
         let op2 = word.mask::<11, 11>();
@@ -98,7 +99,7 @@ impl Parse for A5_21 {
             (0b110, _) => Ok(Self::StrIT3(StrIT3::parse(iter)?)),
             (0b010, 1) => Ok(Self::StrIT4(StrIT4::parse(iter)?)),
             (0b010, 0) => Ok(Self::StrReg(StrReg::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_21")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.21")),
         }
     }
 }
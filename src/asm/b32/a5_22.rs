@@ -5,13 +5,14 @@ use crate::{
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_22 contains
-    And : {
+    And T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -20,14 +21,14 @@ instruction!(
         rn  as u8   : Register    : 16 -> 19 try_into,
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Tst : {
+    Tst T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         imm3 as u8  : u8          : 12 -> 14,
         rn  as u8   : Register    : 16 -> 19 try_into
     },
-    Bic : {
+    Bic T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -37,7 +38,7 @@ instruction!(
 
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Orr : {
+    Orr T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -48,7 +49,7 @@ instruction!(
     },
     // Also contains subtable A5_23
     -> A5_23,
-    Orn : {
+    Orn T1 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -57,7 +58,7 @@ instruction!(
         rn  as u8   : Register    : 16 -> 19 try_into,
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Mvn : {
+    Mvn T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -65,7 +66,7 @@ instruction!(
         imm3 as u8  : u8          : 12 -> 14,
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Eor : {
+    Eor T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -74,14 +75,14 @@ instruction!(
         rn  as u8   : Register    : 16 -> 19 try_into,
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Teq : {
+    Teq T1 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         imm3 as u8  : u8          : 12 -> 14,
         rn  as u8   : Register    : 16 -> 19 try_into
     },
-    Pkh : {
+    Pkh T1 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         t    as u8  : bool        : 4 -> 4 local_try_into,
         tb   as u8  : bool        : 5 -> 5 local_try_into,
@@ -91,7 +92,7 @@ instruction!(
         rn   as u8  : Register    : 16 -> 19 try_into,
         _s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Add : {
+    Add T3 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -100,14 +101,14 @@ instruction!(
         rn  as u8   : Register    : 16 -> 19 try_into,
         s   as u8   : bool        : 20 -> 20 local_try_into
     },
-    Cmn : {
+    Cmn T2 : {
         rm  as u8   : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         imm3 as u8  : u8          : 12 -> 14,
         rn  as u8   : Register    : 16 -> 19 try_into
     },
-    Adc : {
+    Adc T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -116,7 +117,7 @@ instruction!(
         rn   as u8  : Register    : 16 -> 19 try_into,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Sbc : {
+    Sbc T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -125,7 +126,7 @@ instruction!(
         rn   as u8  : Register    : 16 -> 19 try_into,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Sub : {
+    Sub T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -134,14 +135,14 @@ instruction!(
         rn   as u8  : Register    : 16 -> 19 try_into,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Cmp : {
+    Cmp T3 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         imm3 as u8  : u8          : 12 -> 14,
         rn   as u8  : Register    : 16 -> 19 try_into
     },
-    Rsb : {
+    Rsb T1 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         ty  as u8   : Shift       : 4 -> 5 try_into,
         imm2 as u8  : u8          : 6 -> 7,
@@ -159,7 +160,7 @@ macro_rules! fields {
     ) => {
         let word : $width = match $iter.peek::<1>(){
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram)
+            None => Err(ErrorKind::IncompleteProgram)
         }?;
         $(
             let $id : $type = (word.mask::<$start,$end>())$(.$map() ?)?;
@@ -181,23 +182,22 @@ impl Parse for A5_22 {
             s   : bool  : 20 -> 20 local_try_into,
             op  : u32   : 21 -> 24
         );
+        // Writing the PC without setting the flags is UNPREDICTABLE, see
+        // `Reason::of`.
         if op == 0 {
-            if rd == 0b1111 && !s {
-                return Err(ParseError::Unpredictable);
-            }
-            if rd != 0b1111 {
-                return Ok(Self::And(And::parse(iter)?));
-            }
-            if s {
+            if rd == 0b1111 && s {
                 return Ok(Self::Tst(Tst::parse(iter)?));
             }
+            return Ok(Self::And(And::parse(iter)?));
         }
         if op == 1 {
             return Ok(Self::Bic(Bic::parse(iter)?));
         }
         if op == 2 {
             if rn == 0b1111 {
-                return Ok(Self::SubtableA5_23(A5_23::parse(iter)?));
+                return Ok(Self::SubtableA5_23(
+                    A5_23::parse(iter).map_err(|e| e.in_table("A5.23"))?,
+                ));
             }
             return Ok(Self::Orr(Orr::parse(iter)?));
         }
@@ -208,25 +208,19 @@ impl Parse for A5_22 {
             return Ok(Self::Orn(Orn::parse(iter)?));
         }
         if op == 4 {
-            if rd != 0b1111 {
-                return Ok(Self::Eor(Eor::parse(iter)?));
+            if rd == 0b1111 && s {
+                return Ok(Self::Teq(Teq::parse(iter)?));
             }
-            return match s {
-                true => Ok(Self::Teq(Teq::parse(iter)?)),
-                false => Err(ParseError::Unpredictable),
-            };
+            return Ok(Self::Eor(Eor::parse(iter)?));
         }
         if op == 6 {
             return Ok(Self::Pkh(Pkh::parse(iter)?));
         }
         if op == 0b1000 {
-            if rd != 0b1111 {
-                return Ok(Self::Add(Add::parse(iter)?));
-            }
-            if !s {
-                return Err(ParseError::Unpredictable);
+            if rd == 0b1111 && s {
+                return Ok(Self::Cmn(Cmn::parse(iter)?));
             }
-            return Ok(Self::Cmn(Cmn::parse(iter)?));
+            return Ok(Self::Add(Add::parse(iter)?));
         }
         match op {
             0b1010 => return Ok(Self::Adc(Adc::parse(iter)?)),
@@ -235,15 +229,12 @@ impl Parse for A5_22 {
             _ => {}
         };
         if op == 0b1101 {
-            if rd != 0b1111 {
-                return Ok(Self::Sub(Sub::parse(iter)?));
+            if rd == 0b1111 && s {
+                return Ok(Self::Cmp(Cmp::parse(iter)?));
             }
-            if !s {
-                return Err(ParseError::Unpredictable);
-            }
-            return Ok(Self::Cmp(Cmp::parse(iter)?));
+            return Ok(Self::Sub(Sub::parse(iter)?));
         }
-        Err(ParseError::Invalid32Bit("A5_22"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.22"))
     }
 }
 macro_rules! shift {
@@ -433,6 +424,31 @@ mod test {
         assert_eq!(instr, target)
     }
 
+    #[test]
+    fn test_parse_and_reg_lsr() {
+        let mut bin = vec![];
+        bin.extend([0b1110_1010u8, 0b0001_0011u8].into_iter().rev());
+        bin.extend([0b0000_0011u8, 0b0001_0011u8].into_iter().rev());
+
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        let instr = Operation::parse(&mut stream).expect("Parser broken").1;
+
+        let shift = ImmShift {
+            shift_t: Shift::Lsr,
+            shift_n: 32,
+        };
+
+        let target: Operation = operation::AndRegister::builder()
+            .set_rn(Register::R3)
+            .set_s(Some(true.into()))
+            .set_rd(Some(Register::R3))
+            .set_rm(Register::R3)
+            .set_shift(Some(shift))
+            .complete()
+            .into();
+        assert_eq!(instr, target)
+    }
+
     #[test]
     fn test_parse_tst_reg() {
         let mut bin = vec![];
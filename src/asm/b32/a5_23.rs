@@ -5,44 +5,45 @@ use crate::{
     combine,
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_23 contains
-    Mov : {
+    Mov T3 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         rd   as u8  : Register    : 8 -> 11 try_into,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Lsl : {
+    Lsl T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         rd   as u8  : Register    : 8 -> 11 try_into,
         imm3 as u8  : u8          : 12 -> 14,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Lsr : {
+    Lsr T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         rd   as u8  : Register    : 8 -> 11 try_into,
         imm3 as u8  : u8          : 12 -> 14,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Asr : {
+    Asr T2 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         rd   as u8  : Register    : 8 -> 11 try_into,
         imm3 as u8  : u8          : 12 -> 14,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Rrx : {
+    Rrx T1 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         rd   as u8  : Register    : 8 -> 11 try_into,
         s    as u8  : bool        : 20 -> 20 local_try_into
     },
-    Ror : {
+    Ror T1 : {
         rm   as u8  : Register    : 0 -> 3 try_into,
         imm2 as u8  : u8          : 6 -> 7,
         rd   as u8  : Register    : 8 -> 11 try_into,
@@ -60,7 +61,7 @@ impl Parse for A5_23 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let ty = word.mask::<4, 5>();
@@ -74,7 +75,7 @@ impl Parse for A5_23 {
             (2, _, _) => Ok(Self::Asr(Asr::parse(iter)?)),
             (3, 0, 0) => Ok(Self::Rrx(Rrx::parse(iter)?)),
             (3, _, _) => Ok(Self::Ror(Ror::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_6")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.6")),
         }
     }
 }
@@ -6,6 +6,7 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
@@ -13,92 +14,92 @@ use crate::{
 // Data processing for registers
 instruction!(
     size u32; A5_24 contains
-    Lsl : {
+    Lsl T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into,
         s           as u8   : bool      : 20 -> 20 local_try_into
     },
-    Lsr : {
+    Lsr T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into,
         s           as u8   : bool      : 20 -> 20 local_try_into
     },
-    Asr : {
+    Asr T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into,
         s           as u8   : bool      : 20 -> 20 local_try_into
     },
-    Ror : {
+    Ror T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into,
         s           as u8   : bool      : 20 -> 20 local_try_into
     },
-    Sxtah : {
+    Sxtah T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Sxth : {
+    Sxth T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
     },
-    Uxtah : {
+    Uxtah T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Uxth : {
+    Uxth T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
     },
-    Sxtab16 : {
+    Sxtab16 T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Sxtb16 : {
+    Sxtb16 T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
     },
-    Uxtab16 : {
+    Uxtab16 T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Uxtb16 : {
+    Uxtb16 T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
     },
-    Sxtab : {
+    Sxtab T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Sxtb : {
+    Sxtb T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
     },
-    Uxtab : {
+    Uxtab T1 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into,
         rn          as u8   : Register  : 16 -> 19 try_into
     },
-    Uxtb : {
+    Uxtb T2 : {
         rm          as u8   : Register  : 0 -> 3 try_into,
         rotate      as u8   : Imm2      : 4 -> 5 try_into,
         rd          as u8   : Register  : 8 -> 11 try_into
@@ -145,15 +146,25 @@ impl Parse for A5_24 {
         }
         if op1 >> 3 == 1 {
             match op2 >> 2 {
-                0 => return Ok(Self::SubtableA5_25(A5_25::parse(iter)?)),
-                1 => return Ok(Self::SubtableA5_26(A5_26::parse(iter)?)),
+                0 => {
+                    return Ok(Self::SubtableA5_25(
+                        A5_25::parse(iter).map_err(|e| e.in_table("A5.25"))?,
+                    ))
+                }
+                1 => {
+                    return Ok(Self::SubtableA5_26(
+                        A5_26::parse(iter).map_err(|e| e.in_table("A5.26"))?,
+                    ))
+                }
                 _ => {}
             }
         }
         if op1 >> 2 == 2 && op2 >> 2 == 2 {
-            return Ok(Self::SubtableA5_27(A5_27::parse(iter)?));
+            return Ok(Self::SubtableA5_27(
+                A5_27::parse(iter).map_err(|e| e.in_table("A5.27"))?,
+            ));
         }
-        Err(ParseError::Invalid32Bit("A5_24"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.24"))
     }
 }
 
@@ -1,95 +1,95 @@
 use paste::paste;
 
-use crate::{asm::Mask, instruction, prelude::*, ParseError, ToOperation};
+use crate::{asm::Mask, instruction, prelude::*, ErrorKind, ParseError, ToOperation};
 
 instruction!(
     size u32; A5_25 contains
-    Sadd16 : {
+    Sadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Sasx : {
+    Sasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Ssax : {
+    Ssax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Ssub16 : {
+    Ssub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Sadd8 : {
+    Sadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Ssub8 : {
+    Ssub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qadd16 : {
+    Qadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qasx : {
+    Qasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qsax : {
+    Qsax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qsub16 : {
+    Qsub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qadd8 : {
+    Qadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Qsub8 : {
+    Qsub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shadd16 : {
+    Shadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shasx : {
+    Shasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shsax : {
+    Shsax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shsub16 : {
+    Shsub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shadd8 : {
+    Shadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Shsub8 : {
+    Shsub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
@@ -105,7 +105,7 @@ impl Parse for A5_25 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op1 = word.mask::<20, 22>();
         let op2 = word.mask::<4, 5>();
@@ -128,7 +128,7 @@ impl Parse for A5_25 {
             (0b101, 0b10) => Ok(Self::Shsub16(Shsub16::parse(iter)?)),
             (0b000, 0b10) => Ok(Self::Shadd8(Shadd8::parse(iter)?)),
             (0b100, 0b10) => Ok(Self::Shsub8(Shsub8::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_25")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.25")),
         }
     }
 }
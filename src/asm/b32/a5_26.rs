@@ -1,95 +1,95 @@
 use paste::paste;
 
-use crate::{asm::Mask, instruction, prelude::*, ParseError, ToOperation};
+use crate::{asm::Mask, instruction, prelude::*, ErrorKind, ParseError, ToOperation};
 
 instruction!(
     size u32; A5_26 contains
-    Uadd16 : {
+    Uadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uasx : {
+    Uasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Usax : {
+    Usax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Usub16 : {
+    Usub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uadd8 : {
+    Uadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Usub8 : {
+    Usub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqadd16 : {
+    Uqadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqasx : {
+    Uqasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqsax : {
+    Uqsax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqsub16 : {
+    Uqsub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqadd8 : {
+    Uqadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uqsub8 : {
+    Uqsub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhadd16 : {
+    Uhadd16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhasx : {
+    Uhasx T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhsax : {
+    Uhsax T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhsub16 : {
+    Uhsub16 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhadd8 : {
+    Uhadd8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
     },
-    Uhsub8 : {
+    Uhsub8 T1 : {
         rm      as u8 : Register    : 0 -> 3 try_into,
         rd      as u8 : Register    : 8 -> 11 try_into,
         rn      as u8 : Register    : 16 -> 19 try_into
@@ -105,7 +105,7 @@ impl Parse for A5_26 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
         let op1 = word.mask::<20, 22>();
         let op2 = word.mask::<4, 5>();
@@ -128,7 +128,7 @@ impl Parse for A5_26 {
             (0b101, 0b10) => Ok(Self::Uhsub16(Uhsub16::parse(iter)?)),
             (0b000, 0b10) => Ok(Self::Uhadd8(Uhadd8::parse(iter)?)),
             (0b100, 0b10) => Ok(Self::Uhsub8(Uhsub8::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_25")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.25")),
         }
     }
 }
@@ -1,51 +1,51 @@
 use paste::paste;
 
-use crate::{asm::Mask, instruction, prelude::*, ParseError, ToOperation};
+use crate::{asm::Mask, instruction, prelude::*, ErrorKind, ParseError, ToOperation};
 
 instruction!(
     size u32; A5_27 contains
-    Qadd : {
+    Qadd T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into,
         rn  as  u8  : Register  : 16 -> 19 try_into
     },
-    Qdadd : {
+    Qdadd T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into,
         rn  as  u8  : Register  : 16 -> 19 try_into
     },
-    Qsub : {
+    Qsub T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into,
         rn  as  u8  : Register  : 16 -> 19 try_into
     },
-    Qdsub : {
+    Qdsub T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into,
         rn  as  u8  : Register  : 16 -> 19 try_into
     },
-    Rev : {
+    Rev T2 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into
     },
-    Rev16 : {
+    Rev16 T2 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into
     },
-    Rbit : {
+    Rbit T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into
     },
-    Revsh : {
+    Revsh T2 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into
     },
-    Sel : {
+    Sel T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into,
         rn  as  u8  : Register  : 16 -> 19 try_into
     },
-    Clz : {
+    Clz T1 : {
         rm  as u8   : Register  : 0 -> 3 try_into,
         rd  as u8   : Register  : 8 -> 11 try_into
     }
@@ -60,7 +60,7 @@ impl Parse for A5_27 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram),
         }?;
 
         let op1 = word.mask::<20, 21>();
@@ -70,13 +70,13 @@ impl Parse for A5_27 {
             if op2 == 0 {
                 return Ok(Self::Clz(Clz::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_27"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.27"));
         }
         if op1 == 0b10 {
             if op2 == 0 {
                 return Ok(Self::Sel(Sel::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_27"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.27"));
         }
         if op1 == 0b01 {
             return Ok(match op2 {
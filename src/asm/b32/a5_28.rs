@@ -4,30 +4,31 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_28 contains
-    Mla : {
+    Mla T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Mul : {
+    Mul T2 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Mls : {
+    Mls T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smla : {
+    Smla T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         n       as u8   : bool      : 5 -> 5 local_try_into,
@@ -35,79 +36,79 @@ instruction!(
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smul : {
+    Smul T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         n       as u8   : bool      : 5 -> 5 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlad : {
+    Smlad T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smuad : {
+    Smuad T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlaw : {
+    Smlaw T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smulw : {
+    Smulw T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlsd : {
+    Smlsd T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smusd : {
+    Smusd T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smmla : {
+    Smmla T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         r       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smmul : {
+    Smmul T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         r       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smmls : {
+    Smmls T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         r       as u8   : bool      : 4 -> 4 local_try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Usada8 : {
+    Usada8 T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         ra      as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Usad8 : {
+    Usad8 T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
@@ -144,7 +145,7 @@ impl Parse for A5_28 {
             (0b110, 0, _) | (0b110, 1, _) => Ok(Self::Smmls(Smmls::parse(iter)?)),
             (0b111, 0, 0b1111) => Ok(Self::Usad8(Usad8::parse(iter)?)),
             (0b111, 0, _) => Ok(Self::Usada8(Usada8::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_28")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.28")),
         }
     }
 }
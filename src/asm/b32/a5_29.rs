@@ -4,41 +4,42 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
 
 instruction!(
     size u32; A5_29 contains
-    Smull : {
+    Smull T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Sdiv : {
+    Sdiv T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Umull : {
+    Umull T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Udiv : {
+    Udiv T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rd      as u8   : Register  : 8 -> 11 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlal : {
+    Smlal T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    SmlalXY : {
+    SmlalXY T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         n       as u8   : bool      : 5 -> 5 local_try_into,
@@ -46,27 +47,27 @@ instruction!(
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlald : {
+    Smlald T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Smlsld : {
+    Smlsld T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         m       as u8   : bool      : 4 -> 4 local_try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Umlal : {
+    Umlal T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
         rn      as u8   : Register  : 16 -> 19 try_into
     },
-    Umaal : {
+    Umaal T1 : {
         rm      as u8   : Register  : 0 -> 3 try_into,
         rdhi    as u8   : Register  : 8 -> 11 try_into,
         rdlo    as u8   : Register  : 12 -> 15 try_into,
@@ -95,13 +96,13 @@ impl Parse for A5_29 {
             if op2 >> 1 == 0b110 {
                 return Ok(Self::Smlald(Smlald::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_29"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.29"));
         }
         if op1 == 0b101 {
             if op2 >> 1 == 0b110 {
                 return Ok(Self::Smlsld(Smlsld::parse(iter)?));
             }
-            return Err(ParseError::Invalid32Bit("A5_29"));
+            return Err(ErrorKind::Invalid32Bit.in_table("A5.29"));
         }
         match (op1, op2) {
             (0b000, 0b0000) => Ok(Self::Smull(Smull::parse(iter)?)),
@@ -110,7 +111,7 @@ impl Parse for A5_29 {
             (0b011, 0b1111) => Ok(Self::Udiv(Udiv::parse(iter)?)),
             (0b110, 0b0000) => Ok(Self::Umlal(Umlal::parse(iter)?)),
             (0b110, 0b0110) => Ok(Self::Umaal(Umaal::parse(iter)?)),
-            _ => Err(ParseError::Invalid32Bit("A5_29")),
+            _ => Err(ErrorKind::Invalid32Bit.in_table("A5.29")),
         }
     }
 }
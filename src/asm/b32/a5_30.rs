@@ -8,6 +8,7 @@ use crate::{
     asm::{LocalTryInto, Mask},
     instruction,
     prelude::*,
+    ErrorKind,
     ParseError,
     ToOperation,
 };
@@ -160,30 +161,30 @@ impl Parse for A5_30 {
     {
         let word: u32 = match iter.peek::<1>() {
             Some(word) => word,
-            None => return Err(ParseError::IncompleteProgram),
+            None => return Err(ErrorKind::IncompleteProgram.into()),
         };
 
         let op = word.mask::<4, 4>();
-        assert!(op <= 1);
         let enc = word.mask::<{ 16 + 12 }, { 16 + 12 }>();
-        assert!(enc <= 1);
         let op1 = word.mask::<{ 16 + 4 }, { 16 + 9 }>();
-        assert!(op1 < (1 << (9 - 4 + 1)) - 1);
         let rn = word.mask::<16, 19>();
-        assert!(rn < (1 << (19 - 16 + 1)));
+
+        if op1 >> 1 == 0 {
+            return Err(ErrorKind::Undefined.in_table("A5.30"));
+        }
 
         if op1 == 0b000100 {
             match enc + 1 {
                 1 => return Ok(Self::McrrT1(McrrT1::parse(iter)?)),
                 2 => return Ok(Self::McrrT2(McrrT2::parse(iter)?)),
-                _ => unreachable!("This is unreachable due to previous asserts"),
+                _ => unreachable!("enc is a single bit"),
             }
         }
         if op1 == 0b000101 {
             match enc + 1 {
                 1 => return Ok(Self::MrrcT1(MrrcT1::parse(iter)?)),
                 2 => return Ok(Self::MrrcT2(MrrcT2::parse(iter)?)),
-                _ => unreachable!("This is unreachable due to previous asserts"),
+                _ => unreachable!("enc is a single bit"),
             }
         }
         match (enc + 1, op1 & 0b110001, op) {
@@ -207,7 +208,7 @@ impl Parse for A5_30 {
             (2, 0b000001, _) => return Ok(Self::LdcImmediateT2(LdcImmediateT2::parse(iter)?)),
             _ => {}
         }
-        Err(ParseError::Invalid32Bit("a5_30"))
+        Err(ErrorKind::Invalid32Bit.in_table("A5.30"))
     }
 }
 impl ToOperation for A5_30 {
@@ -361,7 +362,62 @@ impl ToOperation for A5_30 {
 #[cfg(test)]
 mod test {
 
-    use crate::prelude::*;
+    use crate::{prelude::*, ErrorKind};
+
+    #[test]
+    fn test_parse_op1() {
+        // Sweeps every op1 and op with a literal and a register base, each
+        // one is either decoded as the instruction in A5.30 or rejected.
+        for op1 in 0..(1u8 << 6) {
+            for enc in 0..2u8 {
+                for op in 0..2u8 {
+                    for rn in [0b0000u8, 0b1111u8] {
+                        let mut bin = vec![];
+                        bin.extend(
+                            [
+                                0b1110_1100u8 | (enc << 4) | (op1 >> 4),
+                                ((op1 & 0b1111) << 4) | rn,
+                            ]
+                            .into_iter()
+                            .rev(),
+                        );
+                        bin.extend([0b0000_0000u8, op << 4].into_iter().rev());
+
+                        let mut stream = PeekableBuffer::from(bin.into_iter());
+                        let result = Operation::parse(&mut stream).map(|(_, operation)| operation);
+                        let context = format!("op1 = {op1:#08b}, op = {op}, rn = {rn:#06b}");
+                        let accepted = match (op1 >> 4, op1 & 1, op, rn) {
+                            _ if op1 >> 1 == 0 => {
+                                let error = result.expect_err(&context);
+                                assert!(matches!(error.kind, ErrorKind::Undefined), "{context}");
+                                assert_eq!(error.table, Some("A5.30"), "{context}");
+                                continue;
+                            }
+                            _ if op1 == 0b000100 => matches!(result, Ok(Operation::Mcrr(_))),
+                            _ if op1 == 0b000101 => matches!(result, Ok(Operation::Mrrc(_))),
+                            (0b00 | 0b01, 0, ..) => matches!(result, Ok(Operation::Stc(_))),
+                            (0b00 | 0b01, 1, _, 0b1111) => {
+                                matches!(result, Ok(Operation::LdcLiteral(_)))
+                            }
+                            (0b00 | 0b01, 1, ..) => {
+                                matches!(result, Ok(Operation::LdcImmediate(_)))
+                            }
+                            (0b10, _, 0, _) => matches!(result, Ok(Operation::Cdp(_))),
+                            (0b10, 0, 1, _) => matches!(result, Ok(Operation::Mcr(_))),
+                            (0b10, 1, 1, _) => matches!(result, Ok(Operation::Mrc(_))),
+                            _ => {
+                                let error = result.expect_err(&context);
+                                assert!(matches!(error.kind, ErrorKind::Invalid32Bit), "{context}");
+                                assert_eq!(error.table, Some("A5.30"), "{context}");
+                                continue;
+                            }
+                        };
+                        assert!(accepted, "{context}: {result:?}");
+                    }
+                }
+            }
+        }
+    }
 
     #[test]
     fn test_parse_stc() {
@@ -1,6 +1,6 @@
 //! Defines the statements available in armv7.
 
-use crate::ParseError;
+use crate::{ErrorKind, ParseError};
 
 pub mod b16;
 pub mod b32;
@@ -16,9 +16,7 @@ pub(crate) trait Mask {
 impl LocalTryInto<bool> for u8 {
     fn local_try_into(self) -> Result<bool, ParseError> {
         if self > 1 {
-            return Err(ParseError::InvalidField(format!(
-                "Invalid masking of bool {self}"
-            )));
+            return Err(ErrorKind::InvalidField(format!("Invalid masking of bool {self}")).into());
         }
         Ok(self != 0)
     }
@@ -26,9 +24,7 @@ impl LocalTryInto<bool> for u8 {
 impl LocalTryInto<bool> for u32 {
     fn local_try_into(self) -> Result<bool, ParseError> {
         if self > 1 {
-            return Err(ParseError::InvalidField(format!(
-                "Invalid masking of bool {self}"
-            )));
+            return Err(ErrorKind::InvalidField(format!("Invalid masking of bool {self}")).into());
         }
         Ok(self != 0)
     }
@@ -0,0 +1,564 @@
+//! Builds control flow graphs from a [`Disassembly`].
+//!
+//! The reachable instructions are split in to [`BasicBlock`]s, sequences of
+//! instructions that are always executed in order. The blocks are connected
+//! by labelled [`Edge`]s and grouped in to functions, where a function is
+//! the set of blocks that are reachable from an entry point or a call target
+//! without following calls. Branches to the entry of another function are
+//! treated as tail calls and do not add the blocks of that function.
+//!
+//! Instructions in IT blocks are treated as predicated instructions, they do
+//! not split the basic block unless they write to the PC.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{cfg::Cfg, disassembler::Disassembler, loader::Region};
+//!
+//! let code = [
+//!     0x00, 0x28, // cmp r0, #0
+//!     0x00, 0xd0, // beq 0x106
+//!     0x01, 0x20, // movs r0, #1
+//!     0x70, 0x47, // bx lr
+//! ];
+//! let disassembly = Disassembler::new([Region::new(0x100, &code)])
+//!     .with_entry(0x100)
+//!     .run();
+//! let cfg = Cfg::new(&disassembly);
+//!
+//! assert_eq!(cfg.blocks.len(), 3);
+//! assert!(cfg.to_dot().starts_with("digraph cfg {"));
+//! ```
+
+pub mod call_graph;
+pub mod constants;
+pub mod exception;
+pub mod stack;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+pub use call_graph::CallGraph;
+pub use constants::Constants;
+pub use exception::Exceptions;
+pub use stack::StackUsage;
+
+use crate::{
+    arch::Condition,
+    disassembler::{Disassembly, Flow},
+    operation::Operation,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Enumerates the kinds of edges in the graph.
+pub enum EdgeKind {
+    /// Continues with the next instruction.
+    FallThrough,
+    /// A branch that is taken if the condition holds, unconditional branches
+    /// have the condition [`Condition::None`].
+    ///
+    /// For `cbz` and `cbnz` the condition is [`Condition::Eq`] and
+    /// [`Condition::Ne`] respectively, comparing the register to zero rather
+    /// than the flags.
+    Taken(Condition),
+    /// Continues with the next instruction as the condition did not hold.
+    NotTaken(Condition),
+    /// A call to a function.
+    Call,
+    /// A return from the function.
+    Return,
+    /// A branch through a [`JumpTable`](crate::disassembler::JumpTable),
+    /// there is one edge per distinct target in the table.
+    Table,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An edge between two basic blocks.
+pub struct Edge {
+    /// The address of the block that the edge leaves.
+    pub from: u32,
+    /// The address of the block that the edge enters, None if the target is
+    /// not known, such as for returns and unresolved indirect branches.
+    pub to: Option<u32>,
+    /// What kind of edge it is.
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A sequence of instructions that is always executed in order.
+pub struct BasicBlock {
+    /// The address of the first instruction.
+    pub start: u32,
+    /// The address after the last instruction.
+    pub end: u32,
+    /// The addresses of the instructions in the block.
+    pub instructions: Vec<u32>,
+}
+
+#[derive(Debug, Clone)]
+/// A control flow graph.
+pub struct Cfg {
+    /// The basic blocks indexed by their start address.
+    pub blocks: BTreeMap<u32, BasicBlock>,
+    /// The blocks in each function, indexed by the entry of the function.
+    pub functions: BTreeMap<u32, BTreeSet<u32>>,
+    /// The edges that leave each block.
+    successors: BTreeMap<u32, Vec<Edge>>,
+    /// The edges that enter each block.
+    predecessors: BTreeMap<u32, Vec<Edge>>,
+}
+
+impl EdgeKind {
+    /// Returns true if the edge stays within the function.
+    pub fn is_local(&self) -> bool {
+        !matches!(self, Self::Call | Self::Return)
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::FallThrough => "fall through".to_string(),
+            Self::Taken(Condition::None) => "taken".to_string(),
+            Self::Taken(condition) => format!("taken {condition:?}"),
+            Self::NotTaken(condition) => format!("not taken {condition:?}"),
+            Self::Call => "call".to_string(),
+            Self::Return => "return".to_string(),
+            Self::Table => "table".to_string(),
+        }
+    }
+}
+
+impl Cfg {
+    /// Builds the graph for all of the instructions in the disassembly.
+    pub fn new(disassembly: &Disassembly) -> Self {
+        let instructions = &disassembly.instructions;
+
+        // The outgoing edges of the instructions that end a basic block, as
+        // `(target, kind)` pairs.
+        let mut exits: BTreeMap<u32, Vec<(Option<u32>, EdgeKind)>> = BTreeMap::new();
+        let mut entries: BTreeSet<u32> = disassembly.entries.iter().copied().collect();
+        for (address, instruction) in instructions {
+            let next = address.wrapping_add(instruction.size.bytes());
+            let condition = instruction.condition.clone();
+            let predicated = condition != Condition::None;
+            let not_taken = |condition: Condition| match predicated {
+                true => vec![(Some(next), EdgeKind::NotTaken(condition))],
+                false => vec![],
+            };
+            let mut edges = match instruction.flow(*address) {
+                Flow::Sequential => continue,
+                Flow::Branch {
+                    target,
+                    condition: Condition::None,
+                } => vec![(Some(target), EdgeKind::Taken(Condition::None))],
+                Flow::Branch { target, condition } => vec![
+                    (Some(target), EdgeKind::Taken(condition.clone())),
+                    (Some(next), EdgeKind::NotTaken(condition)),
+                ],
+                Flow::CompareBranch { target } => {
+                    let condition = match &instruction.operation {
+                        Operation::Cbz(el) if el.non == Some(true) => Condition::Ne,
+                        _ => Condition::Eq,
+                    };
+                    vec![
+                        (Some(target), EdgeKind::Taken(condition.clone())),
+                        (Some(next), EdgeKind::NotTaken(condition)),
+                    ]
+                }
+                Flow::Call { target } => {
+                    entries.insert(target);
+                    vec![
+                        (Some(target), EdgeKind::Call),
+                        (Some(next), EdgeKind::FallThrough),
+                    ]
+                }
+                Flow::IndirectCall => {
+                    vec![(None, EdgeKind::Call), (Some(next), EdgeKind::FallThrough)]
+                }
+                Flow::Return => {
+                    let mut edges = vec![(None, EdgeKind::Return)];
+                    edges.extend(not_taken(condition));
+                    edges
+                }
+                flow @ (Flow::Indirect | Flow::Table) => {
+                    let mut edges = match disassembly.tables.get(address) {
+                        Some(table) => table
+                            .targets
+                            .iter()
+                            .collect::<BTreeSet<_>>()
+                            .into_iter()
+                            .map(|target| (Some(*target), EdgeKind::Table))
+                            .collect(),
+                        None if flow == Flow::Table => vec![(None, EdgeKind::Table)],
+                        None => vec![(None, EdgeKind::Taken(condition.clone()))],
+                    };
+                    edges.extend(not_taken(condition));
+                    edges
+                }
+                Flow::Stop => not_taken(condition),
+            };
+            // Edges to instructions that were never reached are dropped.
+            edges.retain(|(target, _)| {
+                target.is_none_or(|target| instructions.contains_key(&target))
+            });
+            exits.insert(*address, edges);
+        }
+
+        // Every target and every instruction after the end of a block starts
+        // a new block.
+        let mut leaders: BTreeSet<u32> = entries.clone();
+        for (address, edges) in &exits {
+            leaders.extend(edges.iter().filter_map(|(target, _)| *target));
+            let size = instructions[address].size.bytes();
+            leaders.insert(address.wrapping_add(size));
+        }
+
+        let mut blocks: BTreeMap<u32, BasicBlock> = BTreeMap::new();
+        let mut edges = Vec::new();
+        let mut current: Option<BasicBlock> = None;
+        for (address, instruction) in instructions {
+            let mut block = match current.take() {
+                Some(block) if block.end == *address && !leaders.contains(address) => block,
+                previous => {
+                    // The previous block continues in to this one.
+                    if let Some(previous) = previous {
+                        if previous.end == *address {
+                            edges.push(Edge {
+                                from: previous.start,
+                                to: Some(*address),
+                                kind: EdgeKind::FallThrough,
+                            });
+                        }
+                        blocks.insert(previous.start, previous);
+                    }
+                    BasicBlock {
+                        start: *address,
+                        end: *address,
+                        instructions: Vec::new(),
+                    }
+                }
+            };
+            block.instructions.push(*address);
+            block.end = address.wrapping_add(instruction.size.bytes());
+            match exits.get(address) {
+                Some(exits) => {
+                    edges.extend(exits.iter().map(|(to, kind)| Edge {
+                        from: block.start,
+                        to: *to,
+                        kind: kind.clone(),
+                    }));
+                    blocks.insert(block.start, block);
+                }
+                None => current = Some(block),
+            }
+        }
+        if let Some(block) = current {
+            blocks.insert(block.start, block);
+        }
+
+        let mut successors: BTreeMap<u32, Vec<Edge>> = BTreeMap::new();
+        let mut predecessors: BTreeMap<u32, Vec<Edge>> = BTreeMap::new();
+        for edge in edges {
+            if let Some(to) = edge.to {
+                predecessors.entry(to).or_default().push(edge.clone());
+            }
+            successors.entry(edge.from).or_default().push(edge);
+        }
+
+        let mut cfg = Self {
+            blocks,
+            functions: BTreeMap::new(),
+            successors,
+            predecessors,
+        };
+        entries.retain(|entry| cfg.blocks.contains_key(entry));
+        for entry in &entries {
+            let function = cfg.reachable(*entry, &entries);
+            cfg.functions.insert(*entry, function);
+        }
+        cfg
+    }
+
+    /// Returns the blocks that are reachable from the block without following
+    /// calls, returns or branches to the other entries, which are tail calls.
+    fn reachable(&self, entry: u32, entries: &BTreeSet<u32>) -> BTreeSet<u32> {
+        let mut visited = BTreeSet::new();
+        let mut work = vec![entry];
+        while let Some(block) = work.pop() {
+            if !visited.insert(block) {
+                continue;
+            }
+            work.extend(
+                self.successors(block)
+                    .filter(|edge| edge.kind.is_local())
+                    .filter_map(|edge| edge.to)
+                    .filter(|to| *to == entry || !entries.contains(to)),
+            );
+        }
+        visited
+    }
+
+    /// Returns all of the edges in the graph, ordered by the block that they
+    /// leave.
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.successors.values().flatten()
+    }
+
+    /// Returns the edges that leave the block.
+    pub fn successors(&self, block: u32) -> impl Iterator<Item = &Edge> {
+        self.successors.get(&block).into_iter().flatten()
+    }
+
+    /// Returns the edges that enter the block.
+    pub fn predecessors(&self, block: u32) -> impl Iterator<Item = &Edge> {
+        self.predecessors.get(&block).into_iter().flatten()
+    }
+
+    /// Returns the block that contains the address, if any.
+    pub fn block(&self, address: u32) -> Option<&BasicBlock> {
+        self.blocks
+            .range(..=address)
+            .next_back()
+            .map(|(_, block)| block)
+            .filter(|block| address < block.end)
+    }
+
+    /// Exports the graph in the Graphviz DOT format.
+    ///
+    /// Each function is drawn as a cluster, edges with unknown targets are
+    /// drawn to a shared `unknown` node.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph cfg {\n    node [shape=box, fontname=\"monospace\"];\n");
+        let mut drawn = BTreeSet::new();
+        for (entry, function) in &self.functions {
+            let _ = writeln!(dot, "    subgraph cluster_{entry:08x} {{");
+            let _ = writeln!(dot, "        label=\"{entry:#010x}\";");
+            for block in function {
+                // Blocks that are shared between functions are only drawn once.
+                if drawn.insert(*block) {
+                    let _ = writeln!(dot, "        {};", self.node(*block));
+                }
+            }
+            dot.push_str("    }\n");
+        }
+        for block in self.blocks.keys().filter(|block| !drawn.contains(*block)) {
+            let _ = writeln!(dot, "    {};", self.node(*block));
+        }
+        if self.edges().any(|edge| edge.to.is_none()) {
+            dot.push_str("    unknown [shape=point];\n");
+        }
+        for edge in self.edges() {
+            let to = match edge.to {
+                Some(to) => format!("b{to:08x}"),
+                None => "unknown".to_string(),
+            };
+            let style = match edge.kind {
+                EdgeKind::Call | EdgeKind::Return => ", style=dashed",
+                _ => "",
+            };
+            let _ = writeln!(
+                dot,
+                "    b{:08x} -> {to} [label=\"{}\"{style}];",
+                edge.from,
+                edge.kind.label()
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn node(&self, block: u32) -> String {
+        let block = &self.blocks[&block];
+        format!(
+            "b{:08x} [label=\"{:#010x}\\l{} instructions\\l\"]",
+            block.start,
+            block.start,
+            block.instructions.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cfg::{Cfg, Edge, EdgeKind},
+        disassembler::Disassembler,
+        helpers::test::code,
+        loader::Region,
+        prelude::*,
+    };
+
+    fn cfg(halfwords: &[u16], entries: &[u32]) -> Cfg {
+        let code = code(halfwords);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entries(entries.iter().copied())
+            .run();
+        Cfg::new(&disassembly)
+    }
+
+    fn edges(cfg: &Cfg, block: u32) -> Vec<(Option<u32>, EdgeKind)> {
+        cfg.successors(block)
+            .map(|edge| (edge.to, edge.kind.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks() {
+        let cfg = cfg(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0x2800, // 0x02 cmp r0, #0
+                0xd002, // 0x04 beq 0x0c
+                0xf000, 0xf804, // 0x06 bl 0x12
+                0x3001, // 0x0a adds r0, #1
+                0xb108, // 0x0c cbz r0, 0x12
+                0xbd10, // 0x0e pop {r4, pc}
+                0x0000, // 0x10 data
+                0x4770, // 0x12 bx lr
+            ],
+            &[0],
+        );
+        let blocks: Vec<(u32, u32)> = cfg
+            .blocks
+            .values()
+            .map(|block| (block.start, block.end))
+            .collect();
+        assert_eq!(blocks, vec![
+            (0x00, 0x06),
+            (0x06, 0x0a),
+            (0x0a, 0x0c),
+            (0x0c, 0x0e),
+            (0x0e, 0x10),
+            (0x12, 0x14)
+        ]);
+        assert_eq!(cfg.blocks[&0x00].instructions, vec![0x00, 0x02, 0x04]);
+
+        assert_eq!(edges(&cfg, 0x00), vec![
+            (Some(0x0c), EdgeKind::Taken(Condition::Eq)),
+            (Some(0x06), EdgeKind::NotTaken(Condition::Eq))
+        ]);
+        assert_eq!(edges(&cfg, 0x06), vec![
+            (Some(0x12), EdgeKind::Call),
+            (Some(0x0a), EdgeKind::FallThrough)
+        ]);
+        assert_eq!(edges(&cfg, 0x0a), vec![(Some(0x0c), EdgeKind::FallThrough)]);
+        assert_eq!(edges(&cfg, 0x0c), vec![
+            (Some(0x12), EdgeKind::Taken(Condition::Eq)),
+            (Some(0x0e), EdgeKind::NotTaken(Condition::Eq))
+        ]);
+        assert_eq!(edges(&cfg, 0x0e), vec![(None, EdgeKind::Return)]);
+        assert_eq!(cfg.predecessors(0x0c).count(), 2);
+        assert_eq!(cfg.predecessors(0x00).count(), 0);
+        assert_eq!(cfg.successors(0x1000).count(), 0);
+
+        // Every edge is indexed by the blocks at both of its ends.
+        for edge in cfg.edges() {
+            assert!(cfg.successors(edge.from).any(|other| other == edge));
+            if let Some(to) = edge.to {
+                assert!(cfg.predecessors(to).any(|other| other == edge));
+            }
+        }
+        let incoming: usize = cfg
+            .blocks
+            .keys()
+            .map(|block| cfg.predecessors(*block).count())
+            .sum();
+        assert_eq!(
+            incoming,
+            cfg.edges().filter(|edge| edge.to.is_some()).count()
+        );
+
+        // The call target is a function of its own, the cbz to it is a tail
+        // call.
+        assert_eq!(cfg.functions.keys().copied().collect::<Vec<_>>(), vec![
+            0x00, 0x12
+        ]);
+        assert_eq!(cfg.functions[&0x00].len(), 5);
+        assert_eq!(cfg.functions[&0x12].len(), 1);
+
+        assert_eq!(cfg.block(0x04).unwrap().start, 0x00);
+        assert!(cfg.block(0x10).is_none());
+    }
+
+    #[test]
+    fn test_it_block() {
+        let cfg = cfg(
+            &[
+                0x2800, // 0x00 cmp r0, #0
+                0xbf0c, // 0x02 ite eq
+                0x2001, // 0x04 moveq r0, #1
+                0x2002, // 0x06 movne r0, #2
+                0xbf08, // 0x08 it eq
+                0x4770, // 0x0a bxeq lr
+                0x3001, // 0x0c adds r0, #1
+                0x4770, // 0x0e bx lr
+            ],
+            &[0],
+        );
+        assert_eq!(cfg.blocks.len(), 2);
+        assert_eq!(cfg.blocks[&0x00].instructions.len(), 6);
+        assert_eq!(cfg.edges().cloned().collect::<Vec<_>>(), vec![
+            Edge {
+                from: 0x00,
+                to: None,
+                kind: EdgeKind::Return
+            },
+            Edge {
+                from: 0x00,
+                to: Some(0x0c),
+                kind: EdgeKind::NotTaken(Condition::Eq)
+            },
+            Edge {
+                from: 0x0c,
+                to: None,
+                kind: EdgeKind::Return
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_table() {
+        let cfg = cfg(
+            &[
+                0x2802, // 0x00 cmp r0, #2
+                0xd806, // 0x02 bhi 0x12
+                0xe8df, 0xf000, // 0x04 tbb [pc, r0]
+                0x0302, 0x0003, // 0x08 table
+                0x2001, // 0x0c movs r0, #1
+                0x4770, // 0x0e bx lr
+                0x4770, // 0x10 bx lr
+                0x2000, // 0x12 movs r0, #0
+                0x4770, // 0x14 bx lr
+            ],
+            &[0],
+        );
+        assert_eq!(edges(&cfg, 0x04), vec![
+            (Some(0x0c), EdgeKind::Table),
+            (Some(0x0e), EdgeKind::Table)
+        ]);
+        assert_eq!(cfg.functions[&0x00].len(), 5);
+    }
+
+    #[test]
+    fn test_dot() {
+        let cfg = cfg(
+            &[
+                0xd000, // 0x00 beq 0x04
+                0x4708, // 0x02 bx r1
+                0x4770, // 0x04 bx lr
+            ],
+            &[0],
+        );
+        let dot = cfg.to_dot();
+        assert!(dot.starts_with("digraph cfg {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("subgraph cluster_00000000 {"));
+        assert!(dot.contains("b00000000 -> b00000004 [label=\"taken Eq\"];"));
+        assert!(dot.contains("b00000000 -> b00000002 [label=\"not taken Eq\"];"));
+        assert!(dot.contains("b00000002 -> unknown [label=\"taken\"];"));
+        assert!(dot.contains("b00000004 -> unknown [label=\"return\", style=dashed];"));
+        assert_eq!(dot.matches("b00000004 [label=\"0x").count(), 1);
+    }
+}
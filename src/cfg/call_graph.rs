@@ -0,0 +1,659 @@
+//! Extracts the call graph from a [`Cfg`].
+//!
+//! The graph contains the direct calls through `bl`, the indirect calls
+//! through `blx` with all of the targets that [`Constants`] could resolve,
+//! and tail calls, branches from one function to the start of another. Call
+//! chains that are recursive are reported by [`CallGraph::recursion`], which
+//! is needed to bound the stack usage and execution time of a program. The
+//! recursive groups are the strongly connected components of the graph,
+//! which are found once when the graph is built.
+//!
+//! The graph can be exported to Graphviz DOT and JSON.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use super::{Cfg, Constants, EdgeKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerates the kinds of calls.
+pub enum CallKind {
+    /// A call to a target that is encoded in the instruction, i.e. `bl`.
+    Direct,
+    /// A call through a register, i.e. `blx r3`.
+    Indirect,
+    /// A branch to the start of another function.
+    TailCall,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The functions that a call can enter.
+pub enum Callee {
+    /// The entries of the functions that can be called, a tail call through
+    /// a jump table can enter several functions.
+    Resolved(BTreeSet<u32>),
+    /// The target of an indirect call that could not be resolved.
+    Unresolved,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A call from one function to another.
+pub struct Call {
+    /// The address of the calling instruction.
+    pub site: u32,
+    /// The entry of the function that contains the call site.
+    pub caller: u32,
+    /// The called functions.
+    pub callee: Callee,
+    /// How the function is called.
+    pub kind: CallKind,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The calls between the functions of a program.
+pub struct CallGraph {
+    /// The entries of all functions, including the ones that are only
+    /// called.
+    pub functions: BTreeSet<u32>,
+    /// The calls, ordered by caller and call site.
+    pub calls: Vec<Call>,
+    /// The groups of mutually recursive functions, ordered by their first
+    /// function.
+    recursion: Vec<BTreeSet<u32>>,
+    /// The index of the recursive group that each recursive function is
+    /// part of.
+    groups: BTreeMap<u32, usize>,
+}
+
+impl CallKind {
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Indirect => "indirect",
+            Self::TailCall => "tail",
+        }
+    }
+}
+
+impl Callee {
+    /// Returns the entries of the functions that can be called, this is
+    /// empty if the call could not be resolved.
+    pub fn targets(&self) -> impl Iterator<Item = u32> + '_ {
+        let targets = match self {
+            Self::Resolved(targets) => Some(targets),
+            Self::Unresolved => None,
+        };
+        targets.into_iter().flatten().copied()
+    }
+
+    /// Returns true if the function can be called.
+    pub fn contains(&self, function: u32) -> bool {
+        matches!(self, Self::Resolved(targets) if targets.contains(&function))
+    }
+}
+
+impl CallGraph {
+    /// Extracts the calls from the functions in the graph, the constants are
+    /// used to resolve indirect calls.
+    pub fn new(cfg: &Cfg, constants: &Constants) -> Self {
+        let mut graph = Self::default();
+        for (entry, blocks) in &cfg.functions {
+            graph.functions.insert(*entry);
+            for block in blocks {
+                let Some(site) = cfg.blocks[block].instructions.last().copied() else {
+                    continue;
+                };
+                for edge in cfg.successors(*block) {
+                    let (targets, kind) = match (&edge.kind, edge.to) {
+                        (EdgeKind::Call, Some(target)) => {
+                            (Some(BTreeSet::from([target])), CallKind::Direct)
+                        }
+                        (EdgeKind::Call, None) => {
+                            (constants.targets.get(&site).cloned(), CallKind::Indirect)
+                        }
+                        (EdgeKind::Taken(_) | EdgeKind::Table, Some(target))
+                            if target != *entry && cfg.functions.contains_key(&target) =>
+                        {
+                            (Some(BTreeSet::from([target])), CallKind::TailCall)
+                        }
+                        (EdgeKind::Taken(_), None) => match constants.targets.get(&site) {
+                            Some(targets) => (Some(targets.clone()), CallKind::TailCall),
+                            None => continue,
+                        },
+                        _ => continue,
+                    };
+                    graph.functions.extend(targets.iter().flatten());
+                    // The targets of a jump table are separate edges from the
+                    // same call site.
+                    match (graph.calls.last_mut(), targets) {
+                        (Some(call), Some(targets))
+                            if call.site == site && call.caller == *entry && call.kind == kind =>
+                        {
+                            if let Callee::Resolved(callees) = &mut call.callee {
+                                callees.extend(targets);
+                            }
+                        }
+                        (_, targets) => graph.calls.push(Call {
+                            site,
+                            caller: *entry,
+                            callee: match targets {
+                                Some(targets) => Callee::Resolved(targets),
+                                None => Callee::Unresolved,
+                            },
+                            kind,
+                        }),
+                    }
+                }
+            }
+        }
+        graph.calls.sort_by_key(|call| (call.caller, call.site));
+        graph.recursion = graph.components();
+        graph.groups = graph
+            .recursion
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| group.iter().map(move |function| (*function, index)))
+            .collect();
+        graph
+    }
+
+    /// Finds the strongly connected components of the graph using Tarjan's
+    /// algorithm and returns the ones that are recursive, i.e. that contain
+    /// more than one function or a function that calls itself.
+    fn components(&self) -> Vec<BTreeSet<u32>> {
+        let mut edges: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+        for call in &self.calls {
+            edges
+                .entry(call.caller)
+                .or_default()
+                .extend(call.callee.targets());
+        }
+        let none = BTreeSet::new();
+        let callees = |function: u32| edges.get(&function).unwrap_or(&none);
+
+        // The order in which the functions were visited and the lowest order
+        // that can be reached from them.
+        let mut order: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+        let mut stack: Vec<u32> = Vec::new();
+        let mut on_stack: BTreeSet<u32> = BTreeSet::new();
+        let mut components = Vec::new();
+        for root in &self.functions {
+            if order.contains_key(root) {
+                continue;
+            }
+            // The functions that are being visited along with the callees
+            // that remain to be visited.
+            let mut frames = vec![(*root, callees(*root).iter())];
+            order.insert(*root, (order.len(), order.len()));
+            stack.push(*root);
+            on_stack.insert(*root);
+            while let Some((function, remaining)) = frames.last_mut() {
+                let function = *function;
+                match remaining.next().copied() {
+                    Some(callee) if !order.contains_key(&callee) => {
+                        order.insert(callee, (order.len(), order.len()));
+                        stack.push(callee);
+                        on_stack.insert(callee);
+                        frames.push((callee, callees(callee).iter()));
+                    }
+                    // The callee is part of the component that is being
+                    // visited.
+                    Some(callee) if on_stack.contains(&callee) => {
+                        let visited = order[&callee].0;
+                        let low = &mut order.get_mut(&function).expect("Visited").1;
+                        *low = (*low).min(visited);
+                    }
+                    Some(_) => {}
+                    None => {
+                        frames.pop();
+                        let (visited, low) = order[&function];
+                        if let Some((parent, _)) = frames.last() {
+                            let parent = &mut order.get_mut(parent).expect("Visited").1;
+                            *parent = (*parent).min(low);
+                        }
+                        if visited != low {
+                            continue;
+                        }
+                        let mut component = BTreeSet::new();
+                        while let Some(member) = stack.pop() {
+                            on_stack.remove(&member);
+                            component.insert(member);
+                            if member == function {
+                                break;
+                            }
+                        }
+                        if component.len() > 1 || callees(function).contains(&function) {
+                            components.push(component);
+                        }
+                    }
+                }
+            }
+        }
+        components.sort();
+        components
+    }
+
+    /// Returns the functions that are called by the function.
+    pub fn callees(&self, function: u32) -> BTreeSet<u32> {
+        self.calls
+            .iter()
+            .filter(|call| call.caller == function)
+            .flat_map(|call| call.callee.targets())
+            .collect()
+    }
+
+    /// Returns the functions that call the function.
+    pub fn callers(&self, function: u32) -> BTreeSet<u32> {
+        self.calls
+            .iter()
+            .filter(|call| call.callee.contains(function))
+            .map(|call| call.caller)
+            .collect()
+    }
+
+    /// Returns the functions that can be reached through one or more calls
+    /// from the function.
+    pub fn reachable(&self, function: u32) -> BTreeSet<u32> {
+        let mut reachable = BTreeSet::new();
+        let mut work: Vec<u32> = self.callees(function).into_iter().collect();
+        while let Some(function) = work.pop() {
+            if reachable.insert(function) {
+                work.extend(self.callees(function));
+            }
+        }
+        reachable
+    }
+
+    /// Returns the groups of mutually recursive functions, a function that
+    /// calls itself forms a group of its own.
+    pub fn recursion(&self) -> &[BTreeSet<u32>] {
+        &self.recursion
+    }
+
+    /// Returns the group of mutually recursive functions that the function
+    /// is part of, if any.
+    pub fn recursive_group(&self, function: u32) -> Option<&BTreeSet<u32>> {
+        self.groups
+            .get(&function)
+            .map(|index| &self.recursion[*index])
+    }
+
+    /// Returns true if the function can call itself, directly or through
+    /// other functions.
+    pub fn is_recursive(&self, function: u32) -> bool {
+        self.groups.contains_key(&function)
+    }
+
+    /// Returns true if there are indirect calls from the function that could
+    /// not be resolved.
+    pub fn has_unresolved(&self, function: u32) -> bool {
+        self.calls
+            .iter()
+            .any(|call| call.caller == function && call.callee == Callee::Unresolved)
+    }
+
+    /// Exports the graph in the Graphviz DOT format.
+    ///
+    /// Unresolved calls are drawn to a shared `unknown` node, indirect calls
+    /// are dashed and tail calls are dotted.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph calls {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for function in &self.functions {
+            let _ = writeln!(dot, "    f{function:08x} [label=\"{function:#010x}\"];");
+        }
+        if self
+            .calls
+            .iter()
+            .any(|call| call.callee == Callee::Unresolved)
+        {
+            dot.push_str("    unknown [shape=point];\n");
+        }
+        for call in &self.calls {
+            let callees = match &call.callee {
+                Callee::Resolved(targets) => targets
+                    .iter()
+                    .map(|callee| format!("f{callee:08x}"))
+                    .collect(),
+                Callee::Unresolved => vec!["unknown".to_string()],
+            };
+            let style = match call.kind {
+                CallKind::Direct => "",
+                CallKind::Indirect => ", style=dashed",
+                CallKind::TailCall => ", style=dotted",
+            };
+            for callee in callees {
+                let _ = writeln!(
+                    dot,
+                    "    f{:08x} -> {callee} [label=\"{:#x}\"{style}];",
+                    call.caller, call.site
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph as JSON.
+    ///
+    /// The output is an object with a list of `functions`, each with its
+    /// `address` and whether it is `recursive`, and a list of `calls` with
+    /// the `site`, `caller`, `callees` and `kind` of each call. The callees
+    /// of unresolved calls are `null`.
+    pub fn to_json(&self) -> String {
+        let functions: Vec<String> = self
+            .functions
+            .iter()
+            .map(|function| {
+                format!(
+                    "{{\"address\":{function},\"recursive\":{}}}",
+                    self.is_recursive(*function)
+                )
+            })
+            .collect();
+        let calls: Vec<String> = self
+            .calls
+            .iter()
+            .map(|call| {
+                let callees = match &call.callee {
+                    Callee::Resolved(targets) => format!(
+                        "[{}]",
+                        targets
+                            .iter()
+                            .map(|target| target.to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    ),
+                    Callee::Unresolved => "null".to_string(),
+                };
+                format!(
+                    "{{\"site\":{},\"caller\":{},\"callees\":{callees},\"kind\":\"{}\"}}",
+                    call.site,
+                    call.caller,
+                    call.kind.label()
+                )
+            })
+            .collect();
+        format!(
+            "{{\"functions\":[{}],\"calls\":[{}]}}",
+            functions.join(","),
+            calls.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        cfg::{
+            call_graph::{Call, CallKind, Callee},
+            CallGraph,
+            Cfg,
+            Constants,
+        },
+        disassembler::Disassembler,
+        helpers::test::code,
+        loader::Region,
+    };
+
+    fn graph<I: IntoIterator<Item = u32>>(halfwords: &[u16], entries: I) -> CallGraph {
+        let code = code(halfwords);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entries(entries)
+            .run();
+        let cfg = Cfg::new(&disassembly);
+        CallGraph::new(&cfg, &Constants::new(&cfg, &disassembly))
+    }
+
+    fn call(site: u32, caller: u32, callee: Option<u32>, kind: CallKind) -> Call {
+        Call {
+            site,
+            caller,
+            callee: match callee {
+                Some(callee) => Callee::Resolved(BTreeSet::from([callee])),
+                None => Callee::Unresolved,
+            },
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_direct() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0xf000, 0xf804, // 0x02 bl 0x0e
+                0xf000, 0xf803, // 0x06 bl 0x10
+                0xbd10, // 0x0a pop {r4, pc}
+                0xbf00, // 0x0c nop
+                0x4770, // 0x0e bx lr
+                0xf7ff, 0xfffd, // 0x10 bl 0x0e
+                0x4770, // 0x14 bx lr
+            ],
+            [0x00],
+        );
+        assert_eq!(graph.calls, vec![
+            call(0x02, 0x00, Some(0x0e), CallKind::Direct),
+            call(0x06, 0x00, Some(0x10), CallKind::Direct),
+            call(0x10, 0x10, Some(0x0e), CallKind::Direct),
+        ]);
+        assert_eq!(graph.functions, BTreeSet::from([0x00, 0x0e, 0x10]));
+        assert_eq!(graph.callees(0x00), BTreeSet::from([0x0e, 0x10]));
+        assert_eq!(graph.callers(0x0e), BTreeSet::from([0x00, 0x10]));
+        assert!(!graph.has_unresolved(0x00));
+        assert!(graph.recursion().is_empty());
+    }
+
+    #[test]
+    fn test_indirect() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0x4b02, // 0x02 ldr r3, [pc, #8]
+                0x4798, // 0x04 blx r3
+                0x4790, // 0x06 blx r2
+                0xbd10, // 0x08 pop {r4, pc}
+                0x4770, // 0x0a bx lr
+                0x000b, 0x0000, // 0x0c .word 0x0b
+            ],
+            [0x00],
+        );
+        assert_eq!(graph.calls, vec![
+            call(0x04, 0x00, Some(0x0a), CallKind::Indirect),
+            call(0x06, 0x00, None, CallKind::Indirect),
+        ]);
+        assert_eq!(graph.calls[1].callee.targets().count(), 0);
+        assert_eq!(graph.callers(0x0a), BTreeSet::from([0x00]));
+        assert!(graph.has_unresolved(0x00));
+        assert!(!graph.has_unresolved(0x0a));
+    }
+
+    #[test]
+    fn test_tail_call() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0xf000, 0xf801, // 0x02 bl 0x08
+                0xbd10, // 0x06 pop {r4, pc}
+                0x2001, // 0x08 movs r0, #1
+                0xe7ff, // 0x0a b 0x0c
+                0x4770, // 0x0c bx lr
+            ],
+            [0x00, 0x0c],
+        );
+        assert_eq!(graph.calls, vec![
+            call(0x02, 0x00, Some(0x08), CallKind::Direct),
+            call(0x0a, 0x08, Some(0x0c), CallKind::TailCall),
+        ]);
+        assert_eq!(graph.reachable(0x00), BTreeSet::from([0x08, 0x0c]));
+    }
+
+    #[test]
+    fn test_recursion() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0xf000, 0xf803, // 0x02 bl 0x0c
+                0xf000, 0xf80a, // 0x06 bl 0x1e
+                0xbd10, // 0x0a pop {r4, pc}
+                0xb510, // 0x0c push {r4, lr}
+                0xf000, 0xf801, // 0x0e bl 0x14
+                0xbd10, // 0x12 pop {r4, pc}
+                0x2800, // 0x14 cmp r0, #0
+                0xd001, // 0x16 beq 0x1c
+                0xf7ff, 0xfff8, // 0x18 bl 0x0c
+                0x4770, // 0x1c bx lr
+                0xb510, // 0x1e push {r4, lr}
+                0xf7ff, 0xfffd, // 0x20 bl 0x1e
+                0xbd10, // 0x24 pop {r4, pc}
+            ],
+            [0x00],
+        );
+        assert_eq!(graph.recursion(), vec![
+            BTreeSet::from([0x0c, 0x14]),
+            BTreeSet::from([0x1e])
+        ]);
+        assert!(!graph.is_recursive(0x00));
+        assert!(graph.is_recursive(0x0c));
+        assert!(graph.is_recursive(0x1e));
+        assert_eq!(
+            graph.recursive_group(0x14),
+            Some(&BTreeSet::from([0x0c, 0x14]))
+        );
+        assert_eq!(graph.recursive_group(0x00), None);
+        assert_eq!(graph.reachable(0x00), BTreeSet::from([0x0c, 0x14, 0x1e]));
+    }
+
+    #[test]
+    fn test_export() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0xf000, 0xf802, // 0x02 bl 0x0a
+                0x4790, // 0x06 blx r2
+                0xe7ff, // 0x08 b 0x0a
+                0xb510, // 0x0a push {r4, lr}
+                0xf7ff, 0xfffd, // 0x0c bl 0x0a
+                0xbd10, // 0x10 pop {r4, pc}
+            ],
+            [0x00],
+        );
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph calls {\n"));
+        assert!(dot.contains("    unknown [shape=point];\n"));
+        assert!(dot.contains("f00000000 -> f0000000a [label=\"0x2\"];"));
+        assert!(dot.contains("f00000000 -> unknown [label=\"0x6\", style=dashed];"));
+        assert!(dot.contains("f00000000 -> f0000000a [label=\"0x8\", style=dotted];"));
+
+        let json = graph.to_json();
+        assert!(json.starts_with("{\"functions\":[{\"address\":0,\"recursive\":false},"));
+        assert!(json.contains("{\"address\":10,\"recursive\":true}],\"calls\""));
+        assert!(json.contains("{\"site\":6,\"caller\":0,\"callees\":null,\"kind\":\"indirect\"}"));
+        assert!(json.contains("{\"site\":8,\"caller\":0,\"callees\":[10],\"kind\":\"tail\"}"));
+        assert!(
+            json.ends_with("{\"site\":12,\"caller\":10,\"callees\":[10],\"kind\":\"direct\"}]}")
+        );
+    }
+
+    #[test]
+    fn test_table_tail_call() {
+        let graph = graph(
+            &[
+                0x2801, // 0x00 cmp r0, #1
+                0xd804, // 0x02 bhi 0x0e
+                0xe8df, 0xf000, // 0x04 tbb [pc, r0]
+                0x0503, // 0x08 table
+                0x0000, // 0x0a padding
+                0x4770, // 0x0c bx lr
+                0x4770, // 0x0e bx lr
+                0x4770, // 0x10 bx lr
+                0xe7f5, // 0x12 b 0x00
+            ],
+            [0x00, 0x0e, 0x12],
+        );
+
+        // Both entries of the table are other functions.
+        let table: Vec<&Call> = graph
+            .calls
+            .iter()
+            .filter(|call| call.site == 0x04)
+            .collect();
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].kind, CallKind::TailCall);
+        assert_eq!(
+            table[0].callee,
+            Callee::Resolved(BTreeSet::from([0x0e, 0x12]))
+        );
+        assert_eq!(graph.callers(0x12), BTreeSet::from([0x00]));
+        assert!(graph
+            .to_dot()
+            .contains("f00000000 -> f00000012 [label=\"0x4\", style=dotted];"));
+
+        // The tail calls through the table and back form a cycle.
+        assert_eq!(graph.recursion(), &[BTreeSet::from([0x00, 0x12])]);
+        assert!(!graph.is_recursive(0x0e));
+    }
+
+    #[test]
+    fn test_indirect_targets() {
+        let graph = graph(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0x4b04, // 0x02 ldr r3, [pc, #16]
+                0x2800, // 0x04 cmp r0, #0
+                0xbf08, // 0x06 it eq
+                0x4b03, // 0x08 ldreq r3, [pc, #12]
+                0x4798, // 0x0a blx r3
+                0xbd10, // 0x0c pop {r4, pc}
+                0xbf00, // 0x0e nop
+                0x4770, // 0x10 bx lr
+                0xbf00, // 0x12 nop
+                0x0011, 0x0000, // 0x14 .word 0x11
+                0x0001, 0x0000, // 0x18 .word 0x01
+            ],
+            [0x00, 0x10],
+        );
+
+        // The call enters either the leaf or the caller itself.
+        assert_eq!(graph.calls, vec![Call {
+            site: 0x0a,
+            caller: 0x00,
+            callee: Callee::Resolved(BTreeSet::from([0x00, 0x10])),
+            kind: CallKind::Indirect,
+        }]);
+        assert_eq!(graph.recursion(), &[BTreeSet::from([0x00])]);
+        assert!(graph.is_recursive(0x00));
+        assert!(!graph.is_recursive(0x10));
+    }
+
+    #[test]
+    fn test_components() {
+        // A chain of calls where every function calls back to the first one,
+        // along with a function that is called by the chain.
+        let mut graph = CallGraph {
+            functions: (0..100).collect(),
+            ..Default::default()
+        };
+        let call = |caller, callee| Call {
+            site: caller,
+            caller,
+            callee: Callee::Resolved(BTreeSet::from([callee])),
+            kind: CallKind::Direct,
+        };
+        graph.calls = (0..98)
+            .map(|function| call(function, function + 1))
+            .collect();
+        graph.calls.push(call(97, 0));
+        graph.calls.push(call(50, 98));
+        graph.calls.push(call(99, 99));
+        let components = graph.components();
+        assert_eq!(components, vec![
+            (0..98).collect::<BTreeSet<u32>>(),
+            BTreeSet::from([99])
+        ]);
+    }
+}
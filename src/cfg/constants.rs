@@ -0,0 +1,449 @@
+//! Propagates constants within basic blocks.
+//!
+//! Compilers materialise 32 bit addresses with pairs of instructions such as
+//! `movw r0, #0xe100; movt r0, #0xe000` or `adr r0, table; adds r0, #8`.
+//! [`Constants`] evaluates the [lifted](crate::lift) instructions of each
+//! basic block to reconstruct these values, and to find the memory that is
+//! accessed through them. This makes it possible to find all of the code that
+//! touches a peripheral register such as `0xE000E100`.
+//!
+//! The propagation is local to each block, all registers are unknown at the
+//! start of a block. A register that is conditionally overwritten inside an
+//! IT block is unknown afterwards, but the values that it can hold are kept
+//! so that indirect branches through it resolve to all of their targets.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::Cfg;
+use crate::{
+    arch::{Condition, Register},
+    disassembler::{Disassembly, Flow, Instruction, Literal},
+    lift::{
+        ir::{Local, Statement, Value},
+        Context,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A memory access with a known address.
+pub struct Access {
+    /// The address that is accessed.
+    pub address: u32,
+    /// The number of bytes that are accessed.
+    pub size: u32,
+    /// True if the access is a store.
+    pub write: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The constants that are computed by the instructions in a [`Cfg`].
+pub struct Constants {
+    /// The values that are completed by an instruction from registers that
+    /// were set earlier in the block, such as a `movt` following a `movw`,
+    /// indexed by the address of the instruction.
+    pub values: BTreeMap<u32, u32>,
+    /// The memory accesses with known addresses indexed by the address of the
+    /// instruction, loads from literal pools are not included.
+    pub accesses: BTreeMap<u32, Vec<Access>>,
+    /// The targets of the indirect branches and calls whose register is
+    /// known, such as `ldr r3, =handler; blx r3`, indexed by the address of
+    /// the instruction. A register that was conditionally overwritten has
+    /// several targets. The Thumb bit is cleared.
+    pub targets: BTreeMap<u32, BTreeSet<u32>>,
+    /// The values that are written to the PC by the indirect branches, calls
+    /// and returns whose source is known, indexed by the address of the
+    /// instruction. Unlike [`targets`](Self::targets) the values are kept
+    /// as is, such as the EXC_RETURN value in `ldr lr, =0xfffffffd; bx lr`.
+    pub branches: BTreeMap<u32, u32>,
+}
+
+#[derive(Debug, Default)]
+/// Evaluates the statements of a single basic block.
+struct Evaluator {
+    registers: [Option<u32>; 16],
+    /// The values that the registers which are unknown due to a predicated
+    /// write can hold.
+    alternatives: [BTreeSet<u32>; 16],
+    locals: HashMap<usize, u64>,
+    /// The values that the locals read from such registers can hold.
+    choices: HashMap<usize, BTreeSet<u32>>,
+}
+
+impl Constants {
+    /// Propagates the constants in all of the blocks of the graph.
+    pub fn new(cfg: &Cfg, disassembly: &Disassembly) -> Self {
+        let mut constants = Self::default();
+        for block in cfg.blocks.values() {
+            let mut evaluator = Evaluator::default();
+            for address in &block.instructions {
+                let Some(instruction) = disassembly.instruction(*address) else {
+                    continue;
+                };
+                evaluator.instruction(
+                    *address,
+                    instruction,
+                    disassembly.literals.get(address),
+                    &mut constants,
+                );
+            }
+        }
+        constants
+    }
+
+    /// Returns the value that is completed by the instruction at `address`.
+    pub fn value(&self, address: u32) -> Option<u32> {
+        self.values.get(&address).copied()
+    }
+
+    /// Returns the addresses of the instructions that access any of the
+    /// `size` bytes starting at `address`.
+    pub fn touching(&self, address: u32, size: u32) -> Vec<u32> {
+        let end = address as u64 + size as u64;
+        self.accesses
+            .iter()
+            .filter(|(_, accesses)| {
+                accesses.iter().any(|access| {
+                    (access.address as u64) < end
+                        && (address as u64) < access.address as u64 + access.size as u64
+                })
+            })
+            .map(|(instruction, _)| *instruction)
+            .collect()
+    }
+
+    /// Describes the value that is completed by the instruction at
+    /// `address`, i.e. `=0xe000e100` or `=0x20000010 <buffer+0x10>`.
+    #[cfg(feature = "listing")]
+    pub fn describe<S: crate::listing::SymbolTable>(
+        &self,
+        address: u32,
+        symbols: &S,
+    ) -> Option<String> {
+        let value = self.value(address)?;
+        Some(match symbols.describe(value) {
+            Some(symbol) => format!("={value:#x} <{symbol}>"),
+            None => format!("={value:#x}"),
+        })
+    }
+}
+
+impl Evaluator {
+    /// Evaluates the instruction and records the values and accesses that it
+    /// produces.
+    fn instruction(
+        &mut self,
+        address: u32,
+        instruction: &Instruction,
+        literal: Option<&Literal>,
+        constants: &mut Constants,
+    ) {
+        // A predicated instruction might not be executed, so anything that it
+        // writes is unknown.
+        let predicated = instruction.condition != Condition::None;
+        let context = Context {
+            in_it_block: predicated,
+            size: instruction.size,
+        };
+        let flow = instruction.flow(address);
+        let mut derived = false;
+        let mut completed = None;
+        self.locals.clear();
+        self.choices.clear();
+        for statement in instruction.operation.lift(&context) {
+            match statement {
+                Statement::Assign { dst, expression } => {
+                    if let Some(value) = expression.evaluate(|local| self.local(local)) {
+                        self.locals.insert(dst.id, value & dst.width.mask());
+                    }
+                }
+                Statement::ReadRegister { dst, register } => {
+                    let value = match register {
+                        Register::PC => Some(address.wrapping_add(4)),
+                        register => self.registers[u8::from(register) as usize],
+                    };
+                    match value {
+                        Some(value) => {
+                            derived |= register != Register::PC;
+                            self.locals.insert(dst.id, value as u64);
+                        }
+                        None => {
+                            let alternatives = &self.alternatives[u8::from(register) as usize];
+                            if !alternatives.is_empty() {
+                                self.choices.insert(dst.id, alternatives.clone());
+                            }
+                        }
+                    }
+                }
+                Statement::WriteRegister { register, value } => {
+                    let index = u8::from(register) as usize;
+                    let value = self.value(&value).map(|value| value as u32);
+                    // The register keeps its previous value if a predicated
+                    // write is skipped.
+                    let mut previous = std::mem::take(&mut self.alternatives[index]);
+                    previous.extend(self.registers[index]);
+                    match value {
+                        Some(value) if !predicated => {
+                            self.registers[index] = Some(value);
+                            completed = Some(value);
+                        }
+                        Some(value) if !previous.is_empty() => {
+                            previous.insert(value);
+                            self.alternatives[index] = previous;
+                            self.registers[index] = None;
+                        }
+                        _ => self.registers[index] = None,
+                    }
+                }
+                Statement::Load {
+                    dst,
+                    address: target,
+                } => {
+                    let Some(target) = self.value(&target) else {
+                        continue;
+                    };
+                    let target = target as u32;
+                    let size = dst.width.bits() / 8;
+                    match literal.filter(|literal| literal.contains(target)) {
+                        Some(literal) => {
+                            if let Some(value) = literal.value {
+                                let offset = target.wrapping_sub(literal.address) * 8;
+                                self.locals
+                                    .insert(dst.id, (value >> offset) & dst.width.mask());
+                            }
+                        }
+                        None => constants.accesses.entry(address).or_default().push(Access {
+                            address: target,
+                            size,
+                            write: false,
+                        }),
+                    }
+                }
+                Statement::Store {
+                    address: target,
+                    value,
+                }
+                | Statement::StoreExclusive {
+                    address: target,
+                    value,
+                    ..
+                } => {
+                    if let Some(target) = self.value(&target) {
+                        constants.accesses.entry(address).or_default().push(Access {
+                            address: target as u32,
+                            size: value.width().bits() / 8,
+                            write: true,
+                        });
+                    }
+                }
+                Statement::Branch { target, .. }
+                    if matches!(flow, Flow::Indirect | Flow::IndirectCall | Flow::Return) =>
+                {
+                    let targets = match self.value(&target) {
+                        Some(target) => {
+                            constants.branches.insert(address, target as u32);
+                            BTreeSet::from([target as u32])
+                        }
+                        None => match target {
+                            Value::Local(local) => match self.choices.get(&local.id) {
+                                Some(choices) => choices.clone(),
+                                None => continue,
+                            },
+                            Value::Constant(..) => continue,
+                        },
+                    };
+                    if flow != Flow::Return {
+                        constants
+                            .targets
+                            .insert(address, targets.iter().map(|target| target & !1).collect());
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Branches write the link register or the PC, neither of which are
+        // constants that the instruction completes.
+        if let Some(value) = completed.filter(|_| derived && flow == Flow::Sequential) {
+            constants.values.insert(address, value);
+        }
+    }
+
+    fn value(&self, value: &Value) -> Option<u64> {
+        value.evaluate(|local| self.local(local))
+    }
+
+    fn local(&self, local: Local) -> Option<u64> {
+        self.locals.get(&local.id).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        cfg::{constants::Access, Cfg, Constants},
+        disassembler::Disassembler,
+        helpers::test::code,
+        loader::Region,
+    };
+
+    fn constants(halfwords: &[u16]) -> Constants {
+        let code = code(halfwords);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        Constants::new(&Cfg::new(&disassembly), &disassembly)
+    }
+
+    #[test]
+    fn test_movw_movt() {
+        let constants = constants(&[
+            0xf24e, 0x1000, // 0x00 movw r0, #0xe100
+            0xf2ce, 0x0000, // 0x04 movt r0, #0xe000
+            0x4770, // 0x08 bx lr
+        ]);
+        assert_eq!(
+            constants.values.into_iter().collect::<Vec<_>>(),
+            vec![(0x04, 0xe000_e100)]
+        );
+    }
+
+    #[test]
+    fn test_adr() {
+        let constants = constants(&[
+            0xa201, // 0x00 adr r2, 0x08
+            0x3204, // 0x02 adds r2, #4
+            0x6813, // 0x04 ldr r3, [r2]
+            0x4770, // 0x06 bx lr
+            0x0000, 0x0000, // 0x08 .word 0
+            0x0000, 0x0000, // 0x0c .word 0
+        ]);
+        // The address is taken from the PC, so the adr completes nothing.
+        assert_eq!(constants.value(0x00), None);
+        assert_eq!(constants.value(0x02), Some(0x0c));
+        assert_eq!(constants.accesses[&0x04], vec![Access {
+            address: 0x0c,
+            size: 4,
+            write: false
+        }]);
+    }
+
+    #[test]
+    fn test_store() {
+        let constants = constants(&[
+            0xf24e, 0x1000, // 0x00 movw r0, #0xe100
+            0xf2ce, 0x0000, // 0x04 movt r0, #0xe000
+            0x2101, // 0x08 movs r1, #1
+            0x6001, // 0x0a str r1, [r0]
+            0x4770, // 0x0c bx lr
+        ]);
+        assert_eq!(constants.accesses.len(), 1);
+        assert_eq!(constants.accesses[&0x0a], vec![Access {
+            address: 0xe000_e100,
+            size: 4,
+            write: true
+        }]);
+        assert_eq!(constants.touching(0xe000_e100, 4), vec![0x0a]);
+        assert_eq!(constants.touching(0xe000_e0fd, 4), vec![0x0a]);
+        assert!(constants.touching(0xe000_e0fc, 4).is_empty());
+    }
+
+    #[test]
+    fn test_literal_base() {
+        let constants = constants(&[
+            0x4c01, // 0x00 ldr r4, [pc, #4]
+            0x69a5, // 0x02 ldr r5, [r4, #24]
+            0x4770, // 0x04 bx lr
+            0xbf00, // 0x06 nop
+            0x1000, 0x4002, // 0x08 .word 0x40021000
+        ]);
+        // The load from the literal pool is not an access.
+        assert_eq!(constants.accesses.len(), 1);
+        assert_eq!(constants.accesses[&0x02][0].address, 0x4002_1018);
+        assert_eq!(constants.touching(0x4002_1000, 0x400), vec![0x02]);
+    }
+
+    #[test]
+    fn test_predicated() {
+        let constants = constants(&[
+            0xf24e, 0x1000, // 0x00 movw r0, #0xe100
+            0xf2ce, 0x0000, // 0x04 movt r0, #0xe000
+            0x2101, // 0x08 movs r1, #1
+            0xbf08, // 0x0a it eq
+            0x4606, // 0x0c moveq r6, r0
+            0x6031, // 0x0e str r1, [r6]
+            0x4770, // 0x10 bx lr
+        ]);
+        // r6 is only known if the move is executed.
+        assert!(constants.accesses.is_empty());
+    }
+
+    #[test]
+    fn test_block_local() {
+        let constants = constants(&[
+            0xf24e, 0x1000, // 0x00 movw r0, #0xe100
+            0xf2ce, 0x0000, // 0x04 movt r0, #0xe000
+            0xe7ff, // 0x08 b 0x0a
+            0x6807, // 0x0a ldr r7, [r0]
+            0x4770, // 0x0c bx lr
+        ]);
+        // All registers are unknown at the start of the next block.
+        assert!(constants.accesses.is_empty());
+    }
+
+    #[test]
+    fn test_targets() {
+        let constants = constants(&[
+            0x4b01, // 0x00 ldr r3, [pc, #4]
+            0x4798, // 0x02 blx r3
+            0x4770, // 0x04 bx lr
+            0xbf00, // 0x06 nop
+            0x0021, 0x0000, // 0x08 .word 0x21
+        ]);
+        assert_eq!(constants.targets[&0x02], BTreeSet::from([0x20]));
+        assert_eq!(constants.branches[&0x02], 0x21);
+    }
+
+    #[test]
+    fn test_conditional_targets() {
+        let constants = constants(&[
+            0x4b02, // 0x00 ldr r3, [pc, #8]
+            0x2800, // 0x02 cmp r0, #0
+            0xbf08, // 0x04 it eq
+            0x4b02, // 0x06 ldreq r3, [pc, #8]
+            0x4798, // 0x08 blx r3
+            0x4770, // 0x0a bx lr
+            0x0021, 0x0000, // 0x0c .word 0x21
+            0x0031, 0x0000, // 0x10 .word 0x31
+        ]);
+        assert_eq!(constants.targets[&0x08], BTreeSet::from([0x20, 0x30]));
+        // The value that is written to the PC is not known.
+        assert!(!constants.branches.contains_key(&0x08));
+    }
+
+    #[cfg(feature = "listing")]
+    #[test]
+    fn test_describe() {
+        use crate::listing::Symbols;
+
+        let mut symbols = Symbols::new();
+        symbols.insert("data", 0x08, 8);
+        let adr = constants(&[
+            0xa201, // 0x00 adr r2, 0x08
+            0x3204, // 0x02 adds r2, #4
+            0x4770, // 0x04 bx lr
+            0xbf00, // 0x06 nop
+            0x0000, 0x0000, // 0x08 .word 0
+            0x0000, 0x0000, // 0x0c .word 0
+        ]);
+        assert_eq!(adr.describe(0x02, &symbols).unwrap(), "=0xc <data+0x4>");
+        assert_eq!(adr.describe(0x00, &symbols), None);
+
+        let movt = constants(&[
+            0xf24e, 0x1000, // 0x00 movw r0, #0xe100
+            0xf2ce, 0x0000, // 0x04 movt r0, #0xe000
+            0x4770, // 0x08 bx lr
+        ]);
+        assert_eq!(movt.describe(0x04, &symbols).unwrap(), "=0xe000e100");
+    }
+}
@@ -0,0 +1,330 @@
+//! Finds the instructions that return from exceptions or change the
+//! privilege of the processor.
+//!
+//! On Cortex-M an exception returns when an EXC_RETURN value, `0xFFFFFFxx`,
+//! is written to the PC by `bx`, `pop {.., pc}` or `ldm`. Context switches in
+//! an RTOS combine these with writes to `CONTROL`, `PSP` and `MSP`, `cps`
+//! and `svc`. [`Exceptions`] collects all of these [`Site`]s so that such
+//! code can be audited.
+//!
+//! An EXC_RETURN value is recognised if it is known from [`Constants`], or if
+//! a handler returns, since the link register holds an EXC_RETURN value when
+//! a handler is entered.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
+
+use super::{Cfg, Constants};
+use crate::{
+    arch::{ApsrMask, SpecialRegister},
+    disassembler::{Disassembly, Flow},
+    operation::Operation,
+};
+
+/// Values written to the PC that are at least this large are EXC_RETURN
+/// values.
+const EXC_RETURN: u32 = 0xffff_ff00;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerates the kinds of sites.
+pub enum Site {
+    /// Returns from an exception.
+    ExceptionReturn {
+        /// The EXC_RETURN value, None if it is only known that a handler
+        /// returns.
+        value: Option<u32>,
+    },
+    /// Reads a special register with `mrs`.
+    ReadSpecial(SpecialRegister),
+    /// Writes a special register with `msr`.
+    WriteSpecial(SpecialRegister, ApsrMask),
+    /// Enables or disables exceptions with `cps`.
+    ChangeProcessorState {
+        /// True for `cpsie`, false for `cpsid`.
+        enable: bool,
+        /// True if PRIMASK is affected.
+        affect_pri: bool,
+        /// True if FAULTMASK is affected.
+        affect_fault: bool,
+    },
+    /// Calls the supervisor with `svc`.
+    SupervisorCall(u8),
+}
+
+#[derive(Debug, Clone, Default)]
+/// The exception returns and privileged instructions in a [`Cfg`].
+pub struct Exceptions {
+    /// The sites indexed by the address of the instruction.
+    pub sites: BTreeMap<u32, Site>,
+    /// The functions that contain each site, indexed by the address of the
+    /// instruction.
+    pub functions: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl Site {
+    /// Returns true if the site can change the privilege or the stack
+    /// pointer that is used, or mask exceptions.
+    pub const fn changes_privilege(&self) -> bool {
+        match self {
+            Self::ExceptionReturn { .. }
+            | Self::ChangeProcessorState { .. }
+            | Self::SupervisorCall(_) => true,
+            Self::WriteSpecial(register, _) => register.is_stack_or_privilege(),
+            Self::ReadSpecial(_) => false,
+        }
+    }
+
+    /// Returns the name of the special register that is accessed, including
+    /// the flags for writes to the APSR, i.e. `APSR_nzcvq`.
+    pub fn register(&self) -> Option<String> {
+        Some(match self {
+            Self::ReadSpecial(register) => register.to_string(),
+            Self::WriteSpecial(register, mask) if register.has_flags() => {
+                format!("{register}_{mask}")
+            }
+            Self::WriteSpecial(register, _) => register.to_string(),
+            _ => return None,
+        })
+    }
+}
+
+impl Display for Site {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExceptionReturn { value: Some(value) } => {
+                write!(f, "exception return {value:#x}")
+            }
+            Self::ExceptionReturn { value: None } => write!(f, "exception return"),
+            Self::ReadSpecial(_) => write!(f, "mrs {}", self.register().unwrap_or_default()),
+            Self::WriteSpecial(..) => write!(f, "msr {}", self.register().unwrap_or_default()),
+            Self::ChangeProcessorState {
+                enable,
+                affect_pri,
+                affect_fault,
+            } => {
+                let op = if *enable { "cpsie" } else { "cpsid" };
+                let pri = if *affect_pri { "i" } else { "" };
+                let fault = if *affect_fault { "f" } else { "" };
+                write!(f, "{op} {pri}{fault}")
+            }
+            Self::SupervisorCall(imm) => write!(f, "svc #{imm}"),
+        }
+    }
+}
+
+impl Exceptions {
+    /// Finds the sites in all functions of the graph.
+    ///
+    /// `handlers` are the entries of the exception handlers, any return from
+    /// these is an exception return. The reset handler should not be
+    /// included as it runs in thread mode.
+    pub fn new<I: IntoIterator<Item = u32>>(
+        cfg: &Cfg,
+        disassembly: &Disassembly,
+        constants: &Constants,
+        handlers: I,
+    ) -> Self {
+        let handlers: BTreeSet<u32> = handlers.into_iter().map(|address| address & !1).collect();
+        let mut exceptions = Self::default();
+        for (entry, blocks) in &cfg.functions {
+            for address in blocks
+                .iter()
+                .flat_map(|block| &cfg.blocks[block].instructions)
+            {
+                let Some(instruction) = disassembly.instruction(*address) else {
+                    continue;
+                };
+                let branch = constants.branches.get(address).copied();
+                let site = match &instruction.operation {
+                    Operation::Mrs(el) => Site::ReadSpecial(el.sysm),
+                    Operation::Msr(el) => Site::WriteSpecial(el.sysm, el.mask),
+                    Operation::Cps(el) => Site::ChangeProcessorState {
+                        enable: el.enable && !el.disable,
+                        affect_pri: el.affect_pri,
+                        affect_fault: el.affect_fault,
+                    },
+                    Operation::Svc(el) => Site::SupervisorCall(el.imm),
+                    _ => match (instruction.flow(*address), branch) {
+                        (Flow::Indirect | Flow::Return, Some(value)) if value >= EXC_RETURN => {
+                            Site::ExceptionReturn { value: Some(value) }
+                        }
+                        (Flow::Return, None) if handlers.contains(entry) => {
+                            Site::ExceptionReturn { value: None }
+                        }
+                        _ => continue,
+                    },
+                };
+                exceptions.sites.insert(*address, site);
+                exceptions
+                    .functions
+                    .entry(*address)
+                    .or_default()
+                    .insert(*entry);
+            }
+        }
+        exceptions
+    }
+
+    /// Returns the exception returns and their EXC_RETURN values, if known.
+    pub fn exception_returns(&self) -> impl Iterator<Item = (u32, Option<u32>)> + '_ {
+        self.sites.iter().filter_map(|(address, site)| match site {
+            Site::ExceptionReturn { value } => Some((*address, *value)),
+            _ => None,
+        })
+    }
+
+    /// Returns the sites that can change the privilege or the stack pointer
+    /// that is used, see [`Site::changes_privilege`].
+    pub fn privileged(&self) -> impl Iterator<Item = (u32, &Site)> + '_ {
+        self.sites
+            .iter()
+            .filter(|(_, site)| site.changes_privilege())
+            .map(|(address, site)| (*address, site))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        arch::SpecialRegister,
+        cfg::{exception::Site, Cfg, Constants, Exceptions},
+        disassembler::Disassembler,
+        helpers::test::code,
+        loader::Region,
+    };
+
+    fn exceptions<H: IntoIterator<Item = u32>>(halfwords: &[u16], handlers: H) -> Exceptions {
+        let code = code(halfwords);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        let cfg = Cfg::new(&disassembly);
+        let constants = Constants::new(&cfg, &disassembly);
+        Exceptions::new(&cfg, &disassembly, &constants, handlers)
+    }
+
+    fn privileged(exceptions: &Exceptions) -> Vec<String> {
+        exceptions
+            .privileged()
+            .map(|(address, site)| format!("{address:#x}: {site}"))
+            .collect()
+    }
+
+    #[test]
+    fn test_context_switch() {
+        let exceptions = exceptions(
+            &[
+                0xf3ef, 0x8009, // 0x00 mrs r0, psp
+                0xe8b0, 0x0ff0, // 0x04 ldm.w r0!, {r4-r11}
+                0xf380, 0x8809, // 0x08 msr psp, r0
+                0xf04e, 0x0e04, // 0x0c orr lr, lr, #4
+                0x4770, // 0x10 bx lr
+            ],
+            [0x01],
+        );
+        // The handler returns with the value it was entered with.
+        assert_eq!(exceptions.exception_returns().collect::<Vec<_>>(), vec![(
+            0x10, None
+        )]);
+        assert_eq!(
+            exceptions.sites[&0x00],
+            Site::ReadSpecial(SpecialRegister::Psp)
+        );
+        assert_eq!(privileged(&exceptions), vec![
+            "0x8: msr PSP",
+            "0x10: exception return"
+        ]);
+    }
+
+    #[test]
+    fn test_thread_return() {
+        let exceptions = exceptions(
+            &[
+                0x4770, // 0x00 bx lr
+            ],
+            [],
+        );
+        assert!(exceptions.sites.is_empty());
+    }
+
+    #[test]
+    fn test_exc_return_lr() {
+        let exceptions = exceptions(
+            &[
+                0xf06f, 0x0e02, // 0x00 mvn lr, #2
+                0x4770, // 0x04 bx lr
+            ],
+            [],
+        );
+        assert_eq!(exceptions.exception_returns().collect::<Vec<_>>(), vec![(
+            0x04,
+            Some(0xffff_fffd)
+        )]);
+        assert_eq!(privileged(&exceptions), vec![
+            "0x4: exception return 0xfffffffd"
+        ]);
+    }
+
+    #[test]
+    fn test_exc_return_register() {
+        let exceptions = exceptions(
+            &[
+                0xf06f, 0x000e, // 0x00 mvn r0, #14
+                0x4700, // 0x04 bx r0
+            ],
+            [],
+        );
+        assert_eq!(exceptions.exception_returns().collect::<Vec<_>>(), vec![(
+            0x04,
+            Some(0xffff_fff1)
+        )]);
+    }
+
+    #[test]
+    fn test_pop_return() {
+        let exceptions = exceptions(
+            &[
+                0xb510, // 0x00 push {r4, lr}
+                0xb671, // 0x02 cpsid f
+                0xbd10, // 0x04 pop {r4, pc}
+            ],
+            [0x01],
+        );
+        assert_eq!(privileged(&exceptions), vec![
+            "0x2: cpsid f",
+            "0x4: exception return"
+        ]);
+        assert_eq!(exceptions.functions[&0x04].first(), Some(&0x00));
+    }
+
+    #[test]
+    fn test_special_registers() {
+        let exceptions = exceptions(
+            &[
+                0x2002, // 0x00 movs r0, #2
+                0xf380, 0x8814, // 0x02 msr control, r0
+                0xf3bf, 0x8f6f, // 0x06 isb sy
+                0xb662, // 0x0a cpsie i
+                0xf381, 0x8800, // 0x0c msr apsr_nzcvq, r1
+                0xf3ef, 0x8210, // 0x10 mrs r2, primask
+                0xdf01, // 0x14 svc #1
+                0x4770, // 0x16 bx lr
+            ],
+            [],
+        );
+        assert_eq!(privileged(&exceptions), vec![
+            "0x2: msr CONTROL",
+            "0xa: cpsie i",
+            "0x14: svc #1"
+        ]);
+        assert_eq!(
+            exceptions.sites[&0x0c].register().as_deref(),
+            Some("APSR_nzcvq")
+        );
+        assert!(!exceptions.sites[&0x0c].changes_privilege());
+        assert_eq!(exceptions.sites[&0x10].to_string(), "mrs PRIMASK");
+        assert!(!exceptions.sites.contains_key(&0x16));
+    }
+}
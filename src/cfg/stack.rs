@@ -0,0 +1,543 @@
+//! Computes the worst case stack depth of functions.
+//!
+//! The stack pointer is tracked as an offset from its value at the entry of
+//! the function by evaluating the [lifted](crate::lift) instructions of each
+//! basic block, so every instruction that writes the stack pointer is
+//! covered, such as `push`, `pop`, `stmdb sp!`, `sub sp, #imm`,
+//! `add sp, #imm` and `str rt, [sp, #-4]!`. The floating point `vpush` will
+//! be covered once the floating point extension is decoded.
+//!
+//! The depth of each function is then propagated over the [`CallGraph`],
+//! adding the depth of the callee to the depth at each call site. The result
+//! is [`StackDepth::Unbounded`] if it can not be bounded statically, see
+//! [`Reason`].
+
+use std::collections::BTreeMap;
+
+use super::{call_graph::CallGraph, Cfg, EdgeKind};
+use crate::{
+    arch::{Condition, Register},
+    disassembler::{Disassembly, Instruction},
+    lift::{
+        ir::{BinaryOp, Expression, Statement, UnaryOp, Value, Width},
+        Context,
+    },
+};
+
+/// The number of times that the state at the start of a block may change
+/// before the stack is assumed to grow without bound.
+const UPDATE_LIMIT: usize = 32;
+
+/// The registers that are not preserved by calls, as per the AAPCS.
+const CALLER_SAVED: [Register; 6] = [
+    Register::R0,
+    Register::R1,
+    Register::R2,
+    Register::R3,
+    Register::R12,
+    Register::LR,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerates the reasons that the stack depth can not be bounded.
+pub enum Reason {
+    /// The function at the address is recursive.
+    Recursion(u32),
+    /// The instruction at the address sets the stack pointer to a value that
+    /// is not a constant offset, such as `sub sp, r3`.
+    DynamicAllocation(u32),
+    /// The indirect call at the address could not be resolved.
+    IndirectCall(u32),
+    /// The stack grows every time that the block at the address is executed.
+    Growing(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The depth of the stack in bytes.
+pub enum StackDepth {
+    /// The stack never grows deeper than the given number of bytes.
+    Bounded(u32),
+    /// The depth can not be bounded.
+    Unbounded(Reason),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The stack usage of a single function, excluding its calls.
+pub struct Frame {
+    /// The deepest the stack gets within the function.
+    pub depth: StackDepth,
+    /// The depth before each instruction, indexed by the address of the
+    /// instruction.
+    pub depths: BTreeMap<u32, u32>,
+}
+
+#[derive(Debug, Clone)]
+/// The stack usage of all functions in a [`Cfg`].
+pub struct StackUsage {
+    /// The usage of each function, excluding its calls, indexed by the entry
+    /// of the function.
+    pub frames: BTreeMap<u32, Frame>,
+    /// The worst case depth of each function including its calls, indexed by
+    /// the entry of the function.
+    pub worst_case: BTreeMap<u32, StackDepth>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A symbolic value.
+enum Term {
+    /// A known value.
+    Constant(u64),
+    /// The stack pointer at the entry of the function plus an offset.
+    Stack(i32),
+}
+
+/// The values of the registers, None if a value is unknown.
+type State = [Option<Term>; 16];
+
+impl StackDepth {
+    /// Returns the depth if it is bounded.
+    pub const fn bound(&self) -> Option<u32> {
+        match self {
+            Self::Bounded(depth) => Some(*depth),
+            Self::Unbounded(_) => None,
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Bounded(lhs), Self::Bounded(rhs)) => Self::Bounded(lhs.max(rhs)),
+            (Self::Unbounded(reason), _) | (_, Self::Unbounded(reason)) => Self::Unbounded(reason),
+        }
+    }
+}
+
+impl StackUsage {
+    /// Computes the stack usage of all of the functions in the graph.
+    ///
+    /// The call graph is used to add the depth of the called functions.
+    pub fn new(cfg: &Cfg, disassembly: &Disassembly, calls: &CallGraph) -> Self {
+        let frames: BTreeMap<u32, Frame> = cfg
+            .functions
+            .keys()
+            .map(|entry| (*entry, Frame::new(cfg, disassembly, *entry)))
+            .collect();
+        let mut usage = Self {
+            frames,
+            worst_case: BTreeMap::new(),
+        };
+        for function in cfg.functions.keys() {
+            usage.propagate(calls, *function);
+        }
+        usage
+    }
+
+    /// Returns the worst case depth of the function including its calls.
+    pub fn depth(&self, function: u32) -> Option<StackDepth> {
+        self.worst_case.get(&function).copied()
+    }
+
+    fn propagate(&mut self, calls: &CallGraph, function: u32) -> StackDepth {
+        if let Some(depth) = self.worst_case.get(&function) {
+            return *depth;
+        }
+        let depth = match self.frames.get(&function) {
+            _ if calls.is_recursive(function) => StackDepth::Unbounded(Reason::Recursion(function)),
+            None => StackDepth::Unbounded(Reason::IndirectCall(function)),
+            Some(frame) => {
+                let mut depth = frame.depth;
+                let sites: Vec<(u32, Vec<u32>)> = calls
+                    .calls
+                    .iter()
+                    .filter(|call| call.caller == function)
+                    .map(|call| (call.site, call.callee.targets().collect()))
+                    .collect();
+                for (site, callees) in sites {
+                    let unknown = StackDepth::Unbounded(Reason::IndirectCall(site));
+                    // The deepest of the functions that can be called.
+                    let mut callee = match callees.is_empty() {
+                        true => unknown,
+                        false => StackDepth::Bounded(0),
+                    };
+                    for target in callees {
+                        callee = callee.max(match self.frames.contains_key(&target) {
+                            true => self.propagate(calls, target),
+                            false => unknown,
+                        });
+                    }
+                    let site = self.frames[&function]
+                        .depths
+                        .get(&site)
+                        .copied()
+                        .unwrap_or(0);
+                    depth = depth.max(match callee {
+                        StackDepth::Bounded(callee) => StackDepth::Bounded(site + callee),
+                        unbounded => unbounded,
+                    });
+                }
+                depth
+            }
+        };
+        self.worst_case.insert(function, depth);
+        depth
+    }
+}
+
+impl Frame {
+    /// Computes the stack usage of the function with the given entry.
+    pub fn new(cfg: &Cfg, disassembly: &Disassembly, entry: u32) -> Self {
+        let mut frame = Self {
+            depth: StackDepth::Bounded(0),
+            depths: BTreeMap::new(),
+        };
+        let Some(blocks) = cfg.functions.get(&entry) else {
+            return frame;
+        };
+        let mut initial: State = [None; 16];
+        initial[u8::from(Register::SP) as usize] = Some(Term::Stack(0));
+        let mut states: BTreeMap<u32, State> = BTreeMap::from([(entry, initial)]);
+        let mut updates: BTreeMap<u32, usize> = BTreeMap::new();
+        let mut work = vec![entry];
+        while let Some(block) = work.pop() {
+            let mut state = states[&block];
+            for address in &cfg.blocks[&block].instructions {
+                if let Some(depth) = depth(&state) {
+                    let previous = frame.depths.entry(*address).or_insert(depth);
+                    *previous = (*previous).max(depth);
+                    frame.depth = frame.depth.max(StackDepth::Bounded(depth));
+                }
+                if let Some(instruction) = disassembly.instruction(*address) {
+                    evaluate(&mut state, *address, instruction);
+                }
+                let Some(depth) = depth(&state) else {
+                    frame.depth = StackDepth::Unbounded(Reason::DynamicAllocation(*address));
+                    return frame;
+                };
+                frame.depth = frame.depth.max(StackDepth::Bounded(depth));
+            }
+
+            let edges: Vec<_> = cfg.successors(block).collect();
+            if edges.iter().any(|edge| edge.kind == EdgeKind::Call) {
+                for register in CALLER_SAVED {
+                    state[u8::from(register) as usize] = None;
+                }
+            }
+            for edge in edges {
+                let Some(to) = edge
+                    .to
+                    .filter(|to| edge.kind.is_local() && blocks.contains(to))
+                else {
+                    continue;
+                };
+                let merged = match states.get(&to) {
+                    Some(previous) => merge(previous, &state),
+                    None => state,
+                };
+                if states.get(&to) == Some(&merged) {
+                    continue;
+                }
+                let count = updates.entry(to).or_default();
+                *count += 1;
+                if *count > UPDATE_LIMIT {
+                    frame.depth = StackDepth::Unbounded(Reason::Growing(to));
+                    return frame;
+                }
+                states.insert(to, merged);
+                work.push(to);
+            }
+        }
+        frame
+    }
+}
+
+/// Returns the depth of the stack, None if the stack pointer is unknown.
+fn depth(state: &State) -> Option<u32> {
+    match state[u8::from(Register::SP) as usize] {
+        Some(Term::Stack(offset)) => Some(offset.min(0).unsigned_abs()),
+        _ => None,
+    }
+}
+
+/// Merges the states at the start of a block, keeping the deepest stack.
+fn merge(lhs: &State, rhs: &State) -> State {
+    let mut merged = *lhs;
+    for (index, (lhs, rhs)) in lhs.iter().zip(rhs).enumerate() {
+        merged[index] = match (lhs, rhs) {
+            (Some(Term::Stack(lhs)), Some(Term::Stack(rhs))) if index == 13 => {
+                Some(Term::Stack(*lhs.min(rhs)))
+            }
+            (lhs, rhs) if lhs == rhs => *lhs,
+            _ => None,
+        };
+    }
+    merged
+}
+
+/// Evaluates the instruction in the state.
+fn evaluate(state: &mut State, address: u32, instruction: &Instruction) {
+    // A predicated instruction might not be executed, so the stack pointer
+    // is kept at the deepest of the two outcomes and all other writes are
+    // unknown.
+    let predicated = instruction.condition != Condition::None;
+    let context = Context {
+        in_it_block: predicated,
+        size: instruction.size,
+    };
+    let mut locals: BTreeMap<usize, Term> = BTreeMap::new();
+    let value = |locals: &BTreeMap<usize, Term>, value: &Value| match value {
+        Value::Constant(value, width) => Some(Term::Constant(value & width.mask())),
+        Value::Local(local) => locals.get(&local.id).copied(),
+    };
+    for statement in instruction.operation.lift(&context) {
+        match statement {
+            Statement::Assign { dst, expression } => {
+                let term = match expression {
+                    Expression::Value(operand)
+                    | Expression::ZeroExtend(operand, _)
+                    | Expression::SignExtend(operand, _) => value(&locals, &operand),
+                    Expression::Extract {
+                        value: operand,
+                        high: 31,
+                        low: 0,
+                    } => value(&locals, &operand),
+                    Expression::Unary(op, operand) => match (op, value(&locals, &operand)) {
+                        (UnaryOp::Not, Some(Term::Constant(operand))) => {
+                            Some(Term::Constant(!operand))
+                        }
+                        _ => None,
+                    },
+                    Expression::Binary(op, lhs, rhs) => {
+                        binary(op, value(&locals, &lhs), value(&locals, &rhs))
+                    }
+                    _ => None,
+                };
+                if let Some(term) = term {
+                    let term = match term {
+                        Term::Constant(constant) => Term::Constant(constant & dst.width.mask()),
+                        stack => stack,
+                    };
+                    locals.insert(dst.id, term);
+                }
+            }
+            Statement::ReadRegister { dst, register } => {
+                let term = match register {
+                    Register::PC => Some(Term::Constant(address.wrapping_add(4) as u64)),
+                    register => state[u8::from(register) as usize],
+                };
+                if let Some(term) = term {
+                    locals.insert(dst.id, term);
+                }
+            }
+            Statement::WriteRegister {
+                register,
+                value: written,
+            } => {
+                let index = u8::from(register) as usize;
+                let written = value(&locals, &written);
+                state[index] = match (predicated, state[index], written) {
+                    (false, _, written) => written,
+                    (true, Some(Term::Stack(previous)), Some(Term::Stack(written)))
+                        if register == Register::SP =>
+                    {
+                        Some(Term::Stack(previous.min(written)))
+                    }
+                    (true, previous, written) if previous == written => written,
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Evaluates a binary operation on terms, only the operations that are used
+/// to compute stack offsets are supported.
+fn binary(op: BinaryOp, lhs: Option<Term>, rhs: Option<Term>) -> Option<Term> {
+    let offset = |constant: u64| constant as u32 as i32;
+    Some(match (op, lhs?, rhs?) {
+        (BinaryOp::Add, Term::Stack(stack), Term::Constant(constant))
+        | (BinaryOp::Add, Term::Constant(constant), Term::Stack(stack)) => {
+            Term::Stack(stack.wrapping_add(offset(constant)))
+        }
+        (BinaryOp::Sub, Term::Stack(stack), Term::Constant(constant)) => {
+            Term::Stack(stack.wrapping_sub(offset(constant)))
+        }
+        (BinaryOp::Sub, Term::Stack(lhs), Term::Stack(rhs)) => {
+            Term::Constant(lhs.wrapping_sub(rhs) as u32 as u64)
+        }
+        // Aligning the stack, i.e. `bic sp, #7`, can move it down by at most
+        // the bits that are cleared.
+        (BinaryOp::And, Term::Stack(stack), Term::Constant(mask))
+            if (!mask & Width::Word.mask()) < 0x100 =>
+        {
+            Term::Stack(stack.wrapping_sub((!mask & Width::Word.mask()) as i32))
+        }
+        (op, Term::Constant(lhs), Term::Constant(rhs)) => Term::Constant(match op {
+            BinaryOp::Add => lhs.wrapping_add(rhs),
+            BinaryOp::Sub => lhs.wrapping_sub(rhs),
+            BinaryOp::And => lhs & rhs,
+            BinaryOp::Or => lhs | rhs,
+            BinaryOp::Xor => lhs ^ rhs,
+            _ => return None,
+        }),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        cfg::{
+            stack::{Reason, StackDepth},
+            CallGraph,
+            Cfg,
+            Constants,
+            StackUsage,
+        },
+        disassembler::Disassembler,
+        helpers::test::code,
+        loader::Region,
+    };
+
+    fn usage<I: IntoIterator<Item = u32>>(halfwords: &[u16], entries: I) -> StackUsage {
+        let code = code(halfwords);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entries(entries)
+            .run();
+        let cfg = Cfg::new(&disassembly);
+        let calls = CallGraph::new(&cfg, &Constants::new(&cfg, &disassembly));
+        StackUsage::new(&cfg, &disassembly, &calls)
+    }
+
+    #[test]
+    fn test_frame() {
+        let usage = usage(
+            &[
+                0xb580, // 0x00 push {r7, lr}
+                0xb082, // 0x02 sub sp, #8
+                0xb002, // 0x04 add sp, #8
+                0xbd80, // 0x06 pop {r7, pc}
+            ],
+            [0x00],
+        );
+        assert_eq!(usage.frames[&0x00].depth, StackDepth::Bounded(16));
+        let depths: Vec<(u32, u32)> = usage.frames[&0x00]
+            .depths
+            .iter()
+            .map(|(address, depth)| (*address, *depth))
+            .collect();
+        assert_eq!(depths, vec![(0x00, 0), (0x02, 8), (0x04, 16), (0x06, 8)]);
+        assert_eq!(usage.depth(0x100), None);
+    }
+
+    #[test]
+    fn test_writeback() {
+        let usage = usage(
+            &[
+                0xf84d, 0x0d04, // 0x00 str r0, [sp, #-4]!
+                0xf85d, 0x0b04, // 0x04 ldr r0, [sp], #4
+                0x4770, // 0x08 bx lr
+            ],
+            [0x00],
+        );
+        assert_eq!(usage.frames[&0x00].depth, StackDepth::Bounded(4));
+        assert_eq!(usage.frames[&0x00].depths[&0x04], 4);
+        assert_eq!(usage.frames[&0x00].depths[&0x08], 0);
+    }
+
+    #[test]
+    fn test_worst_case() {
+        let usage = usage(
+            &[
+                0xb580, // 0x00 push {r7, lr}
+                0xb082, // 0x02 sub sp, #8
+                0xf000, 0xf802, // 0x04 bl 0x0c
+                0xb002, // 0x08 add sp, #8
+                0xbd80, // 0x0a pop {r7, pc}
+                0xb510, // 0x0c push {r4, lr}
+                0xf000, 0xf801, // 0x0e bl 0x14
+                0xbd10, // 0x12 pop {r4, pc}
+                0xf84d, 0x0d04, // 0x14 str r0, [sp, #-4]!
+                0xf85d, 0x0b04, // 0x18 ldr r0, [sp], #4
+                0x4770, // 0x1c bx lr
+            ],
+            [0x00],
+        );
+        assert_eq!(usage.frames[&0x00].depth, StackDepth::Bounded(16));
+        assert_eq!(usage.frames[&0x0c].depth, StackDepth::Bounded(8));
+        assert_eq!(usage.depth(0x14), Some(StackDepth::Bounded(4)));
+        assert_eq!(usage.depth(0x0c), Some(StackDepth::Bounded(12)));
+        assert_eq!(usage.depth(0x00), Some(StackDepth::Bounded(28)));
+        assert_eq!(usage.depth(0x00).and_then(|depth| depth.bound()), Some(28));
+    }
+
+    #[test]
+    fn test_dynamic_allocation() {
+        let usage = usage(
+            &[
+                0xb580, // 0x00 push {r7, lr}
+                0xebad, 0x0d00, // 0x02 sub.w sp, sp, r0
+                0x46bd, // 0x06 mov sp, r7
+                0xbd80, // 0x08 pop {r7, pc}
+            ],
+            [0x00],
+        );
+        assert_eq!(
+            usage.depth(0x00),
+            Some(StackDepth::Unbounded(Reason::DynamicAllocation(0x02)))
+        );
+    }
+
+    #[test]
+    fn test_indirect_call() {
+        let usage = usage(
+            &[
+                0xb500, // 0x00 push {lr}
+                0x4798, // 0x02 blx r3
+                0xbd00, // 0x04 pop {pc}
+            ],
+            [0x00],
+        );
+        assert_eq!(
+            usage.depth(0x00),
+            Some(StackDepth::Unbounded(Reason::IndirectCall(0x02)))
+        );
+    }
+
+    #[test]
+    fn test_recursion() {
+        let usage = usage(
+            &[
+                0xb500, // 0x00 push {lr}
+                0xf7ff, 0xfffd, // 0x02 bl 0x00
+                0xbd00, // 0x06 pop {pc}
+            ],
+            [0x00],
+        );
+        assert_eq!(
+            usage.depth(0x00),
+            Some(StackDepth::Unbounded(Reason::Recursion(0x00)))
+        );
+    }
+
+    #[test]
+    fn test_growing() {
+        let usage = usage(
+            &[
+                0xb401, // 0x00 push {r0}
+                0x2800, // 0x02 cmp r0, #0
+                0xd100, // 0x04 bne 0x08
+                0x4770, // 0x06 bx lr
+                0xb401, // 0x08 push {r0}
+                0xe7ff, // 0x0a b 0x0c
+                0x3801, // 0x0c subs r0, #1
+                0xd1fb, // 0x0e bne 0x08
+                0x4770, // 0x10 bx lr
+            ],
+            [0x00],
+        );
+        assert_eq!(
+            usage.depth(0x00),
+            Some(StackDepth::Unbounded(Reason::Growing(0x08)))
+        );
+    }
+}
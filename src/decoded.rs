@@ -0,0 +1,292 @@
+//! Defines the [`DecodedInstruction`], an [`Operation`] along with the
+//! encoding that it was decoded from.
+//!
+//! Many operations can be decoded from several encodings, `AddImmediate`
+//! has four 32 bit and 16 bit encodings. The [`EncodingId`] keeps track of
+//! which one was used, which is needed to compute the size of code or to
+//! encode the operation again.
+
+use std::fmt::Display;
+
+use crate::{operation::Operation, unpredictable::Reason};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Identifies an encoding by the decode table in chapter A5 of the ARM ARM,
+/// the name of the encoding in that table and the number of the encoding
+/// in the ARM ARM, i.e. `3` for `T3`.
+///
+/// The name is unique within the table, tables that decode several
+/// encodings of an instruction name them by their encoding, i.e.
+/// `LdrbImmediateT3`. The encoding number refers to the instruction that the
+/// encoding is decoded to, `ADD.W r0, r1, #1` from table A5.10 is
+/// `AddImmediate` T3.
+pub struct EncodingId {
+    /// The table, spelled as in the ARM ARM, i.e. `A5.10`.
+    pub table: &'static str,
+    /// The name of the encoding in the table.
+    pub name: &'static str,
+    /// The number of the encoding, `3` for `T3`.
+    pub encoding: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// The size of an instruction.
+pub enum InstructionSize {
+    /// A 16 bit instruction.
+    Narrow,
+    /// A 32 bit instruction.
+    Wide,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A decoded [`Operation`] along with its encoding.
+pub struct DecodedInstruction {
+    /// The decoded operation.
+    pub op: Operation,
+    /// The encoding that the operation was decoded from.
+    pub encoding: EncodingId,
+    /// The raw bits of the instruction, the first halfword is in the most
+    /// significant bits of 32 bit instructions.
+    pub raw: u32,
+    /// The size of the instruction.
+    pub size: InstructionSize,
+    /// The reason that the instruction is UNPREDICTABLE, if it was decoded
+    /// with a permissive [`Policy`](crate::Policy).
+    pub unpredictable: Option<Reason>,
+}
+
+impl EncodingId {
+    /// Creates an identifier for the encoding `T<encoding>` in the table.
+    pub(crate) const fn new(table: &'static str, name: &'static str, encoding: u8) -> Self {
+        Self {
+            table,
+            name,
+            encoding,
+        }
+    }
+}
+
+/// Spells the name of a table type as in the ARM ARM, `A5_10` becomes
+/// `A5.10`.
+pub(crate) const fn table<const N: usize>(ident: &str) -> [u8; N] {
+    let ident = ident.as_bytes();
+    let mut table = [0; N];
+    let mut idx = 0;
+    while idx < N {
+        table[idx] = match ident[idx] {
+            b'_' => b'.',
+            c => c,
+        };
+        idx += 1;
+    }
+    table
+}
+
+/// Reads the encoding number from the trailing `T<n>` of an encoding
+/// annotation or name, i.e. `LdrImmediateT3`.
+pub(crate) const fn encoding(annotation: &str) -> u8 {
+    match annotation.as_bytes() {
+        [.., b'T', n @ b'1'..=b'9'] => *n - b'0',
+        _ => panic!("Encodings are annotated as T<n>"),
+    }
+}
+
+impl InstructionSize {
+    /// Returns the size in bits.
+    pub const fn bits(&self) -> usize {
+        match self {
+            Self::Narrow => 16,
+            Self::Wide => 32,
+        }
+    }
+
+    /// Returns the size in bytes.
+    pub const fn bytes(&self) -> u32 {
+        self.bits() as u32 / 8
+    }
+
+    /// Returns the number of halfwords.
+    pub const fn halfwords(&self) -> usize {
+        self.bits() / 16
+    }
+
+    /// Splits the raw bits of an instruction of this size in to its
+    /// halfwords in the order that they are stored in memory.
+    pub(crate) fn split(&self, raw: u32) -> impl Iterator<Item = u16> {
+        [(raw >> 16) as u16, raw as u16]
+            .into_iter()
+            .skip(2 - self.halfwords())
+    }
+}
+
+impl DecodedInstruction {
+    /// Returns the halfwords of the instruction in the order that they are
+    /// stored in memory.
+    pub fn halfwords(&self) -> impl Iterator<Item = u16> {
+        self.size.split(self.raw)
+    }
+
+    /// Returns the bytes of the instruction in the order that they are
+    /// stored in memory.
+    pub fn bytes(&self) -> impl Iterator<Item = u8> {
+        self.halfwords().flat_map(u16::to_le_bytes)
+    }
+}
+
+impl Display for EncodingId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} T{}", self.table, self.name, self.encoding)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        helpers::test::code,
+        prelude::*,
+        DecodedInstruction,
+        EncodingId,
+        InstructionSize,
+        Policy,
+        Reason,
+    };
+
+    fn decode(halfwords: &[u16]) -> Vec<DecodedInstruction> {
+        let bin = code(halfwords);
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        let mut decoded = Vec::new();
+        while (stream.peek::<1>() as Option<u16>).is_some() {
+            decoded.push(Operation::decode(&mut stream, Policy::Strict).unwrap());
+        }
+        decoded
+    }
+
+    fn encodings(decoded: &[DecodedInstruction]) -> Vec<String> {
+        decoded
+            .iter()
+            .map(|instruction| instruction.encoding.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_add_immediate() {
+        let decoded = decode(&[
+            0x1c48, // adds r0, r1, #1
+            0x3001, // adds r0, #1
+            0xf111, 0x0001, // adds.w r0, r1, #1
+            0xf201, 0x0001, // addw r0, r1, #1
+        ]);
+        assert!(decoded
+            .iter()
+            .all(|instruction| matches!(instruction.op, Operation::AddImmediate(_))));
+        assert_eq!(encodings(&decoded), vec![
+            "A5.2 AddImmediate3 T1",
+            "A5.2 AddImmediate8 T2",
+            "A5.10 Add T3",
+            "A5.12 Add T4"
+        ]);
+        let numbers: Vec<u8> = decoded
+            .iter()
+            .map(|instruction| instruction.encoding.encoding)
+            .collect();
+        assert_eq!(numbers, vec![1, 2, 3, 4]);
+        assert_eq!(decoded[0].raw, 0x1c48);
+        assert_eq!(decoded[2].raw, 0xf111_0001);
+        assert_eq!(decoded[2].size, InstructionSize::Wide);
+    }
+
+    #[test]
+    fn test_ldr_immediate() {
+        let decoded = decode(&[
+            0x6848, // ldr r0, [r1, #4]
+            0x9801, // ldr r0, [sp, #4]
+            0xf8d1, 0x0004, // ldr.w r0, [r1, #4]
+            0xf851, 0x0c04, // ldr r0, [r1, #-4]
+        ]);
+        assert!(decoded
+            .iter()
+            .all(|instruction| matches!(instruction.op, Operation::LdrImmediate(_))));
+        assert_eq!(encodings(&decoded), vec![
+            "A5.5 LdrI T1",
+            "A5.5 LdrRI T2",
+            "A5.18 LdrImmediateT3 T3",
+            "A5.18 LdrImmediateT4 T4"
+        ]);
+    }
+
+    #[test]
+    fn test_subtables() {
+        let decoded = decode(&[
+            0xbf00, // nop
+            0xf3bf, 0x8f4f, // dsb sy
+            0xf3af, 0x8000, // nop.w
+            0xf3af, 0x80f0, // dbg #0
+            0x4801, // ldr r0, [pc, #4]
+        ]);
+        let encodings: Vec<EncodingId> = decoded
+            .iter()
+            .map(|instruction| instruction.encoding)
+            .collect();
+        assert_eq!(encodings, vec![
+            EncodingId {
+                table: "A5.7",
+                name: "Hint",
+                encoding: 1
+            },
+            EncodingId {
+                table: "A5.15",
+                name: "Dsb",
+                encoding: 1
+            },
+            EncodingId {
+                table: "A5.14",
+                name: "Hint",
+                encoding: 2
+            },
+            EncodingId {
+                table: "A5.14",
+                name: "Hint",
+                encoding: 1
+            },
+            EncodingId {
+                table: "A5.1",
+                name: "Ldr",
+                encoding: 1
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_asm_statements() {
+        let bin = code(&[
+            0x1c48, // adds r0, r1, #1
+            0xf111, 0x0001, // adds.w r0, r1, #1
+            0xea0f, 0x0000, // and.w pc, pc, r0
+        ]);
+        let mut stream = PeekableBuffer::from(bin.clone().into_iter());
+        let asm = ASM::parse_with_policy(&mut stream, Policy::Permissive).unwrap();
+
+        let sizes: Vec<InstructionSize> = asm.statements().iter().map(|stmt| stmt.size).collect();
+        assert_eq!(sizes, vec![
+            InstructionSize::Narrow,
+            InstructionSize::Wide,
+            InstructionSize::Wide
+        ]);
+        let halfwords: Vec<u16> = asm
+            .statements()
+            .iter()
+            .flat_map(DecodedInstruction::halfwords)
+            .collect();
+        assert_eq!(halfwords, vec![0x1c48, 0xf111, 0x0001, 0xea0f, 0x0000]);
+        let bytes: Vec<u8> = asm
+            .statements()
+            .iter()
+            .flat_map(DecodedInstruction::bytes)
+            .collect();
+        assert_eq!(bytes, bin);
+        assert_eq!(asm.unpredictable().collect::<Vec<_>>(), vec![(
+            2,
+            Reason::Register(Register::PC)
+        )]);
+    }
+}
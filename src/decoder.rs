@@ -0,0 +1,248 @@
+//! Defines a lazy decoder for a [`Stream`] of instructions.
+//!
+//! This modules main export is the [`Decoder`] which decodes one
+//! instruction at a time, unlike [`ASM`](crate::ASM) which decodes the
+//! entire stream up front. This allows the caller to stop early, i.e. at the
+//! first return in a function, or to compose the decoding with iterator
+//! adapters.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::prelude::*;
+//!
+//! let bin: Vec<u8> = [
+//!     0xb510u16, // push {r4, lr}
+//!     0x3001,    // adds r0, #1
+//!     0xbd10,    // pop {r4, pc}
+//!     0xffff,    // not part of the function
+//! ]
+//! .iter()
+//! .flat_map(|half| half.to_le_bytes())
+//! .collect();
+//!
+//! let mut decoder = Decoder::new(PeekableBuffer::from(bin.into_iter()));
+//! let function: Vec<Operation> = decoder
+//!     .iter()
+//!     .map(|decoded| decoded.unwrap().op)
+//!     .take_while(|op| !matches!(op, Operation::Pop(_)))
+//!     .collect();
+//! assert_eq!(function.len(), 2);
+//!
+//! // The pop was consumed by the iterator, the trailing halfword was not.
+//! assert_eq!(decoder.offset(), 6);
+//! let remaining: Option<u16> = decoder.remaining().peek::<1>();
+//! assert_eq!(remaining, Some(0xffff));
+//! ```
+
+use std::iter::FusedIterator;
+
+use crate::{operation::Operation, DecodedInstruction, ParseError, Policy, Stream};
+
+#[derive(Debug)]
+/// Lazily decodes the instructions in a [`Stream`].
+///
+/// The instructions are decoded by the [`Iter`] returned from
+/// [`iter`](Decoder::iter). If an instruction can not be decoded the error
+/// is yielded once, after which the decoder does not decode any more
+/// instructions until the offending halfword has been
+/// [`skipped`](Decoder::skip_halfword).
+pub struct Decoder<T: Stream> {
+    stream: T,
+    policy: Policy,
+    offset: u32,
+    stalled: bool,
+}
+
+#[derive(Debug)]
+/// An iterator over the instructions decoded by a [`Decoder`].
+///
+/// The iterator ends at the end of the input or after the first error.
+pub struct Iter<'a, T: Stream> {
+    decoder: &'a mut Decoder<T>,
+    done: bool,
+}
+
+impl<T: Stream> Decoder<T> {
+    /// Creates a decoder that decodes the stream with [`Policy::Permissive`].
+    pub const fn new(stream: T) -> Self {
+        Self {
+            stream,
+            policy: Policy::Permissive,
+            offset: 0,
+            stalled: false,
+        }
+    }
+
+    /// Sets the [`Policy`] used for UNPREDICTABLE instructions.
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns an iterator that decodes the remaining instructions.
+    pub fn iter(&mut self) -> Iter<'_, T> {
+        Iter {
+            decoder: self,
+            done: false,
+        }
+    }
+
+    /// Returns the number of bytes that have been consumed from the stream.
+    pub const fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the input that has not been decoded yet.
+    ///
+    /// Consuming elements from the returned stream is not reflected in the
+    /// [`offset`](Decoder::offset).
+    pub fn remaining(&mut self) -> &mut T {
+        &mut self.stream
+    }
+
+    /// Consumes the decoder, returning the input that has not been decoded
+    /// yet.
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+
+    /// Skips a single halfword, i.e. after an error, and resumes decoding
+    /// from the next halfword.
+    ///
+    /// Returns the skipped halfword or None if the stream is empty.
+    pub fn skip_halfword(&mut self) -> Option<u16> {
+        let [halfword]: [u16; 1] = self.stream.consume::<1>()?;
+        self.offset += 2;
+        self.stalled = false;
+        Some(halfword)
+    }
+}
+
+impl<T: Stream> Iter<'_, T> {
+    /// Returns the number of bytes that have been consumed from the stream.
+    pub const fn offset(&self) -> u32 {
+        self.decoder.offset
+    }
+
+    /// Returns the input that has not been decoded yet.
+    pub fn remaining(&mut self) -> &mut T {
+        self.decoder.remaining()
+    }
+}
+
+impl<T: Stream> Iterator for Iter<'_, T> {
+    type Item = Result<DecodedInstruction, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.decoder.stalled {
+            self.done = true;
+            return None;
+        }
+        if (self.decoder.stream.peek::<1>() as Option<u16>).is_none() {
+            self.done = true;
+            return None;
+        }
+        let decoder = &mut *self.decoder;
+        match Operation::decode(&mut decoder.stream, decoder.policy) {
+            Ok(decoded) => {
+                decoder.offset += decoded.size.bytes();
+                Some(Ok(decoded))
+            }
+            Err(e) => {
+                decoder.stalled = true;
+                self.done = true;
+                Some(Err(e.at(decoder.offset)))
+            }
+        }
+    }
+}
+
+impl<T: Stream> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T: Stream> IntoIterator for &'a mut Decoder<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = Result<DecodedInstruction, ParseError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{helpers::test::code, prelude::*, ErrorKind, Policy, Reason};
+
+    fn stream(halfwords: &[u16]) -> PeekableBuffer<u8, std::vec::IntoIter<u8>> {
+        let bin = code(halfwords);
+        PeekableBuffer::from(bin.into_iter())
+    }
+
+    #[test]
+    fn test_stop_early() {
+        let mut decoder = Decoder::new(stream(&[
+            0x3001, // adds r0, #1
+            0xf111, 0x0001, // adds.w r0, r1, #1
+            0x4770, // bx lr
+            0xffff, 0xffff, // never decoded
+        ]));
+        let mut function = Vec::new();
+        for decoded in decoder.iter() {
+            let op = decoded.unwrap().op;
+            let ret = matches!(op, Operation::Bx(_));
+            function.push(op);
+            if ret {
+                break;
+            }
+        }
+        assert_eq!(function.len(), 3);
+        assert_eq!(decoder.offset(), 8);
+        let remaining: Option<u32> = decoder.remaining().peek::<1>();
+        assert_eq!(remaining, Some(0xffff_ffff));
+    }
+
+    #[test]
+    fn test_resume() {
+        let mut decoder = Decoder::new(stream(&[
+            0x3001, // adds r0, #1
+            0xea0f, 0x0000, // and.w pc, pc, r0
+            0x3001, // adds r0, #1
+        ]))
+        .with_policy(Policy::Strict);
+        let mut iter = decoder.iter();
+        assert!(iter.next().unwrap().is_ok());
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!(error.location, Some(2));
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Unpredictable(Reason::Register(Register::PC))
+        ));
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+
+        // The error is not decoded again until the halfword has been skipped.
+        assert!(decoder.iter().next().is_none());
+        assert_eq!(decoder.skip_halfword(), Some(0xea0f));
+        let ops: Vec<Operation> = decoder.iter().map(|decoded| decoded.unwrap().op).collect();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(decoder.offset(), 8);
+        assert_eq!(decoder.skip_halfword(), None);
+    }
+
+    #[test]
+    fn test_policy() {
+        // The decoder is permissive unless told otherwise.
+        let mut decoder = Decoder::new(stream(&[
+            0xea0f, 0x0000, // and.w pc, pc, r0
+        ]));
+        let decoded: Vec<_> = (&mut decoder)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(
+            decoded[0].unpredictable,
+            Some(Reason::Register(Register::PC))
+        );
+    }
+}
@@ -0,0 +1,622 @@
+//! Recursive descent disassembly driven by the control flow.
+//!
+//! A linear sweep over an image decodes everything as instructions,
+//! including literal pools and jump tables that are embedded in the code.
+//! The [`Disassembler`] instead starts from a set of entry points, such as
+//! the handlers in the [`VectorTable`] or the function symbols of an
+//! [`Elf`] file, and only follows the paths that the program can take. The
+//! bytes that are never reached are treated as data.
+//!
+//! Branches through [`JumpTable`]s are followed when the bounds of the table
+//! can be found, the table itself is then treated as data. The same goes for
+//! the [`Literal`] pools, any Thumb function pointers that they hold are used
+//! as additional entry points. Branches to
+//! targets that can not be computed, such as `bx r0`, are reported as
+//! [`unresolved`](Disassembly::unresolved).
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{disassembler::Disassembler, loader::Region};
+//!
+//! let code = [
+//!     0x00, 0xbf, // nop
+//!     0x01, 0xe0, // b 0x108
+//!     0xef, 0xbe, // data
+//!     0xad, 0xde, // data
+//!     0x70, 0x47, // bx lr
+//! ];
+//! let disassembly = Disassembler::new([Region::new(0x100, &code)])
+//!     .with_entry(0x101)
+//!     .run();
+//!
+//! assert!(disassembly.instructions.contains_key(&0x108));
+//! assert!(!disassembly.is_code(0x104));
+//! assert_eq!(disassembly.data(), vec![(0x104, 0x108)]);
+//! ```
+
+pub mod functions;
+pub mod jump_table;
+pub mod literal;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+pub use functions::{Evidence, Function};
+pub use jump_table::JumpTable;
+pub use literal::Literal;
+
+pub use crate::flow::Flow;
+use crate::{
+    arch::Condition,
+    loader::{elf::Elf, vector_table::VectorTable, Region},
+    operation::Operation,
+    DecodedInstruction,
+    EncodingId,
+    ErrorKind,
+    InstructionSize,
+    ParseError,
+    Policy,
+    Reason,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A decoded instruction.
+pub struct Instruction {
+    /// The size of the instruction.
+    pub size: InstructionSize,
+    /// The decoded operation.
+    pub operation: Operation,
+    /// The condition from the enclosing IT block, or [`Condition::None`] if
+    /// the instruction is not in an IT block.
+    pub condition: Condition,
+    /// The reason that the instruction is UNPREDICTABLE, if it was decoded
+    /// with a permissive [`Policy`].
+    pub unpredictable: Option<Reason>,
+    /// The encoding that the instruction was decoded from.
+    pub encoding: EncodingId,
+    /// The raw bits of the instruction, the first halfword of a 32 bit
+    /// instruction is in the most significant bits.
+    pub raw: u32,
+}
+
+#[derive(Debug, Clone)]
+/// Disassembles the code that is reachable from a set of entry points.
+pub struct Disassembler<'a> {
+    regions: Vec<Region<'a>>,
+    /// The bounds of the regions that hold code, as `(start, end)` pairs.
+    /// All of the regions are assumed to hold code if None.
+    executable: Option<Vec<(u32, u64)>>,
+    entries: BTreeSet<u32>,
+    policy: Policy,
+}
+
+#[derive(Debug)]
+/// The result of a [`Disassembler::run`].
+pub struct Disassembly {
+    /// The reachable instructions indexed by their address.
+    pub instructions: BTreeMap<u32, Instruction>,
+    /// The entry points that the disassembly started from, including the
+    /// function pointers that were found in literal pools.
+    pub entries: Vec<u32>,
+    /// The addresses of the indirect branches and calls whose targets could
+    /// not be resolved, returns are not included.
+    pub unresolved: Vec<u32>,
+    /// The reachable addresses that could not be decoded.
+    pub invalid: Vec<(u32, ParseError)>,
+    /// The resolved jump tables indexed by the address of the branch that
+    /// uses them.
+    pub tables: BTreeMap<u32, JumpTable>,
+    /// The PC relative data indexed by the address of the instruction that
+    /// references it.
+    pub literals: BTreeMap<u32, Literal>,
+    /// The bounds of the regions that were disassembled, as `(start, end)`
+    /// pairs.
+    regions: Vec<(u32, u64)>,
+}
+
+impl Instruction {
+    /// Returns how the instruction at `address` affects the control flow.
+    ///
+    /// Branches in an IT block take the condition of the block.
+    pub fn flow(&self, address: u32) -> Flow {
+        match (self.operation.control_flow(address), &self.condition) {
+            (
+                Flow::Branch {
+                    target,
+                    condition: Condition::None,
+                },
+                condition,
+            ) => Flow::Branch {
+                target,
+                condition: condition.clone(),
+            },
+            (flow, _) => flow,
+        }
+    }
+
+    /// Returns true if execution can continue with the next instruction,
+    /// which is the case for all conditional instructions.
+    pub fn falls_through(&self, address: u32) -> bool {
+        self.condition != Condition::None || self.flow(address).falls_through()
+    }
+
+    /// Returns the halfwords of the instruction in the order that they are
+    /// stored in memory.
+    pub fn halfwords(&self) -> impl Iterator<Item = u16> {
+        self.size.split(self.raw)
+    }
+}
+
+impl<'a> Disassembler<'a> {
+    /// Creates a disassembler over the memory regions, without any entry
+    /// points.
+    pub fn new<I: IntoIterator<Item = Region<'a>>>(regions: I) -> Self {
+        Self {
+            regions: regions.into_iter().collect(),
+            executable: None,
+            entries: BTreeSet::new(),
+            policy: Policy::Permissive,
+        }
+    }
+
+    /// Creates a disassembler over the allocated sections of the ELF file,
+    /// or its `PT_LOAD` segments if it has no section headers.
+    ///
+    /// The entry point of the file and all of the function symbols are used
+    /// as entry points. Function pointers are only followed in to the
+    /// [`code`](Elf::code) of the file.
+    pub fn from_elf(elf: &Elf<'a>) -> Self {
+        let sections: Vec<Region<'a>> = elf
+            .sections()
+            .iter()
+            .filter(|section| section.is_allocated() && !section.data.is_empty())
+            .map(|section| section.region())
+            .collect();
+        let regions = match sections.is_empty() {
+            false => sections,
+            true => elf
+                .segments()
+                .iter()
+                .filter(|segment| segment.is_load())
+                .map(|segment| segment.region())
+                .collect(),
+        };
+        let functions = elf.functions().map(|symbol| symbol.value);
+        Self::new(regions)
+            .with_executable(elf.code())
+            .with_entry(elf.entry())
+            .with_entries(functions)
+    }
+
+    /// Adds an entry point, the Thumb bit is ignored.
+    pub fn with_entry(mut self, address: u32) -> Self {
+        self.entries.insert(address & !1);
+        self
+    }
+
+    /// Adds a set of entry points, the Thumb bits are ignored.
+    pub fn with_entries<I: IntoIterator<Item = u32>>(self, addresses: I) -> Self {
+        addresses.into_iter().fold(self, |disassembler, address| {
+            disassembler.with_entry(address)
+        })
+    }
+
+    /// Sets the regions that hold code, function pointers in to any other
+    /// memory are not followed.
+    ///
+    /// All of the memory regions are assumed to hold code by default.
+    pub fn with_executable<I: IntoIterator<Item = Region<'a>>>(mut self, regions: I) -> Self {
+        self.executable = Some(
+            regions
+                .into_iter()
+                .map(|region| (region.address, region.end()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets how UNPREDICTABLE instructions are decoded, they are decoded and
+    /// tagged with their [`Reason`] by default.
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Adds all of the exception handlers as entry points.
+    pub fn with_vector_table(self, table: &VectorTable) -> Self {
+        self.with_entries(table.entry_points())
+    }
+
+    /// Returns the memory regions.
+    pub fn regions(&self) -> &[Region<'a>] {
+        &self.regions
+    }
+
+    /// Returns true if the address is in a region that holds code.
+    pub fn is_executable(&self, address: u32) -> bool {
+        match &self.executable {
+            Some(executable) => executable
+                .iter()
+                .any(|(start, end)| *start <= address && (address as u64) < *end),
+            None => self.regions.iter().any(|region| region.contains(address)),
+        }
+    }
+
+    /// Reads `size` bytes of memory starting at `address`.
+    ///
+    /// Returns None if any of the bytes are outside of the regions.
+    pub fn read(&self, address: u32, size: usize) -> Option<&'a [u8]> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| region.contains(address))?;
+        let offset = (address - region.address) as usize;
+        region.data.get(offset..offset.checked_add(size)?)
+    }
+
+    /// Decodes the instruction at `address` along with its encoding, with
+    /// the [`Policy`] of the disassembler.
+    pub fn decode(&self, address: u32) -> Result<DecodedInstruction, ParseError> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| region.contains(address))
+            .ok_or_else(|| ParseError::from(ErrorKind::IncompleteProgram).at(address))?;
+        Operation::decode(
+            &mut region.slice(address, region.end()).stream(),
+            self.policy,
+        )
+        .map_err(|e| e.at(address))
+    }
+
+    /// Disassembles all of the code that is reachable from the entry
+    /// points.
+    pub fn run(&self) -> Disassembly {
+        let mut disassembly = Disassembly {
+            instructions: BTreeMap::new(),
+            entries: self.entries.iter().copied().collect(),
+            unresolved: Vec::new(),
+            invalid: Vec::new(),
+            tables: BTreeMap::new(),
+            literals: BTreeMap::new(),
+            regions: self
+                .regions
+                .iter()
+                .map(|region| (region.address, region.end()))
+                .collect(),
+        };
+
+        // Each work item is an address and the remaining conditions of the IT
+        // block that it is in.
+        let mut work: Vec<(u32, Vec<Condition>)> = self
+            .entries
+            .iter()
+            .rev()
+            .map(|address| (*address, Vec::new()))
+            .collect();
+        // Function pointers are only followed once all of the other code has
+        // been disassembled, so that the literal pools are known.
+        let mut pointers: Vec<u32> = Vec::new();
+        // The jump tables and literal pools as `start -> end`.
+        let mut data: BTreeMap<u32, u32> = BTreeMap::new();
+        while let Some((address, mut conditions)) = work
+            .pop()
+            .or_else(|| pointers.pop().map(|pointer| (pointer, Vec::new())))
+        {
+            if disassembly.instructions.contains_key(&address)
+                || disassembly
+                    .invalid
+                    .iter()
+                    .any(|(invalid, _)| *invalid == address)
+                || data
+                    .range(..=address)
+                    .next_back()
+                    .is_some_and(|(_, end)| address < *end)
+            {
+                continue;
+            }
+            let DecodedInstruction {
+                op: operation,
+                size,
+                unpredictable,
+                encoding,
+                raw,
+            } = match self.decode(address) {
+                Ok(decoded) => decoded,
+                Err(error) => {
+                    disassembly.invalid.push((address, error));
+                    continue;
+                }
+            };
+            let condition = match conditions.is_empty() {
+                true => Condition::None,
+                false => conditions.remove(0),
+            };
+            if let Operation::It(el) = &operation {
+                conditions = el.conds.conditions.clone();
+            }
+            let instruction = Instruction {
+                size,
+                operation,
+                condition,
+                unpredictable,
+                encoding,
+                raw,
+            };
+
+            if let Some(literal) = Literal::resolve(self, address, &instruction.operation) {
+                if literal.size != 0 {
+                    data.insert(literal.address, literal.address.wrapping_add(literal.size));
+                }
+                if let Some(pointer) = literal.function_pointer() {
+                    if self.is_executable(pointer)
+                        && self.read(pointer, 2).is_some()
+                        && !disassembly.entries.contains(&pointer)
+                    {
+                        disassembly.entries.push(pointer);
+                        pointers.push(pointer);
+                    }
+                }
+                disassembly.literals.insert(address, literal);
+            }
+
+            let next = address.wrapping_add(size.bytes());
+            let flow = instruction.flow(address);
+            if instruction.falls_through(address) {
+                work.push((next, conditions));
+            }
+            disassembly.instructions.insert(address, instruction);
+            match flow {
+                Flow::Branch { target, .. }
+                | Flow::CompareBranch { target }
+                | Flow::Call { target } => work.push((target, Vec::new())),
+                // The bounds check preceding the branch has already been
+                // disassembled as it falls through to the branch.
+                Flow::Indirect | Flow::Table => {
+                    match JumpTable::resolve(self, &disassembly.instructions, address) {
+                        Some(table) => {
+                            work.extend(
+                                table
+                                    .targets
+                                    .iter()
+                                    .rev()
+                                    .map(|target| (*target, Vec::new())),
+                            );
+                            data.insert(table.address, table.end());
+                            disassembly.tables.insert(address, table);
+                        }
+                        None => disassembly.unresolved.push(address),
+                    }
+                }
+                Flow::IndirectCall => disassembly.unresolved.push(address),
+                Flow::Sequential | Flow::Return | Flow::Stop => {}
+            }
+        }
+        disassembly.unresolved.sort();
+        disassembly.entries.sort();
+        disassembly
+    }
+}
+
+impl Disassembly {
+    /// Returns the instruction that starts at `address`, if any.
+    pub fn instruction(&self, address: u32) -> Option<&Instruction> {
+        self.instructions.get(&address)
+    }
+
+    /// Returns true if the address is part of a reachable instruction.
+    pub fn is_code(&self, address: u32) -> bool {
+        self.instructions
+            .range(..=address)
+            .next_back()
+            .is_some_and(|(start, instruction)| {
+                (address as u64) < *start as u64 + instruction.size.bytes() as u64
+            })
+    }
+
+    /// Returns true if the address is part of a resolved jump table or
+    /// literal.
+    pub fn is_data(&self, address: u32) -> bool {
+        self.tables.values().any(|table| table.contains(address))
+            || self
+                .literals
+                .values()
+                .any(|literal| literal.contains(address))
+    }
+
+    /// Returns the parts of the regions that are not reachable code, as
+    /// `(start, end)` pairs.
+    pub fn data(&self) -> Vec<(u32, u64)> {
+        let mut data = Vec::new();
+        for (start, end) in &self.regions {
+            let mut current = *start as u64;
+            for (address, instruction) in self.instructions.range(start..) {
+                let address = *address as u64;
+                if address >= *end {
+                    break;
+                }
+                if address > current {
+                    data.push((current as u32, address));
+                }
+                current = current.max(address + instruction.size.bytes() as u64);
+            }
+            if current < *end {
+                data.push((current as u32, *end));
+            }
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        disassembler::{Disassembler, Flow},
+        helpers::test::code,
+        loader::{
+            elf::{test::image, Elf},
+            vector_table::VectorTable,
+            Region,
+        },
+        prelude::*,
+    };
+
+    #[test]
+    fn test_linear_sweep_differs() {
+        let code = code(&[
+            0x2800, // 0x00 cmp r0, #0
+            0xd002, // 0x02 beq 0x0a
+            0xf000, 0xf804, // 0x04 bl 0x10
+            0xe001, // 0x08 b 0x0e
+            0x2001, // 0x0a movs r0, #1
+            0x4770, // 0x0c bx lr
+            0xbd00, // 0x0e pop {pc}
+            0x4708, // 0x10 bx r1
+            0xffff, // 0x12 data
+            0xffff, // 0x14 data
+        ]);
+        let region = Region::new(0, &code);
+        assert!(region.decode().is_err());
+
+        let disassembly = Disassembler::new([region]).with_entry(1).run();
+        let addresses: Vec<u32> = disassembly.instructions.keys().copied().collect();
+        assert_eq!(addresses, vec![
+            0x00, 0x02, 0x04, 0x08, 0x0a, 0x0c, 0x0e, 0x10
+        ]);
+        assert_eq!(disassembly.unresolved, vec![0x10]);
+        assert!(disassembly.invalid.is_empty());
+        assert_eq!(disassembly.data(), vec![(0x12, 0x16)]);
+        assert!(disassembly.is_code(0x06));
+        assert!(!disassembly.is_code(0x12));
+        assert_eq!(
+            disassembly.instruction(0x02).unwrap().flow(0x02),
+            Flow::Branch {
+                target: 0x0a,
+                condition: Condition::Eq
+            }
+        );
+    }
+
+    #[test]
+    fn test_it_block() {
+        let code = code(&[
+            0x2800, // 0x00 cmp r0, #0
+            0xbf0c, // 0x02 ite eq
+            0x4770, // 0x04 bxeq lr
+            0xe001, // 0x06 bne 0x0c
+            0xffff, // 0x08 data
+            0xffff, // 0x0a data
+            0x4770, // 0x0c bx lr
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        let addresses: Vec<u32> = disassembly.instructions.keys().copied().collect();
+        assert_eq!(addresses, vec![0x00, 0x02, 0x04, 0x06, 0x0c]);
+
+        let bx = disassembly.instruction(0x04).unwrap();
+        assert_eq!(bx.condition, Condition::Eq);
+        assert!(bx.falls_through(0x04));
+
+        let b = disassembly.instruction(0x06).unwrap();
+        assert_eq!(b.condition, Condition::Ne);
+        assert_eq!(b.flow(0x06), Flow::Branch {
+            target: 0x0c,
+            condition: Condition::Ne
+        });
+        assert_eq!(
+            disassembly.instruction(0x0c).unwrap().condition,
+            Condition::None
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let code = code(&[
+            0xe7fe, // 0x00 b 0x00
+            0xf000, // 0x02 truncated 32 bit instruction
+        ]);
+        let disassembly = Disassembler::new([Region::new(0x20, &code)])
+            .with_entries([0x20, 0x22, 0x40])
+            .run();
+        assert_eq!(disassembly.instructions.len(), 1);
+        let invalid: Vec<u32> = disassembly
+            .invalid
+            .iter()
+            .map(|(address, _)| *address)
+            .collect();
+        assert_eq!(invalid, vec![0x22, 0x40]);
+    }
+
+    #[test]
+    fn test_entry_points() {
+        let code = code(&[
+            0x4770, // 0x100 bx lr
+            0x4770, // 0x102 bx lr
+            0xe7fe, // 0x104 b 0x104
+        ]);
+        let mut table: Vec<u8> = [0x2000_0000u32, 0x103, 0x105]
+            .iter()
+            .flat_map(|entry| entry.to_le_bytes())
+            .collect();
+        table.resize(64, 0);
+        let vectors = VectorTable::parse(&Region::new(0, &table), 0).unwrap();
+
+        let disassembly = Disassembler::new([Region::new(0x100, &code)])
+            .with_vector_table(&vectors)
+            .run();
+        assert_eq!(disassembly.entries, vec![0x102, 0x104]);
+        assert_eq!(disassembly.data(), vec![(0x100, 0x102)]);
+
+        let data = image(0x8000, &code, &[("first", 0x8001, 2, 2)]);
+        let elf = Elf::parse(&data).unwrap();
+        let disassembler = Disassembler::from_elf(&elf);
+        assert_eq!(disassembler.read(0x8004, 2), Some(&code[4..]));
+        assert_eq!(disassembler.read(0x8004, 3), None);
+        let disassembly = disassembler.run();
+        assert_eq!(disassembly.entries, vec![0x8000]);
+        assert_eq!(disassembly.data(), vec![(0x8002, 0x8006)]);
+    }
+
+    #[test]
+    fn test_function_pointers() {
+        let pool = code(&[
+            0x4800, // 0x00 ldr r0, [pc, #0]
+            0x4770, // 0x02 bx lr
+            0x0101, 0x0000, // 0x04 .word 0x101
+        ]);
+        let handler = code(&[0x4770]);
+        let regions = [Region::new(0, &pool), Region::new(0x100, &handler)];
+
+        let disassembly = Disassembler::new(regions).with_entry(0).run();
+        assert_eq!(disassembly.entries, vec![0x00, 0x100]);
+
+        // The pointer in to the data region is not followed.
+        let disassembler = Disassembler::new(regions)
+            .with_executable([regions[0]])
+            .with_entry(0);
+        assert!(disassembler.is_executable(0x04));
+        assert!(!disassembler.is_executable(0x100));
+        let disassembly = disassembler.run();
+        assert_eq!(disassembly.entries, vec![0x00]);
+        assert!(!disassembly.instructions.contains_key(&0x100));
+
+        // The pointer points in to data according to the mapping symbols.
+        let text = code(&[
+            0x4801, // 0x8000 ldr r0, [pc, #4]
+            0x4770, // 0x8002 bx lr
+            0x4770, // 0x8004 data
+            0xffff, // 0x8006 data
+            0x8005, 0x0000, // 0x8008 .word 0x8005
+        ]);
+        let data = image(0x8000, &text, &[("$t", 0x8000, 0, 0), ("$d", 0x8004, 0, 0)]);
+        let elf = Elf::parse(&data).unwrap();
+        let disassembly = Disassembler::from_elf(&elf).run();
+        assert_eq!(
+            disassembly.literals[&0x8000].function_pointer(),
+            Some(0x8004)
+        );
+        assert_eq!(disassembly.entries, vec![0x8000]);
+        assert!(!disassembly.instructions.contains_key(&0x8004));
+    }
+}
@@ -0,0 +1,405 @@
+//! Finds function boundaries in code without symbols.
+//!
+//! Stripped images often only provide the handlers in the vector table as
+//! entry points, which leaves any function that is only called indirectly
+//! undiscovered. The heuristics in this module look for the patterns that
+//! compilers emit around functions and record each guess as a [`Function`]
+//! with the [`Evidence`] that supports it.
+//!
+//! [`Disassembler::run_with_heuristics`] feeds the guesses back in to the
+//! recursive disassembly until no new functions are found.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{Disassembler, Disassembly, Flow};
+use crate::{
+    arch::{Condition, Register},
+    operation::Operation,
+};
+
+/// The halfwords that are used to pad functions to an alignment, `0x0000`
+/// and `nop`.
+const PADDING: [[u8; 2]; 2] = [[0x00, 0x00], [0x00, 0xbf]];
+
+/// The maximum number of instructions that are decoded when checking if a
+/// guess is plausible.
+const PLAUSIBILITY_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Enumerates the reasons to believe that an address is the start of a
+/// function.
+pub enum Evidence {
+    /// The address is one of the entry points of the disassembler.
+    Entry,
+    /// The address is the target of a `bl`.
+    Call,
+    /// The address is a Thumb function pointer in a literal pool.
+    Pointer,
+    /// The instruction at the address is a `push {.., lr}`.
+    Prologue,
+    /// The address is the target of an unconditional `b` from another
+    /// function.
+    TailCall,
+    /// The address follows the end of a function, after optional alignment
+    /// padding of `nop` or `0x0000` halfwords.
+    Padding,
+    /// The function returns with `bx lr`, `pop {.., pc}` or similar.
+    Epilogue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A function found by the heuristics.
+pub struct Function {
+    /// The address of the first instruction.
+    pub start: u32,
+    /// The address after the last instruction.
+    pub end: u32,
+    /// The evidence for the function, in the order of [`Evidence`].
+    pub evidence: Vec<Evidence>,
+}
+
+impl Evidence {
+    /// Returns how much the evidence contributes to the confidence.
+    pub const fn weight(&self) -> u8 {
+        match self {
+            Self::Entry => 100,
+            Self::Call => 80,
+            Self::Pointer => 60,
+            Self::Prologue => 50,
+            Self::TailCall => 40,
+            Self::Padding => 30,
+            Self::Epilogue => 20,
+        }
+    }
+
+    /// Sums the weights of the evidence, saturating at 100.
+    pub fn confidence<'a, I: IntoIterator<Item = &'a Self>>(evidence: I) -> u8 {
+        evidence
+            .into_iter()
+            .fold(0u8, |sum, evidence| sum.saturating_add(evidence.weight()))
+            .min(100)
+    }
+}
+
+impl Function {
+    /// Returns the confidence that this is a function, from 0 to 100.
+    pub fn confidence(&self) -> u8 {
+        Evidence::confidence(&self.evidence)
+    }
+}
+
+/// Returns true if the operation saves the link register on the stack.
+fn is_prologue(operation: &Operation) -> bool {
+    match operation {
+        Operation::Push(el) => el.registers.registers.contains(&Register::LR),
+        Operation::Stmdb(el) => {
+            el.rn == Register::SP && el.registers.registers.contains(&Register::LR)
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Disassembler<'a> {
+    /// Disassembles the code that is reachable from the entry points and
+    /// from the functions that are found by the heuristics.
+    ///
+    /// Addresses in the unreachable parts of the regions are used as
+    /// additional entry points if the confidence in them is at least
+    /// `threshold`, this is repeated until no more functions are found.
+    pub fn run_with_heuristics(&self, threshold: u8) -> (Disassembly, BTreeMap<u32, Function>) {
+        let mut disassembler = self.clone();
+        let mut found = BTreeSet::new();
+        loop {
+            let disassembly = disassembler.run();
+            let accepted: Vec<u32> = disassembler
+                .candidates(&disassembly)
+                .into_iter()
+                .filter(|(address, evidence)| {
+                    Evidence::confidence(evidence) >= threshold
+                        && !found.contains(address)
+                        && disassembler.is_plausible(&disassembly, *address)
+                })
+                .map(|(address, _)| address)
+                .collect();
+            if accepted.is_empty() {
+                let functions = self.functions(&disassembly, &found);
+                return (disassembly, functions);
+            }
+            found.extend(accepted.iter().copied());
+            disassembler = disassembler.with_entries(accepted);
+        }
+    }
+
+    /// Collects the functions in the disassembly.
+    ///
+    /// `found` holds the entry points that were added by the heuristics.
+    pub fn functions(
+        &self,
+        disassembly: &Disassembly,
+        found: &BTreeSet<u32>,
+    ) -> BTreeMap<u32, Function> {
+        let flows: Vec<(u32, Flow)> = disassembly
+            .instructions
+            .iter()
+            .map(|(address, instruction)| (*address, instruction.flow(*address)))
+            .collect();
+        let calls: BTreeSet<u32> = flows
+            .iter()
+            .filter_map(|(_, flow)| match flow {
+                Flow::Call { target } => Some(*target),
+                _ => None,
+            })
+            .collect();
+        let starts: BTreeSet<u32> = self
+            .entries
+            .iter()
+            .chain(&disassembly.entries)
+            .chain(&calls)
+            .chain(found)
+            .copied()
+            .filter(|start| disassembly.instructions.contains_key(start))
+            .collect();
+
+        let mut functions = BTreeMap::new();
+        let mut starts_iter = starts.iter().peekable();
+        while let Some(start) = starts_iter.next() {
+            let limit = starts_iter.peek().map_or(u64::MAX, |next| **next as u64);
+            let end = disassembly
+                .instructions
+                .range(*start..)
+                .take_while(|(address, _)| (**address as u64) < limit)
+                .map(|(address, instruction)| address.wrapping_add(instruction.size.bytes()))
+                .max()
+                .unwrap_or(*start);
+
+            let mut evidence = Vec::new();
+            if self.entries.contains(start) {
+                evidence.push(Evidence::Entry);
+            }
+            if calls.contains(start) {
+                evidence.push(Evidence::Call);
+            }
+            if disassembly.entries.contains(start)
+                && !self.entries.contains(start)
+                && !found.contains(start)
+            {
+                evidence.push(Evidence::Pointer);
+            }
+            if is_prologue(&disassembly.instructions[start].operation) {
+                evidence.push(Evidence::Prologue);
+            }
+            let tail_call = flows.iter().any(|(address, flow)| {
+                matches!(flow, Flow::Branch { target, condition }
+                    if target == start
+                        && *condition == Condition::None
+                        && !(*start..end).contains(address))
+            });
+            if tail_call {
+                evidence.push(Evidence::TailCall);
+            }
+            if self.is_padded(disassembly, *start) {
+                evidence.push(Evidence::Padding);
+            }
+            let returns = flows
+                .iter()
+                .any(|(address, flow)| (*start..end).contains(address) && *flow == Flow::Return);
+            if returns {
+                evidence.push(Evidence::Epilogue);
+            }
+            functions.insert(*start, Function {
+                start: *start,
+                end,
+                evidence,
+            });
+        }
+        functions
+    }
+
+    /// Finds the addresses in the unreachable parts of the regions that look
+    /// like the start of a function.
+    fn candidates(&self, disassembly: &Disassembly) -> BTreeMap<u32, Vec<Evidence>> {
+        let mut candidates: BTreeMap<u32, Vec<Evidence>> = BTreeMap::new();
+        for (start, end) in disassembly.data() {
+            for address in (start as u64..end).step_by(2).map(|address| address as u32) {
+                if disassembly.is_data(address) {
+                    continue;
+                }
+                if self.is_padded(disassembly, address) {
+                    candidates
+                        .entry(address)
+                        .or_default()
+                        .push(Evidence::Padding);
+                }
+                if self
+                    .decode(address)
+                    .is_ok_and(|decoded| is_prologue(&decoded.op))
+                {
+                    candidates
+                        .entry(address)
+                        .or_default()
+                        .push(Evidence::Prologue);
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Returns true if the address follows an instruction that does not fall
+    /// through, after optional padding.
+    fn is_padded(&self, disassembly: &Disassembly, address: u32) -> bool {
+        if self
+            .read(address, 2)
+            .is_none_or(|data| PADDING.contains(&[data[0], data[1]]))
+        {
+            return false;
+        }
+        let mut previous = address;
+        loop {
+            let Some((start, instruction)) = disassembly.instructions.range(..previous).next_back()
+            else {
+                return false;
+            };
+            if start.wrapping_add(instruction.size.bytes()) == previous {
+                return !instruction.falls_through(*start);
+            }
+            previous = previous.wrapping_sub(2);
+            match self.read(previous, 2) {
+                Some(data) if PADDING.contains(&[data[0], data[1]]) => {}
+                _ => return false,
+            }
+        }
+    }
+
+    /// Returns true if the straight line code starting at the address
+    /// decodes without errors and does not run in to known data.
+    fn is_plausible(&self, disassembly: &Disassembly, mut address: u32) -> bool {
+        for _ in 0..PLAUSIBILITY_LIMIT {
+            if disassembly.is_data(address) {
+                return false;
+            }
+            let Ok(decoded) = self.decode(address) else {
+                return false;
+            };
+            if !decoded.op.control_flow(address).falls_through() {
+                return true;
+            }
+            address = address.wrapping_add(decoded.size.bytes());
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        disassembler::{Disassembler, Evidence},
+        helpers::test::code,
+        loader::Region,
+    };
+
+    #[test]
+    fn test_prologue() {
+        let code = code(&[
+            0xb510, // 0x00 push {r4, lr}
+            0xbd10, // 0x02 pop {r4, pc}
+            0xffff, 0xffff, // 0x04 data
+            0xb580, // 0x08 push {r7, lr}
+            0x2001, // 0x0a movs r0, #1
+            0xbd80, // 0x0c pop {r7, pc}
+        ]);
+        let disassembler = Disassembler::new([Region::new(0, &code)]).with_entry(0);
+        assert!(!disassembler.run().instructions.contains_key(&0x08));
+
+        let (disassembly, functions) = disassembler.run_with_heuristics(50);
+        assert!(disassembly.instructions.contains_key(&0x08));
+        assert_eq!(disassembly.data(), vec![(0x04, 0x08)]);
+        assert_eq!(functions[&0x00].evidence, vec![
+            Evidence::Entry,
+            Evidence::Prologue,
+            Evidence::Epilogue
+        ]);
+        assert_eq!(functions[&0x00].confidence(), 100);
+        assert_eq!(functions[&0x08].end, 0x0e);
+        assert_eq!(functions[&0x08].evidence, vec![
+            Evidence::Prologue,
+            Evidence::Epilogue
+        ]);
+        assert_eq!(functions[&0x08].confidence(), 70);
+    }
+
+    #[test]
+    fn test_call() {
+        let code = code(&[
+            0xb510, // 0x00 push {r4, lr}
+            0xf000, 0xf801, // 0x02 bl 0x08
+            0xbd10, // 0x06 pop {r4, pc}
+            0x2001, // 0x08 movs r0, #1
+            0x4770, // 0x0a bx lr
+        ]);
+        let (_, functions) = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run_with_heuristics(50);
+        assert_eq!(functions[&0x00].end, 0x08);
+        assert_eq!(functions[&0x08].end, 0x0c);
+        assert_eq!(functions[&0x08].evidence, vec![
+            Evidence::Call,
+            Evidence::Padding,
+            Evidence::Epilogue
+        ]);
+        assert_eq!(functions[&0x08].confidence(), 100);
+    }
+
+    #[test]
+    fn test_tail_call() {
+        let code = code(&[
+            0xb510, // 0x00 push {r4, lr}
+            0xf000, 0xf803, // 0x02 bl 0x0c
+            0xbd10, // 0x06 pop {r4, pc}
+            0x2001, // 0x08 movs r0, #1
+            0xe7ff, // 0x0a b 0x0c
+            0x4770, // 0x0c bx lr
+        ]);
+        let (_, functions) = Disassembler::new([Region::new(0, &code)])
+            .with_entries([0x00, 0x08])
+            .run_with_heuristics(50);
+        // The branch is within the function at 0x08, which ends at the
+        // function that it branches to.
+        assert_eq!(functions[&0x08].end, 0x0c);
+        assert_eq!(functions[&0x08].evidence, vec![
+            Evidence::Entry,
+            Evidence::Padding
+        ]);
+        assert_eq!(functions[&0x0c].evidence, vec![
+            Evidence::Call,
+            Evidence::TailCall,
+            Evidence::Padding,
+            Evidence::Epilogue
+        ]);
+    }
+
+    #[test]
+    fn test_padding() {
+        let code = code(&[
+            0x2001, // 0x00 movs r0, #1
+            0x4770, // 0x02 bx lr
+            0xbf00, // 0x04 nop
+            0x0000, // 0x06 padding
+            0x2003, // 0x08 movs r0, #3
+            0x4770, // 0x0a bx lr
+        ]);
+        let disassembler = Disassembler::new([Region::new(0, &code)]).with_entry(0);
+        let (disassembly, functions) = disassembler.run_with_heuristics(50);
+        assert_eq!(disassembly.data(), vec![(0x04, 0x0c)]);
+        assert_eq!(functions.len(), 1);
+
+        let (disassembly, functions) = disassembler.run_with_heuristics(30);
+        assert!(disassembly.invalid.is_empty());
+        assert_eq!(disassembly.data(), vec![(0x04, 0x08)]);
+        assert_eq!(functions[&0x08].evidence, vec![
+            Evidence::Padding,
+            Evidence::Epilogue
+        ]);
+        assert_eq!(functions[&0x08].confidence(), 50);
+        assert_eq!(functions.len(), 2);
+    }
+}
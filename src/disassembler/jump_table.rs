@@ -0,0 +1,279 @@
+//! Resolves the targets of switch statements that are compiled to jump
+//! tables.
+//!
+//! Two forms of tables are recognised, both of which must be preceded by a
+//! bounds check on the index register.
+//!
+//! ```text
+//! cmp   rm, #N
+//! bhi   default
+//! tbb   [pc, rm]              ; or tbh [pc, rm, lsl #1]
+//! ```
+//!
+//! where the table of `N + 1` byte or halfword offsets follows the `tbb`,
+//! and
+//!
+//! ```text
+//! cmp   rm, #N
+//! bhi   default
+//! adr   rn, table
+//! ldr   pc, [rn, rm, lsl #2]
+//! ```
+//!
+//! where the table holds `N + 1` absolute addresses. The base register can
+//! not be the PC as `ldr` with a PC base is always a literal load.
+
+use std::collections::BTreeMap;
+
+use super::{Disassembler, Instruction};
+use crate::{
+    arch::{Condition, Register, Shift},
+    operation::Operation,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A resolved jump table.
+pub struct JumpTable {
+    /// The address of the first entry in the table.
+    pub address: u32,
+    /// The size of each entry in bytes.
+    pub entry_size: u32,
+    /// The case targets, in table order.
+    pub targets: Vec<u32>,
+}
+
+impl JumpTable {
+    /// Returns the address after the last entry, rounded up to a halfword
+    /// boundary as the next instruction must be aligned.
+    pub fn end(&self) -> u32 {
+        let size = self.entry_size * self.targets.len() as u32;
+        self.address.wrapping_add(size + size % 2)
+    }
+
+    /// Returns true if the address is part of the table.
+    pub fn contains(&self, address: u32) -> bool {
+        address.wrapping_sub(self.address) < self.end().wrapping_sub(self.address)
+    }
+
+    /// Resolves the table that is used by the branch at `address`.
+    ///
+    /// The instructions leading up to the branch must already have been
+    /// disassembled. Returns None if the branch does not match any of the
+    /// recognised patterns, or if the table is outside of the memory
+    /// regions.
+    pub fn resolve(
+        disassembler: &Disassembler<'_>,
+        instructions: &BTreeMap<u32, Instruction>,
+        address: u32,
+    ) -> Option<Self> {
+        let branch = instructions.get(&address)?;
+        match &branch.operation {
+            Operation::Tb(el) if el.rn == Register::PC => {
+                let entries = bound(instructions, address, &el.rm)?;
+                let entry_size = match el.is_tbh {
+                    Some(true) => 2,
+                    _ => 1,
+                };
+                let table = address.wrapping_add(4);
+                let data = disassembler.read(table, entries.checked_mul(entry_size)? as usize)?;
+                let targets = data
+                    .chunks_exact(entry_size as usize)
+                    .map(|entry| table.wrapping_add(little_endian(entry) * 2))
+                    .collect();
+                Some(Self {
+                    address: table,
+                    entry_size,
+                    targets,
+                })
+            }
+            Operation::LdrRegister(el)
+                if el.rt == Register::PC
+                    && el
+                        .shift
+                        .as_ref()
+                        .is_some_and(|shift| shift.shift_t == Shift::Lsl && shift.shift_n == 2) =>
+            {
+                let (adr_address, adr) = previous(instructions, address)?;
+                let table = match &adr.operation {
+                    Operation::Adr(adr) if adr.rd == el.rn => {
+                        let base = align(adr_address.wrapping_add(4));
+                        match adr.add {
+                            true => base.wrapping_add(adr.imm),
+                            false => base.wrapping_sub(adr.imm),
+                        }
+                    }
+                    _ => return None,
+                };
+                let entries = bound(instructions, adr_address, &el.rm)?;
+                let data = disassembler.read(table, entries.checked_mul(4)? as usize)?;
+                let targets = data
+                    .chunks_exact(4)
+                    .map(|entry| little_endian(entry) & !1)
+                    .collect();
+                Some(Self {
+                    address: table,
+                    entry_size: 4,
+                    targets,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Aligns the address down to a word boundary.
+const fn align(address: u32) -> u32 {
+    address & !0b11
+}
+
+/// Reads a little endian entry of up to four bytes.
+fn little_endian(entry: &[u8]) -> u32 {
+    entry
+        .iter()
+        .rev()
+        .fold(0, |value, byte| (value << 8) | *byte as u32)
+}
+
+/// Returns the instruction that ends at `address`.
+fn previous(
+    instructions: &BTreeMap<u32, Instruction>,
+    address: u32,
+) -> Option<(u32, &Instruction)> {
+    instructions
+        .range(..address)
+        .next_back()
+        .filter(|(start, instruction)| start.wrapping_add(instruction.size.bytes()) == address)
+        .map(|(start, instruction)| (*start, instruction))
+}
+
+/// Finds the `cmp rm, #N; bhi` bounds check that ends at `address` and
+/// returns the number of entries in the table.
+fn bound(instructions: &BTreeMap<u32, Instruction>, address: u32, rm: &Register) -> Option<u32> {
+    let (bhi_address, bhi) = previous(instructions, address)?;
+    match &bhi.operation {
+        Operation::B(el) if el.condition == Condition::Hi => {}
+        _ => return None,
+    }
+    let (_, cmp) = previous(instructions, bhi_address)?;
+    match &cmp.operation {
+        Operation::CmpImmediate(el) if el.rn == *rm => el.imm.checked_add(1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        disassembler::{Disassembler, JumpTable},
+        helpers::test::code,
+        loader::Region,
+    };
+
+    #[test]
+    fn test_tbb() {
+        let code = code(&[
+            0x2802, // 0x00 cmp r0, #2
+            0xd806, // 0x02 bhi 0x12
+            0xe8df, 0xf000, // 0x04 tbb [pc, r0]
+            0x0302, 0x0004, // 0x08 table
+            0x2001, // 0x0c movs r0, #1
+            0x4770, // 0x0e bx lr
+            0x4770, // 0x10 bx lr
+            0x2000, // 0x12 movs r0, #0
+            0x4770, // 0x14 bx lr
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entries([0, 0x08])
+            .run();
+        assert_eq!(disassembly.tables[&0x04], JumpTable {
+            address: 0x08,
+            entry_size: 1,
+            targets: vec![0x0c, 0x0e, 0x10]
+        });
+        assert_eq!(disassembly.tables[&0x04].end(), 0x0c);
+        assert!(disassembly.unresolved.is_empty());
+        assert!(disassembly.invalid.is_empty());
+        assert_eq!(disassembly.data(), vec![(0x08, 0x0c)]);
+    }
+
+    #[test]
+    fn test_tbh() {
+        let code = code(&[
+            0x2801, // 0x00 cmp r0, #1
+            0xd804, // 0x02 bhi 0x0e
+            0xe8df, 0xf010, // 0x04 tbh [pc, r0, lsl #1]
+            0x0002, 0x0003, // 0x08 table
+            0x4770, // 0x0c bx lr
+            0x4770, // 0x0e bx lr
+        ]);
+        let disassembly = Disassembler::new([Region::new(0x100, &code)])
+            .with_entry(0x100)
+            .run();
+        assert_eq!(disassembly.tables[&0x104].targets, vec![0x10c, 0x10e]);
+        assert_eq!(disassembly.data(), vec![(0x108, 0x10c)]);
+    }
+
+    #[test]
+    fn test_ldr() {
+        let code = code(&[
+            0x2802, // 0x00 cmp r0, #2
+            0xd80c, // 0x02 bhi 0x1e
+            0xa101, // 0x04 adr r1, 0x0c
+            0xf851, 0xf020, // 0x06 ldr.w pc, [r1, r0, lsl #2]
+            0xffff, // 0x0a padding
+            0x0019, 0x0000, // 0x0c table
+            0x001b, 0x0000, //
+            0x001d, 0x0000, //
+            0x4770, // 0x18 bx lr
+            0x4770, // 0x1a bx lr
+            0x4770, // 0x1c bx lr
+            0x4770, // 0x1e bx lr
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        assert_eq!(disassembly.tables[&0x06], JumpTable {
+            address: 0x0c,
+            entry_size: 4,
+            targets: vec![0x18, 0x1a, 0x1c]
+        });
+        assert_eq!(disassembly.data(), vec![(0x0a, 0x18)]);
+    }
+
+    #[test]
+    fn test_ldr_without_adr() {
+        // The table base must be set by the preceding adr.
+        let code = code(&[
+            0x2802, // 0x00 cmp r0, #2
+            0xd80c, // 0x02 bhi 0x1e
+            0x4479, // 0x04 add r1, pc
+            0xf851, 0xf020, // 0x06 ldr.w pc, [r1, r0, lsl #2]
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        assert!(disassembly.tables.is_empty());
+        assert_eq!(disassembly.unresolved, vec![0x06]);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let code = code(&[
+            0x2802, // 0x00 cmp r0, #2
+            0xe8df, 0xf000, // 0x02 tbb [pc, r0]
+            0x0302, // 0x06 table
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        assert!(disassembly.tables.is_empty());
+        assert_eq!(disassembly.unresolved, vec![0x02]);
+
+        // The table does not fit in the region.
+        let code = [0x02, 0x28, 0x00, 0xd8, 0xdf, 0xe8, 0x00, 0xf0, 0x02];
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entry(0)
+            .run();
+        assert_eq!(disassembly.unresolved, vec![0x04]);
+    }
+}
@@ -0,0 +1,181 @@
+//! Resolves the PC relative data that is referenced by instructions.
+//!
+//! Compilers place constants that do not fit in an immediate in literal
+//! pools between the functions and load them with PC relative loads such as
+//! `ldr r0, [pc, #12]`. The [`Literal`] records where the constant is and
+//! what value it holds so that it can be shown next to the instruction, e.g.
+//! `ldr r0, [pc, #12] ; =0x40021000`.
+
+use std::fmt::Display;
+
+use super::Disassembler;
+use crate::{arch::Register, operation::Operation};
+
+#[derive(Debug, Clone, PartialEq)]
+/// PC relative data that is referenced by an instruction.
+pub struct Literal {
+    /// The address of the data.
+    pub address: u32,
+    /// The size of the data in bytes, zero for [`Adr`](crate::operation::Adr)
+    /// which only computes the address.
+    pub size: u32,
+    /// The value that is loaded, sign extended for signed loads, or the
+    /// address for [`Adr`](crate::operation::Adr). None if the data is outside
+    /// of the memory regions.
+    pub value: Option<u64>,
+}
+
+impl Literal {
+    /// Resolves the data that is referenced by the operation at `address`.
+    ///
+    /// Returns None if the operation does not reference any PC relative
+    /// data.
+    pub fn resolve(
+        disassembler: &Disassembler<'_>,
+        address: u32,
+        operation: &Operation,
+    ) -> Option<Self> {
+        let base = (address.wrapping_add(4)) & !0b11;
+        let offset = |add: bool, imm: u32| match add {
+            true => base.wrapping_add(imm),
+            false => base.wrapping_sub(imm),
+        };
+        let (address, size, signed) = match operation {
+            Operation::Adr(el) => {
+                let address = offset(el.add, el.imm);
+                return Some(Self {
+                    address,
+                    size: 0,
+                    value: Some(address as u64),
+                });
+            }
+            Operation::LdrLiteral(el) => (offset(el.add, el.imm), 4, false),
+            Operation::LdrhLiteral(el) => (offset(el.add.unwrap_or(true), el.imm), 2, false),
+            Operation::LdrbLiteral(el) => (offset(el.add.unwrap_or(true), el.imm), 1, false),
+            Operation::LdrshLiteral(el) => (offset(el.add, el.imm), 2, true),
+            Operation::LdrsbLiteral(el) => (offset(el.add, el.imm), 1, true),
+            Operation::LdrdLiteral(el) => (offset(el.add.unwrap_or(true), el.imm), 8, false),
+            Operation::LdrdImmediate(el) if el.rn == Register::PC && el.w != Some(true) => {
+                let address = match el.index {
+                    Some(false) => base,
+                    _ => offset(el.add.unwrap_or(true), el.imm),
+                };
+                (address, 8, false)
+            }
+            _ => return None,
+        };
+        let value = disassembler.read(address, size as usize).map(|data| {
+            let value = data
+                .iter()
+                .rev()
+                .fold(0u64, |value, byte| (value << 8) | *byte as u64);
+            match signed {
+                true => {
+                    let shift = 64 - 8 * size;
+                    (((value << shift) as i64 >> shift) as u32) as u64
+                }
+                false => value,
+            }
+        });
+        Some(Self {
+            address,
+            size,
+            value,
+        })
+    }
+
+    /// Returns true if the address is part of the data.
+    pub fn contains(&self, address: u32) -> bool {
+        address.wrapping_sub(self.address) < self.size
+    }
+
+    /// Returns the address of the function if the value looks like a
+    /// pointer to a Thumb function, i.e. it is a word with the Thumb bit set.
+    ///
+    /// The caller is responsible for checking that the address is code.
+    pub fn function_pointer(&self) -> Option<u32> {
+        match (self.size, self.value) {
+            (4, Some(value)) if value & 1 == 1 => Some(value as u32 & !1),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "={value:#x}"),
+            None => write!(f, "=[{:#x}]", self.address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        disassembler::{Disassembler, Literal},
+        helpers::test::code,
+        loader::Region,
+    };
+
+    #[test]
+    fn test_literal_pool() {
+        let code = code(&[
+            0x4802, // 0x00 ldr r0, [pc, #8]
+            0x4903, // 0x02 ldr r1, [pc, #12]
+            0xa201, // 0x04 adr r2, #4
+            0xf9bf, 0x300c, // 0x06 ldrsh.w r3, [pc, #12]
+            0x4770, // 0x0a bx lr
+            0x1000, 0x4002, // 0x0c .word 0x40021000
+            0x0019, 0x0000, // 0x10 .word 0x19
+            0xfffe, // 0x14 .short -2
+            0xffff, // 0x16 padding
+            0x4770, // 0x18 bx lr
+        ]);
+        let disassembly = Disassembler::new([Region::new(0, &code)])
+            .with_entries([0, 0x0c])
+            .run();
+        assert_eq!(disassembly.literals[&0x00], Literal {
+            address: 0x0c,
+            size: 4,
+            value: Some(0x4002_1000)
+        });
+        assert_eq!(disassembly.literals[&0x00].to_string(), "=0x40021000");
+        assert_eq!(disassembly.literals[&0x04].address, 0x0c);
+        assert_eq!(disassembly.literals[&0x04].size, 0);
+        assert_eq!(disassembly.literals[&0x06].value, Some(0xffff_fffe));
+
+        // The pointer is followed but the pool is not decoded.
+        assert_eq!(disassembly.literals[&0x02].function_pointer(), Some(0x18));
+        assert_eq!(disassembly.entries, vec![0x00, 0x0c, 0x18]);
+        assert!(disassembly.instructions.contains_key(&0x18));
+        assert!(disassembly.invalid.is_empty());
+        assert_eq!(disassembly.data(), vec![(0x0c, 0x18)]);
+    }
+
+    #[test]
+    fn test_ldrd() {
+        let code = code(&[
+            0xe9df, 0x4502, // 0x20 ldrd r4, r5, [pc, #8]
+            0xffff, 0xffff, // 0x24
+            0x5678, 0x1234, // 0x28
+            0xcdef, 0x89ab, // 0x2c
+        ]);
+        let disassembler = Disassembler::new([Region::new(0x20, &code)]);
+        let operation = disassembler.decode(0x20).unwrap().op;
+        let literal = Literal::resolve(&disassembler, 0x20, &operation).unwrap();
+        assert_eq!(literal, Literal {
+            address: 0x2c,
+            size: 8,
+            value: None
+        });
+        assert_eq!(literal.to_string(), "=[0x2c]");
+        assert!(literal.contains(0x33));
+        assert!(!literal.contains(0x34));
+
+        let operation = disassembler.decode(0x20).unwrap().op;
+        let literal = Literal::resolve(&disassembler, 0x1c, &operation).unwrap();
+        assert_eq!(literal.value, Some(0x89ab_cdef_1234_5678));
+        assert_eq!(literal.function_pointer(), None);
+    }
+}
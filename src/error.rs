@@ -0,0 +1,295 @@
+//! Defines the errors that are returned when decoding fails.
+//!
+//! A [`ParseError`] is an [`ErrorKind`] along with where the error occurred,
+//! the raw bits that were decoded and the table in the ARM ARM that was
+//! used, which makes it possible to find the failing instruction in a large
+//! image.
+
+use std::fmt::Display;
+
+use crate::{arch::ArchError, decoded::DecodedInstruction, unpredictable::Reason};
+
+#[derive(Debug)]
+/// Enumerates the kinds of errors that might occur during parsing
+/// [`ASM`](crate::ASM).
+pub enum ErrorKind {
+    /// Thrown when the buffer is not long enough.
+    /// The current instruction was not valid
+    IncompleteProgram,
+
+    /// Thrown when there is no matching 16 bit instruction
+    Invalid16Bit,
+
+    /// Thrown when there is no matching 32 bit instruction
+    Invalid32Bit,
+
+    /// Thrown when there is no matching
+    Incomplete32Bit,
+
+    /// Thrown when a field in an identifier is incorrect
+    InvalidField(String),
+
+    /// Thrown when a target register does not exist.
+    InvalidRegister(u8),
+
+    /// Thrown when an unpredictable instruction is decoded with
+    /// [`Policy::Strict`](crate::Policy::Strict)
+    Unpredictable(Reason),
+
+    /// Thrown when an undefined instruction is used
+    Undefined,
+
+    /// Thrown when a non covered case is reached
+    IncompleteParser,
+
+    /// Thrown when an invalid condition is requested
+    InvalidCondition,
+
+    /// Thrown when the parsing fails part way through parsing
+    PartiallyParsed(Box<ParseError>, Vec<DecodedInstruction>),
+
+    /// Sub-crate [`arch`](crate::arch) threw an error
+    ArchError(ArchError),
+
+    /// Thrown when internal logic is faulty, this should never occur
+    InternalError(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The raw bits of the instruction that could not be decoded.
+pub enum Raw {
+    /// A 16 bit instruction.
+    Halfword(u16),
+    /// A 32 bit instruction, the first halfword in the most significant bits.
+    Word(u32),
+}
+
+#[derive(Debug)]
+/// An error that occurred while parsing [`ASM`](crate::ASM).
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ErrorKind,
+    /// The address of the instruction, or its byte offset in the stream if
+    /// the address is not known.
+    pub location: Option<u32>,
+    /// The raw bits of the instruction.
+    pub raw: Option<Raw>,
+    /// The table in the ARM ARM that was being decoded, i.e. `A5.20`.
+    pub table: Option<&'static str>,
+}
+
+impl ErrorKind {
+    /// Returns a stable identifier for the kind of error.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::IncompleteProgram => "E0001",
+            Self::Invalid16Bit => "E0002",
+            Self::Invalid32Bit => "E0003",
+            Self::Incomplete32Bit => "E0004",
+            Self::InvalidField(_) => "E0005",
+            Self::InvalidRegister(_) => "E0006",
+            Self::Unpredictable(_) => "E0007",
+            Self::Undefined => "E0008",
+            Self::IncompleteParser => "E0009",
+            Self::InvalidCondition => "E0010",
+            Self::PartiallyParsed(..) => "E0011",
+            Self::ArchError(_) => "E0012",
+            Self::InternalError(_) => "E0013",
+        }
+    }
+
+    /// Creates an error that occurred while decoding the table.
+    pub fn in_table(self, table: &'static str) -> ParseError {
+        ParseError::from(self).in_table(table)
+    }
+}
+
+impl ParseError {
+    /// Returns a stable identifier for the kind of error, see
+    /// [`ErrorKind::code`].
+    pub const fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// Wraps the error from the first instruction that could not be parsed
+    /// along with the instructions that were parsed before it.
+    pub fn partially_parsed(error: Self, operations: Vec<DecodedInstruction>) -> Self {
+        let (location, raw, table) = (error.location, error.raw, error.table);
+        Self {
+            kind: ErrorKind::PartiallyParsed(Box::new(error), operations),
+            location,
+            raw,
+            table,
+        }
+    }
+
+    /// Sets the location of the error unless it is already known.
+    pub fn at(mut self, location: u32) -> Self {
+        self.location.get_or_insert(location);
+        if let ErrorKind::PartiallyParsed(error, _) = &mut self.kind {
+            error.location.get_or_insert(location);
+        }
+        self
+    }
+
+    /// Sets the raw bits of the instruction unless they are already known.
+    pub fn with_raw(mut self, raw: Raw) -> Self {
+        self.raw.get_or_insert(raw);
+        self
+    }
+
+    /// Sets the table that was being decoded unless it is already known,
+    /// errors from nested tables keep the innermost table.
+    pub fn in_table(mut self, table: &'static str) -> Self {
+        self.table.get_or_insert(table);
+        self
+    }
+}
+
+impl From<ErrorKind> for ParseError {
+    fn from(kind: ErrorKind) -> Self {
+        Self {
+            kind,
+            location: None,
+            raw: None,
+            table: None,
+        }
+    }
+}
+
+impl From<ArchError> for ParseError {
+    fn from(value: ArchError) -> Self {
+        ErrorKind::ArchError(value).into()
+    }
+}
+
+impl Display for Raw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Halfword(halfword) => write!(f, "{halfword:#06x}"),
+            Self::Word(word) => write!(f, "{word:#010x}"),
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompleteProgram => write!(f, "incomplete program"),
+            Self::Invalid16Bit => write!(f, "no matching 16 bit instruction"),
+            Self::Invalid32Bit => write!(f, "no matching 32 bit instruction"),
+            Self::Incomplete32Bit => write!(f, "incomplete 32 bit instruction"),
+            Self::InvalidField(field) => write!(f, "invalid field, {field}"),
+            Self::InvalidRegister(register) => write!(f, "invalid register {register}"),
+            Self::Unpredictable(_) => write!(f, "unpredictable instruction"),
+            Self::Undefined => write!(f, "undefined instruction"),
+            Self::IncompleteParser => write!(f, "instruction is not supported by the parser"),
+            Self::InvalidCondition => write!(f, "invalid condition"),
+            Self::PartiallyParsed(_, operations) => {
+                write!(f, "parsing failed after {} operations", operations.len())
+            }
+            Self::ArchError(error) => write!(f, "{error}"),
+            Self::InternalError(message) => write!(f, "internal error, {message}"),
+        }
+    }
+}
+
+impl Display for ParseError {
+    /// Formats the error as `[E0008] undefined instruction 0xf3af8100 at
+    /// 0x100 (A5.14)`, leaving out the parts that are not known. The
+    /// [`Reason`] is appended to UNPREDICTABLE instructions.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code(), self.kind)?;
+        if let Some(raw) = self.raw {
+            write!(f, " {raw}")?;
+        }
+        if let Some(location) = self.location {
+            write!(f, " at {location:#x}")?;
+        }
+        if let Some(table) = self.table {
+            write!(f, " ({table})")?;
+        }
+        if let ErrorKind::Unpredictable(reason) = &self.kind {
+            write!(f, ", {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::PartiallyParsed(error, _) => Some(error.as_ref()),
+            ErrorKind::ArchError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error;
+
+    use crate::{arch::ArchError, prelude::*, ErrorKind, ParseError, Policy, Raw, Reason};
+
+    fn decode(bin: Vec<u8>) -> ParseError {
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        ASM::parse_with_policy(&mut stream, Policy::Strict).expect_err("Invalid instruction accepted")
+    }
+
+    #[test]
+    fn test_context() {
+        // nop; mrs r2, <reserved>
+        let bin = vec![0x00, 0xbf, 0xef, 0xf3, 0x81, 0x82];
+        let error = decode(bin);
+        assert_eq!(error.code(), "E0011");
+        assert_eq!(error.location, Some(2));
+        let ErrorKind::PartiallyParsed(inner, operations) = &error.kind else {
+            panic!("Expected a partially parsed program");
+        };
+        assert_eq!(operations.len(), 1);
+        assert!(matches!(
+            inner.kind,
+            ErrorKind::Unpredictable(Reason::SpecialRegister(0x81))
+        ));
+        assert_eq!(inner.raw, Some(Raw::Word(0xf3ef_8281)));
+        assert_eq!(inner.table, Some("A5.13"));
+        assert_eq!(
+            inner.to_string(),
+            "[E0007] unpredictable instruction 0xf3ef8281 at 0x2 (A5.13), reserved special register \
+             0x81"
+        );
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_incomplete() {
+        // The first halfword of a 32 bit instruction.
+        let error = decode(vec![0xef, 0xf3]);
+        let ErrorKind::PartiallyParsed(inner, _) = &error.kind else {
+            panic!("Expected a partially parsed program");
+        };
+        assert!(matches!(inner.kind, ErrorKind::IncompleteProgram));
+        assert_eq!(inner.raw, Some(Raw::Halfword(0xf3ef)));
+        assert_eq!(inner.location, Some(0));
+    }
+
+    #[test]
+    fn test_undefined_table() {
+        // A permanently undefined load/store encoding in the 32 bit table.
+        let error = decode(vec![0x70, 0xf8, 0x00, 0x00]);
+        let ErrorKind::PartiallyParsed(inner, _) = &error.kind else {
+            panic!("Expected a partially parsed program");
+        };
+        assert!(matches!(inner.kind, ErrorKind::Undefined));
+        assert_eq!(inner.table, Some("A5.9"));
+    }
+
+    #[test]
+    fn test_arch_source() {
+        let error = ParseError::from(ArchError::InvalidRegister(16));
+        assert_eq!(error.code(), "E0012");
+        let source = error.source().expect("Arch errors are chained");
+        assert_eq!(source.to_string(), "[A0002] invalid register 16");
+    }
+}
@@ -0,0 +1,119 @@
+//! Classifies how instructions affect the control flow.
+//!
+//! The [`Flow`] of an [`Operation`] is shared by the analyses that need to
+//! know whether an instruction branches, such as the disassembler and the
+//! cycle count model.
+
+use crate::{
+    arch::{Condition, Register, RegisterList},
+    operation::Operation,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+/// Describes how an instruction affects the control flow.
+pub enum Flow {
+    /// Continues with the next instruction.
+    Sequential,
+    /// Branches to a target encoded in the instruction.
+    ///
+    /// The branch is unconditional if the condition is [`Condition::None`].
+    Branch {
+        /// The address of the branch target.
+        target: u32,
+        /// The condition under which the branch is taken.
+        condition: Condition,
+    },
+    /// Compares a register to zero and branches to the target, i.e.
+    /// [`Cbz`](crate::operation::Cbz).
+    CompareBranch {
+        /// The address of the branch target.
+        target: u32,
+    },
+    /// Calls a function at a target encoded in the instruction.
+    Call {
+        /// The address of the function.
+        target: u32,
+    },
+    /// Calls a function through a register.
+    IndirectCall,
+    /// Returns from the function, such as `bx lr` or `pop {pc}`.
+    Return,
+    /// Branches to a target that is computed at run time, such as `bx r0` or
+    /// `ldr pc, [r0]`.
+    Indirect,
+    /// Branches through a table of offsets, i.e.
+    /// [`Tb`](crate::operation::Tb).
+    Table,
+    /// Never continues, such as a permanently undefined instruction.
+    Stop,
+}
+
+impl Flow {
+    /// Returns true if the instruction can continue with the next
+    /// instruction.
+    pub fn falls_through(&self) -> bool {
+        match self {
+            Self::Branch { condition, .. } => *condition != Condition::None,
+            Self::Sequential
+            | Self::CompareBranch { .. }
+            | Self::Call { .. }
+            | Self::IndirectCall => true,
+            Self::Return | Self::Indirect | Self::Table | Self::Stop => false,
+        }
+    }
+
+    /// Returns the target of a branch or call that is encoded in the
+    /// instruction.
+    pub const fn target(&self) -> Option<u32> {
+        match self {
+            Self::Branch { target, .. }
+            | Self::CompareBranch { target }
+            | Self::Call { target } => Some(*target),
+            _ => None,
+        }
+    }
+}
+
+impl Operation {
+    /// Classifies how the operation at `address` affects the control flow.
+    ///
+    /// The condition of an enclosing IT block is not considered.
+    pub fn control_flow(&self, address: u32) -> Flow {
+        let writes_pc = |list: &RegisterList| list.registers.contains(&Register::PC);
+        let load = |rn: &Register| match rn {
+            Register::SP => Flow::Return,
+            _ => Flow::Indirect,
+        };
+        match self {
+            Self::B(el) => Flow::Branch {
+                target: address.wrapping_add(4).wrapping_add(el.imm),
+                condition: el.condition.clone(),
+            },
+            Self::Cbz(el) => Flow::CompareBranch {
+                target: address.wrapping_add(4).wrapping_add(el.imm),
+            },
+            Self::Bl(el) => Flow::Call {
+                target: address.wrapping_add(4).wrapping_add(el.imm),
+            },
+            Self::Blx(_) => Flow::IndirectCall,
+            Self::Bx(el) if el.rm == Register::LR => Flow::Return,
+            Self::Bx(_) => Flow::Indirect,
+            Self::Tb(_) => Flow::Table,
+            Self::Pop(el) if writes_pc(&el.registers) => Flow::Return,
+            Self::Ldm(el) if writes_pc(&el.registers) => load(&el.rn),
+            Self::Ldmdb(el) if writes_pc(&el.registers) => load(&el.rn),
+            Self::LdrImmediate(el) if el.rt == Register::PC => load(&el.rn),
+            Self::LdrLiteral(el) if el.rt == Register::PC => Flow::Indirect,
+            Self::LdrRegister(el) if el.rt == Register::PC => Flow::Indirect,
+            Self::MovRegister(el) if el.rd == Register::PC => match el.rm {
+                Register::LR => Flow::Return,
+                _ => Flow::Indirect,
+            },
+            Self::AddRegister(el) if *el.rd.as_ref().unwrap_or(&el.rn) == Register::PC => {
+                Flow::Indirect
+            }
+            Self::Udf(_) => Flow::Stop,
+            _ => Flow::Sequential,
+        }
+    }
+}
@@ -3,14 +3,6 @@
 //! Main macros is the [`instruction`] macro.
 //! This provides the ability to create a new instruction
 //! in a short and readable way.
-use crate::{arch::ArchError, ParseError};
-
-impl From<ArchError> for ParseError {
-    fn from(value: ArchError) -> Self {
-        Self::ArchError(value)
-    }
-}
-
 #[macro_export]
 /// Defines a new instruction or table of instructions
 ///
@@ -30,7 +22,12 @@ impl From<ArchError> for ParseError {
 /// This macro invocation provides an enum SomeTableIdent containing the
 /// variants (SomeInstructionIdent,PossiblyMoreInstructions) which in turn are
 /// structs containing the fields defined in the { } block. All of the fields in
-/// SomeTableIdent implement [`Parse`](crate::Parse).
+/// SomeTableIdent implement [`Parse`](crate::Parse), and the table reports the
+/// [`EncodingId`](crate::EncodingId) of the variant that was decoded.
+///
+/// Variants of a table are annotated with their encoding,
+/// `SomeInstructionIdent T2 : { .. }`, unless the name already ends in it,
+/// i.e. `LdrImmediateT3`.
 macro_rules! instruction {
     (size $size:ty;
      $(
@@ -66,7 +63,7 @@ macro_rules! instruction {
                     Self: Sized {
                     let word: $size = match iter.peek::<1>(){
                         Some(buff) => Ok(buff),
-                        None => Err(ParseError::Invalid16Bit(stringify!($id))),
+                        None => Err($crate::ErrorKind::IncompleteProgram),
                     }?;
                     $(
                         let $field_id:$type = instruction!($size;word $(as $representation)?; $start -> $end $($expr)?);
@@ -97,7 +94,7 @@ macro_rules! instruction {
     (
     size $size:ty; $table:ident contains
         $(
-            $($id:ident : {
+            $($id:ident $($enc:ident)? : {
                 $(
 
                         $field_id:ident $(as $representation:ty)?: $type:ty : $start:literal -> $end:literal $($expr:ident)?
@@ -121,6 +118,30 @@ macro_rules! instruction {
                     )?
                 )+
             }
+
+            impl $crate::Encoded for $table {
+                fn encoding(&self) -> $crate::EncodingId {
+                    const TABLE: &str = {
+                        const IDENT: &str = stringify!($table);
+                        const SPELLED: [u8; IDENT.len()] = $crate::decoded::table(IDENT);
+                        match std::str::from_utf8(&SPELLED) {
+                            Ok(table) => table,
+                            Err(_) => panic!("Table names are ASCII"),
+                        }
+                    };
+                    match self {
+                        $(
+                            $(Self::$id(_) => {
+                                const ENCODING: u8 = $crate::decoded::encoding(
+                                    concat!(stringify!($id) $(, stringify!($enc))?)
+                                );
+                                $crate::EncodingId::new(TABLE, stringify!($id), ENCODING)
+                            },)?
+                            $(Self::[<Subtable $table_id>](table) => $crate::Encoded::encoding(table),)?
+                        )+
+                    }
+                }
+            }
         }
         $(
 
@@ -150,7 +171,7 @@ macro_rules! instruction {
                         // Consume a word from the buffer
                         let word:$size = match iter.peek::<1>(){
                             Some(buff) => Ok(buff),
-                            None => Err(ParseError::Invalid16Bit(stringify!($id))),
+                            None => Err($crate::ErrorKind::IncompleteProgram),
                         }?;
                         $(
                             let $field_id:$type = instruction!($size; word $(as $representation)?; $start -> $end $($expr)?);
@@ -206,7 +227,15 @@ macro_rules! combine {
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
+
+    /// Assembles a list of halfwords in to little endian bytes.
+    pub(crate) fn code(halfwords: &[u16]) -> Vec<u8> {
+        halfwords
+            .iter()
+            .flat_map(|half| half.to_le_bytes())
+            .collect()
+    }
 
     #[test]
     fn test() {
@@ -107,14 +107,36 @@
 pub mod arch;
 mod asm;
 pub mod buffer;
+#[cfg(feature = "cfg")]
+pub mod cfg;
+mod decoded;
+pub mod decoder;
+#[cfg(feature = "disassembler")]
+pub mod disassembler;
+mod error;
+pub mod flow;
 mod helpers;
+#[cfg(feature = "lift")]
+pub mod lift;
+#[cfg(feature = "listing")]
+pub mod listing;
+#[cfg(feature = "loader")]
+pub mod loader;
 pub mod operation;
+#[cfg(feature = "smt")]
+pub mod smt;
+#[cfg(feature = "timing")]
+pub mod timing;
+mod unpredictable;
 
 use std::fmt::Debug;
 
 use arch::ArchError;
 use asm::b16::B16;
+pub use decoded::{DecodedInstruction, EncodingId, InstructionSize};
+pub use error::{ErrorKind, ParseError, Raw};
 use operation::Operation;
+pub use unpredictable::{Policy, Reason};
 
 use crate::asm::b32::B32;
 
@@ -123,9 +145,8 @@ use crate::asm::b32::B32;
 /// This struct is constructed via
 /// [`ASM`](ASM::parse).
 #[derive(Debug)]
-#[allow(dead_code)]
 pub struct ASM {
-    statements: Vec<(usize, operation::Operation)>,
+    statements: Vec<DecodedInstruction>,
 }
 
 /// Denotes that the element can be peeked `N` elements into the future.
@@ -160,7 +181,7 @@ pub trait Stream: Consume<u32> + Consume<u16> + Consume<u8> + Debug {
     {
         match self.peek::<1>() {
             Some(word) => Ok(word),
-            None => Err(ParseError::IncompleteProgram),
+            None => Err(ErrorKind::IncompleteProgram.into()),
         }
     }
 }
@@ -178,57 +199,59 @@ pub trait Parse {
         Self: Sized;
 }
 
-pub(crate) trait ToOperation {
+pub(crate) trait ToOperation: Encoded {
     /// Translates the encoded value in to a [`Operation`] instruction
     fn encoding_specific_operations(self) -> crate::operation::Operation;
-}
 
-#[derive(Debug)]
-/// Enumerates the errors that might occur during parsing [`ASM`].
-pub enum ParseError {
-    /// Thrown when the buffer is not long enough.
-    /// The current instruction was not valid
-    IncompleteProgram,
+    /// Translates the encoded value in to a [`Operation`] instruction along
+    /// with the [`EncodingId`] of the encoding.
+    fn decoded(self) -> (EncodingId, crate::operation::Operation)
+    where
+        Self: Sized,
+    {
+        (self.encoding(), self.encoding_specific_operations())
+    }
+}
 
-    /// Thrown when there is no matching 16 bit instruction
-    ///
-    /// Occurred while parsing the block in question
-    Invalid16Bit(&'static str),
+pub(crate) trait Encoded {
+    /// Returns the encoding that the value was decoded from.
+    fn encoding(&self) -> EncodingId;
+}
 
-    /// Thrown when there is no matching 32 bit instruction
+impl ASM {
+    /// Parses the stream, decoding UNPREDICTABLE instructions according to
+    /// the [`Policy`].
     ///
-    /// Occurred while parsing the block in question
-    Invalid32Bit(&'static str),
-
-    /// Thrown when there is no matching
-    Incomplete32Bit,
-
-    /// Thrown when a field in an identifier is incorrect
-    InvalidField(String),
-
-    /// Thrown when a target register does not exist.
-    InvalidRegister(u8),
-
-    /// Thrown when an unpredictable instruction is used
-    Unpredictable,
-
-    /// Thrown when an undefined instruction is used
-    Undefined,
-
-    /// Thrown when a non covered case is reached
-    IncompleteParser,
-
-    /// Thrown when an invalid condition is requested
-    InvalidCondition,
-
-    /// Thrown when the parsing fails part way through parsing
-    PartiallyParsed(Box<Self>, Vec<Operation>),
+    /// [`ASM::parse`] uses [`Policy::Permissive`].
+    pub fn parse_with_policy<T: Stream>(iter: &mut T, policy: Policy) -> Result<Self, ParseError> {
+        let mut stmts = Vec::new();
+        let mut offset = 0;
+        while let Some(_halfword) = iter.peek::<1>() as Option<u16> {
+            match Operation::decode(iter, policy) {
+                Ok(decoded) => {
+                    offset += decoded.size.bytes();
+                    stmts.push(decoded)
+                }
+                Err(e) => return Err(ParseError::partially_parsed(e.at(offset), stmts)),
+            };
+        }
+        Ok(Self { statements: stmts })
+    }
 
-    /// Sub-crate [`arch`] threw an error
-    ArchError(ArchError),
+    /// Returns the statements in the program, along with their raw bits and
+    /// size.
+    pub fn statements(&self) -> &[DecodedInstruction] {
+        &self.statements
+    }
 
-    /// Thrown when internal logic is faulty, this should never occur
-    InternalError(&'static str),
+    /// Returns the UNPREDICTABLE statements, by their index in the program,
+    /// that were decoded with a permissive [`Policy`].
+    pub fn unpredictable(&self) -> impl Iterator<Item = (usize, Reason)> + '_ {
+        self.statements
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, stmt)| Some((idx, stmt.unpredictable?)))
+    }
 }
 
 impl Parse for ASM {
@@ -238,22 +261,83 @@ impl Parse for ASM {
     where
         Self: Sized,
     {
-        let mut stmts = Vec::new();
-        while let Some(_halfword) = iter.peek::<1>() as Option<u16> {
-            match Operation::parse(iter) {
-                Ok(el) => stmts.push(el),
-                Err(e) => {
-                    return Err(ParseError::PartiallyParsed(
-                        Box::new(e),
-                        stmts.into_iter().map(|el| el.1).collect(),
-                    ))
-                }
-            };
+        Self::parse_with_policy(iter, Policy::Permissive)
+    }
+}
+
+impl operation::Operation {
+    /// Decodes a single operation along with its encoding, decoding
+    /// UNPREDICTABLE instructions according to the [`Policy`].
+    ///
+    /// [`Operation::parse`](Parse::parse) uses [`Policy::Permissive`].
+    pub fn decode<T: Stream>(
+        iter: &mut T,
+        policy: Policy,
+    ) -> Result<DecodedInstruction, ParseError> {
+        let halfword: Option<u16> = iter.peek::<1>();
+        if halfword.is_none() {
+            return Err(ErrorKind::IncompleteProgram.in_table("A5.1"));
+        }
+        let halfword = halfword.unwrap();
+
+        // Nothing is consumed until the instruction is known to be accepted
+        // by the policy, so that the caller can skip past it.
+        let (raw, size, decoded) = match halfword >> 11 {
+            0b11101..=0b11111 => {
+                let raw = match iter.peek::<1>() as Option<u32> {
+                    Some(word) => Raw::Word(word),
+                    None => Raw::Halfword(halfword),
+                };
+                (
+                    raw,
+                    InstructionSize::Wide,
+                    B32::parse_internal(iter).map_err(|e| e.in_table("A5.9")),
+                )
+            }
+            _ => (
+                Raw::Halfword(halfword),
+                InstructionSize::Narrow,
+                B16::parse_internal(iter).map_err(|e| e.in_table("A5.1")),
+            ),
+        };
+        let (encoding, op) = decoded.map_err(|e| e.with_raw(raw))?;
+        let unpredictable = Reason::of(&op, raw);
+        let op = match unpredictable {
+            Some(reason) if policy == Policy::Strict => {
+                return Err(ErrorKind::Unpredictable(reason)
+                    .in_table(encoding.table)
+                    .with_raw(raw))
+            }
+            Some(reason) => policy.apply(op, reason),
+            None => op,
+        };
+        let consumed = match size {
+            InstructionSize::Narrow => (iter.consume::<1>() as Option<[u16; 1]>).is_some(),
+            InstructionSize::Wide => (iter.consume::<1>() as Option<[u32; 1]>).is_some(),
+        };
+        if !consumed {
+            return Err(ErrorKind::IncompleteProgram
+                .in_table(encoding.table)
+                .with_raw(raw));
         }
-        Ok(stmts.into())
+        Ok(DecodedInstruction {
+            op,
+            encoding,
+            raw: match raw {
+                Raw::Halfword(halfword) => halfword as u32,
+                Raw::Word(word) => word,
+            },
+            size,
+            unpredictable,
+        })
     }
 }
 
+/// Decodes a single operation along with its size in bits.
+///
+/// The size is a bare bit count for compatibility with earlier versions,
+/// [`Operation::decode`] returns the [`InstructionSize`] along with the
+/// encoding and raw bits of the operation.
 impl Parse for operation::Operation {
     type Target = (usize, operation::Operation);
 
@@ -261,28 +345,26 @@ impl Parse for operation::Operation {
     where
         Self: Sized,
     {
-        let halfword: Option<u16> = iter.peek::<1>();
-        if halfword.is_none() {
-            return Err(ParseError::IncompleteProgram);
-        }
-        let halfword = halfword.unwrap();
-
-        Ok(match halfword >> 11 {
-            0b11101..=0b11111 => B32::parse(iter)?,
-            _ => B16::parse(iter)?,
-        })
+        Self::decode(iter, Policy::Permissive).map(|decoded| (decoded.size.bits(), decoded.op))
     }
 }
 
-impl From<Vec<(usize, Operation)>> for ASM {
-    fn from(value: Vec<(usize, operation::Operation)>) -> Self {
-        Self { statements: value }
+impl From<ASM> for Vec<DecodedInstruction> {
+    fn from(value: ASM) -> Vec<DecodedInstruction> {
+        value.statements
     }
 }
 
+/// Returns the operations along with their size in bits, kept for
+/// compatibility with earlier versions. Use [`ASM::statements`] for the
+/// [`InstructionSize`] and raw bits.
 impl From<ASM> for Vec<(usize, Operation)> {
     fn from(value: ASM) -> Vec<(usize, Operation)> {
-        value.statements
+        value
+            .statements
+            .into_iter()
+            .map(|stmt| (stmt.size.bits(), stmt.op))
+            .collect()
     }
 }
 
@@ -294,13 +376,18 @@ pub mod prelude {
             self,
             set_flags::SetFlags,
             wrapper_types::*,
+            ApsrMask,
+            BarrierOption,
             Condition,
+            Hint,
             ImmShift,
             Register,
             RegisterList,
             Shift,
+            SpecialRegister,
         },
         buffer::PeekableBuffer,
+        decoder::Decoder,
         operation::{self, Operation},
     };
 }
@@ -0,0 +1,1525 @@
+//! Lifts [`Operation`]s in to a small [`intermediate
+//! representation`](ir).
+//!
+//! Each instruction is translated in to a sequence of side effect explicit
+//! [`Statement`]s. This allows analyses to share a single set of semantics
+//! rather than re-deriving them from the [`Operation`] enum.
+//!
+//! Conditional execution through [`It`](crate::operation::It) blocks is left
+//! to the caller, the lifted statements describe the instruction as if its
+//! condition passed. The only exceptions are the conditional branches
+//! [`B`](crate::operation::B) and [`Cbz`](crate::operation::Cbz) which lift
+//! to a [`ConditionalBranch`](Statement::ConditionalBranch).
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{
+//!     lift::{
+//!         ir::{Statement, Width},
+//!         Context,
+//!     },
+//!     prelude::*,
+//! };
+//!
+//! let op: Operation = operation::AddImmediate::builder()
+//!     .set_s(Some(SetFlags::Literal(false)))
+//!     .set_rd(Some(Register::R0))
+//!     .set_rn(Register::R1)
+//!     .set_imm(4)
+//!     .complete()
+//!     .into();
+//!
+//! let statements = op.lift(&Context::default());
+//! assert!(matches!(
+//!     statements.last(),
+//!     Some(Statement::WriteRegister {
+//!         register: Register::R0,
+//!         ..
+//!     })
+//! ));
+//! ```
+
+pub mod ir;
+mod semantics;
+
+use ir::{BinaryOp, BranchKind, Expression, Flag, Local, Statement, UnaryOp, Value, Width};
+
+use crate::{
+    arch::{Condition, FlagExpr, ImmShift, Register, Shift},
+    operation::Operation,
+    InstructionSize,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The context that an instruction is lifted in.
+pub struct Context {
+    /// True if the instruction is inside of an IT block.
+    ///
+    /// This determines if instructions with
+    /// [`SetFlags::InITBlock`](crate::arch::SetFlags::InITBlock) set the flags.
+    pub in_it_block: bool,
+
+    /// Size of the instruction.
+    pub size: InstructionSize,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            in_it_block: false,
+            size: InstructionSize::Wide,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// Lifts a sequence of [`Operation`]s.
+///
+/// All [`Local`]s are unique within the lifter, which allows straight line
+/// sequences of instructions to be lifted in to a single list of statements.
+pub struct Lifter {
+    next: usize,
+    statements: Vec<Statement>,
+    /// The branch of the current instruction, this is emitted after all other
+    /// statements.
+    branch: Option<Statement>,
+}
+
+impl Operation {
+    /// Lifts the operation in to a list of [`Statement`]s.
+    pub fn lift(&self, context: &Context) -> Vec<Statement> {
+        let mut lifter = Lifter::new();
+        lifter.lift(self, context);
+        lifter.finish()
+    }
+}
+
+impl Lifter {
+    /// Creates a new lifter with no statements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the statements for the operation.
+    pub fn lift(&mut self, operation: &Operation, context: &Context) {
+        self.operation(operation, context);
+        if let Some(branch) = self.branch.take() {
+            self.emit(branch);
+        }
+    }
+
+    /// Returns the statements that have been lifted.
+    pub fn finish(self) -> Vec<Statement> {
+        self.statements
+    }
+
+    fn emit(&mut self, statement: Statement) {
+        self.statements.push(statement);
+    }
+
+    fn local(&mut self, width: Width) -> Local {
+        let id = self.next;
+        self.next += 1;
+        Local { id, width }
+    }
+
+    fn assign(&mut self, width: Width, expression: Expression) -> Value {
+        let dst = self.local(width);
+        self.emit(Statement::Assign { dst, expression });
+        dst.into()
+    }
+
+    fn word(value: u32) -> Value {
+        Value::Constant(value as u64, Width::Word)
+    }
+
+    fn bit(value: bool) -> Value {
+        Value::Constant(value as u64, Width::Bit)
+    }
+
+    fn constant(value: u64, width: Width) -> Value {
+        Value::Constant(value & width.mask(), width)
+    }
+
+    fn read(&mut self, register: Register) -> Value {
+        let dst = self.local(Width::Word);
+        self.emit(Statement::ReadRegister { dst, register });
+        dst.into()
+    }
+
+    /// Writes the register, writes to the PC are treated as an
+    /// `ALUWritePC`.
+    fn write(&mut self, register: Register, value: Value) {
+        match register {
+            Register::PC => {
+                let target = self.binary(BinaryOp::And, value, Self::word(!1));
+                self.branch(target, BranchKind::Jump)
+            }
+            register => self.emit(Statement::WriteRegister { register, value }),
+        }
+    }
+
+    /// Writes the register, writes to the PC are treated as a
+    /// `LoadWritePC`.
+    fn write_loaded(&mut self, register: Register, value: Value, kind: BranchKind) {
+        match register {
+            Register::PC => self.branch(value, kind),
+            register => self.emit(Statement::WriteRegister { register, value }),
+        }
+    }
+
+    fn read_flag(&mut self, flag: Flag) -> Value {
+        let dst = self.local(Width::Bit);
+        self.emit(Statement::ReadFlag { dst, flag });
+        dst.into()
+    }
+
+    fn write_flag(&mut self, flag: Flag, value: Value) {
+        self.emit(Statement::WriteFlag { flag, value })
+    }
+
+    fn load(&mut self, address: Value, width: Width) -> Value {
+        let dst = self.local(width);
+        self.emit(Statement::Load { dst, address });
+        dst.into()
+    }
+
+    fn store(&mut self, address: Value, value: Value) {
+        self.emit(Statement::Store { address, value })
+    }
+
+    fn branch(&mut self, target: Value, kind: BranchKind) {
+        self.branch = Some(Statement::Branch { target, kind });
+    }
+
+    fn conditional_branch(&mut self, condition: Value, target: Value) {
+        self.branch = Some(Statement::ConditionalBranch { condition, target });
+    }
+
+    fn unary(&mut self, op: UnaryOp, value: Value) -> Value {
+        let width = match op {
+            UnaryOp::Clz => Width::Word,
+            _ => value.width(),
+        };
+        self.assign(width, Expression::Unary(op, value))
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: Value, rhs: Value) -> Value {
+        debug_assert_eq!(lhs.width(), rhs.width(), "{op:?}");
+        let width = match op {
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::ULt
+            | BinaryOp::ULe
+            | BinaryOp::SLt
+            | BinaryOp::SLe => Width::Bit,
+            _ => lhs.width(),
+        };
+        self.assign(width, Expression::Binary(op, lhs, rhs))
+    }
+
+    fn add(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binary(BinaryOp::Add, lhs, rhs)
+    }
+
+    fn sub(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binary(BinaryOp::Sub, lhs, rhs)
+    }
+
+    fn and(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binary(BinaryOp::And, lhs, rhs)
+    }
+
+    fn or(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binary(BinaryOp::Or, lhs, rhs)
+    }
+
+    fn xor(&mut self, lhs: Value, rhs: Value) -> Value {
+        self.binary(BinaryOp::Xor, lhs, rhs)
+    }
+
+    fn not(&mut self, value: Value) -> Value {
+        self.unary(UnaryOp::Not, value)
+    }
+
+    fn ite(&mut self, condition: Value, then: Value, otherwise: Value) -> Value {
+        debug_assert_eq!(then.width(), otherwise.width());
+        self.assign(then.width(), Expression::Ite(condition, then, otherwise))
+    }
+
+    fn extract(&mut self, value: Value, high: u8, low: u8) -> Value {
+        let width = match high - low + 1 {
+            1 => Width::Bit,
+            8 => Width::Byte,
+            16 => Width::Half,
+            32 => Width::Word,
+            64 => Width::Double,
+            _ => unreachable!("Extracted field must have a valid width"),
+        };
+        self.assign(width, Expression::Extract { value, high, low })
+    }
+
+    fn zero_extend(&mut self, value: Value, width: Width) -> Value {
+        if value.width() == width {
+            return value;
+        }
+        self.assign(width, Expression::ZeroExtend(value, width))
+    }
+
+    fn sign_extend(&mut self, value: Value, width: Width) -> Value {
+        if value.width() == width {
+            return value;
+        }
+        self.assign(width, Expression::SignExtend(value, width))
+    }
+
+    fn concat(&mut self, high: Value, low: Value) -> Value {
+        let width = match high.width().bits() + low.width().bits() {
+            16 => Width::Half,
+            32 => Width::Word,
+            64 => Width::Double,
+            _ => unreachable!("Concatenation must have a valid width"),
+        };
+        self.assign(width, Expression::Concat(high, low))
+    }
+
+    /// Returns the most significant bit of the value.
+    fn msb(&mut self, value: Value) -> Value {
+        let high = (value.width().bits() - 1) as u8;
+        self.extract(value, high, high)
+    }
+
+    /// Returns true if the value is zero.
+    fn is_zero(&mut self, value: Value) -> Value {
+        let zero = Self::constant(0, value.width());
+        self.binary(BinaryOp::Eq, value, zero)
+    }
+
+    /// Rotates the value right by a constant amount.
+    fn ror(&mut self, value: Value, amount: u32) -> Value {
+        let bits = value.width().bits();
+        let amount = amount % bits;
+        if amount == 0 {
+            return value;
+        }
+        let width = value.width();
+        let right = self.binary(BinaryOp::LShr, value, Self::constant(amount as u64, width));
+        let left = self.binary(
+            BinaryOp::Shl,
+            value,
+            Self::constant((bits - amount) as u64, width),
+        );
+        self.or(right, left)
+    }
+
+    /// Sets the N and Z flags from the result.
+    fn set_nz(&mut self, result: Value) {
+        let n = self.msb(result);
+        let z = self.is_zero(result);
+        self.write_flag(Flag::N, n);
+        self.write_flag(Flag::Z, z);
+    }
+
+    /// The `AddWithCarry` function from section A2.2.1.
+    ///
+    /// Returns the result, the carry and the overflow.
+    fn add_with_carry(&mut self, x: Value, y: Value, carry_in: Value) -> (Value, Value, Value) {
+        let wide_x = self.zero_extend(x, Width::Double);
+        let wide_y = self.zero_extend(y, Width::Double);
+        let wide_c = self.zero_extend(carry_in, Width::Double);
+        let sum = self.add(wide_x, wide_y);
+        let sum = self.add(sum, wide_c);
+        let result = self.extract(sum, 31, 0);
+        let carry = self.extract(sum, 32, 32);
+
+        // Overflow if both operands have the same sign and the result does
+        // not.
+        let x_result = self.xor(x, result);
+        let y_result = self.xor(y, result);
+        let overflow = self.and(x_result, y_result);
+        let overflow = self.msb(overflow);
+        (result, carry, overflow)
+    }
+
+    /// Writes the result and the flags of an `AddWithCarry`.
+    fn write_arithmetic(
+        &mut self,
+        rd: Register,
+        (result, carry, overflow): (Value, Value, Value),
+        set_flags: bool,
+    ) {
+        self.write(rd, result);
+        if set_flags && rd != Register::PC {
+            self.set_nz(result);
+            self.write_flag(Flag::C, carry);
+            self.write_flag(Flag::V, overflow);
+        }
+    }
+
+    /// Writes the result and the flags of a logical operation.
+    fn write_logical(
+        &mut self,
+        rd: Register,
+        result: Value,
+        carry: Option<Value>,
+        set_flags: bool,
+    ) {
+        self.write(rd, result);
+        if set_flags && rd != Register::PC {
+            self.set_nz(result);
+            if let Some(carry) = carry {
+                self.write_flag(Flag::C, carry);
+            }
+        }
+    }
+
+    /// The `Shift_C` function from section A7.4.2 with a constant shift.
+    ///
+    /// Returns the result and the carry out.
+    fn shift_c(
+        &mut self,
+        value: Value,
+        shift: &Option<ImmShift>,
+        carry_in: Value,
+    ) -> (Value, Value) {
+        let (shift_t, amount) = match shift {
+            Some(shift) => (shift.shift_t.clone(), shift.shift_n as u32),
+            None => (Shift::Lsl, 0),
+        };
+        if amount == 0 && shift_t != Shift::Rrx {
+            return (value, carry_in);
+        }
+        match shift_t {
+            Shift::Lsl => {
+                let result = match amount {
+                    0..=31 => self.binary(BinaryOp::Shl, value, Self::word(amount)),
+                    _ => Self::word(0),
+                };
+                let carry = match amount {
+                    1..=32 => {
+                        let bit = (32 - amount) as u8;
+                        self.extract(value, bit, bit)
+                    }
+                    _ => Self::bit(false),
+                };
+                (result, carry)
+            }
+            Shift::Lsr => {
+                let result = self.binary(BinaryOp::LShr, value, Self::word(amount));
+                let carry = match amount {
+                    1..=32 => {
+                        let bit = (amount - 1) as u8;
+                        self.extract(value, bit, bit)
+                    }
+                    _ => Self::bit(false),
+                };
+                (result, carry)
+            }
+            Shift::Asr => {
+                let result = self.binary(BinaryOp::AShr, value, Self::word(amount));
+                let bit = (amount.min(32) - 1) as u8;
+                let carry = self.extract(value, bit, bit);
+                (result, carry)
+            }
+            Shift::Ror => {
+                let result = self.ror(value, amount);
+                let carry = self.msb(result);
+                (result, carry)
+            }
+            Shift::Rrx => {
+                let carry = self.extract(value, 0, 0);
+                let shifted = self.binary(BinaryOp::LShr, value, Self::word(1));
+                let carry_in = self.zero_extend(carry_in, Width::Word);
+                let carry_in = self.binary(BinaryOp::Shl, carry_in, Self::word(31));
+                (self.or(shifted, carry_in), carry)
+            }
+        }
+    }
+
+    /// The `Shift` function from section A7.4.2 with a constant shift.
+    fn shift(&mut self, value: Value, shift: &Option<ImmShift>) -> Value {
+        let carry = match shift {
+            Some(ImmShift {
+                shift_t: Shift::Rrx,
+                ..
+            }) => self.read_flag(Flag::C),
+            _ => Self::bit(false),
+        };
+        self.shift_c(value, shift, carry).0
+    }
+
+    /// The `Shift_C` function from section A7.4.2 with the shift amount in a
+    /// register.
+    fn shift_c_register(
+        &mut self,
+        value: Value,
+        shift_t: Shift,
+        amount: Value,
+        carry_in: Value,
+    ) -> (Value, Value) {
+        let amount = self.and(amount, Self::word(0xff));
+        let amount_is_zero = self.is_zero(amount);
+        let amount_m1 = self.sub(amount, Self::word(1));
+        let (result, carry) = match shift_t {
+            Shift::Lsl => {
+                let result = self.binary(BinaryOp::Shl, value, amount);
+                let carry = self.binary(BinaryOp::Shl, value, amount_m1);
+                (result, self.msb(carry))
+            }
+            Shift::Lsr => {
+                let result = self.binary(BinaryOp::LShr, value, amount);
+                let carry = self.binary(BinaryOp::LShr, value, amount_m1);
+                (result, self.extract(carry, 0, 0))
+            }
+            Shift::Asr => {
+                let result = self.binary(BinaryOp::AShr, value, amount);
+                let carry = self.binary(BinaryOp::AShr, value, amount_m1);
+                (result, self.extract(carry, 0, 0))
+            }
+            Shift::Ror | Shift::Rrx => {
+                let rotation = self.and(amount, Self::word(31));
+                let right = self.binary(BinaryOp::LShr, value, rotation);
+                let inverse = self.sub(Self::word(32), rotation);
+                let left = self.binary(BinaryOp::Shl, value, inverse);
+                let result = self.or(right, left);
+                (result, self.msb(result))
+            }
+        };
+        let carry = self.ite(amount_is_zero, carry_in, carry);
+        (result, carry)
+    }
+
+    /// Computes the value of the condition from the flags.
+    fn condition(&mut self, condition: &Condition) -> Value {
+        self.flag_expr(&condition.formula())
+    }
+
+    fn flag_expr(&mut self, expr: &FlagExpr) -> Value {
+        match expr {
+            FlagExpr::True => Self::bit(true),
+            FlagExpr::Flag(flag) => self.read_flag((*flag).into()),
+            FlagExpr::Not(expr) => {
+                let value = self.flag_expr(expr);
+                self.not(value)
+            }
+            FlagExpr::And(lhs, rhs) => {
+                let (lhs, rhs) = (self.flag_expr(lhs), self.flag_expr(rhs));
+                self.and(lhs, rhs)
+            }
+            FlagExpr::Or(lhs, rhs) => {
+                let (lhs, rhs) = (self.flag_expr(lhs), self.flag_expr(rhs));
+                self.or(lhs, rhs)
+            }
+            FlagExpr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.flag_expr(lhs), self.flag_expr(rhs));
+                self.binary(BinaryOp::Eq, lhs, rhs)
+            }
+        }
+    }
+
+    /// Saturates the signed value to `bits` bits.
+    ///
+    /// Returns the saturated value truncated to 32 bits and a bit that is
+    /// set if the value was saturated.
+    fn signed_saturate(&mut self, value: Value, bits: u32) -> (Value, Value) {
+        let width = value.width();
+        let max = Self::constant((1u64 << (bits - 1)) - 1, width);
+        let min = Self::constant((-(1i64 << (bits - 1))) as u64, width);
+        self.clamp(value, min, max, BinaryOp::SLt)
+    }
+
+    /// Saturates the signed value to an unsigned value with `bits` bits.
+    ///
+    /// Returns the saturated value truncated to 32 bits and a bit that is
+    /// set if the value was saturated.
+    fn unsigned_saturate(&mut self, value: Value, bits: u32) -> (Value, Value) {
+        let width = value.width();
+        let max = Self::constant((1u64 << bits) - 1, width);
+        let min = Self::constant(0, width);
+        self.clamp(value, min, max, BinaryOp::SLt)
+    }
+
+    fn clamp(&mut self, value: Value, min: Value, max: Value, less: BinaryOp) -> (Value, Value) {
+        let below = self.binary(less, value, min);
+        let above = self.binary(less, max, value);
+        let result = self.ite(above, max, value);
+        let result = self.ite(below, min, result);
+        let saturated = self.or(below, above);
+        let result = match result.width() {
+            Width::Double => self.extract(result, 31, 0),
+            _ => result,
+        };
+        (result, saturated)
+    }
+
+    /// Sets the Q flag if the bit is set.
+    fn saturate_q(&mut self, saturated: Value) {
+        let q = self.read_flag(Flag::Q);
+        let q = self.or(q, saturated);
+        self.write_flag(Flag::Q, q);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{
+        ir::{BranchKind, Flag, Intrinsic, Local, Statement, Value},
+        Context,
+    };
+    use crate::{arch::ITCondition, prelude::*, InstructionSize, Policy};
+
+    #[derive(Debug, PartialEq)]
+    enum Exit {
+        Next,
+        Branch(u32, BranchKind),
+        Conditional(u32, bool),
+    }
+
+    /// A small concrete interpreter for the intermediate representation.
+    #[derive(Default)]
+    struct Machine {
+        registers: [u32; 16],
+        flags: HashMap<Flag, bool>,
+        memory: HashMap<u32, u8>,
+        address: u32,
+    }
+
+    impl Machine {
+        fn reg(&self, register: Register) -> u32 {
+            self.registers[u8::from(register) as usize]
+        }
+
+        fn set(&mut self, register: Register, value: u32) {
+            self.registers[u8::from(register) as usize] = value;
+        }
+
+        fn flag(&self, flag: Flag) -> bool {
+            *self.flags.get(&flag).unwrap_or(&false)
+        }
+
+        fn run(&mut self, operation: &Operation, size: InstructionSize) -> Exit {
+            let context = Context {
+                in_it_block: false,
+                size,
+            };
+            let mut locals = HashMap::new();
+            let mut exit = Exit::Next;
+            for statement in operation.lift(&context) {
+                assert_eq!(exit, Exit::Next, "Branch must be the last statement");
+                let lookup =
+                    |locals: &HashMap<usize, u64>, local: Local| locals.get(&local.id).copied();
+                let value = |locals: &HashMap<usize, u64>, value: Value| {
+                    value.evaluate(|local| lookup(locals, local)).unwrap()
+                };
+                match statement {
+                    Statement::Assign { dst, expression } => {
+                        let result = expression.evaluate(|local| lookup(&locals, local)).unwrap();
+                        locals.insert(dst.id, result & dst.width.mask());
+                    }
+                    Statement::ReadRegister { dst, register } => {
+                        let result = match register {
+                            Register::PC => self.address.wrapping_add(4),
+                            register => self.reg(register),
+                        };
+                        locals.insert(dst.id, result as u64);
+                    }
+                    Statement::WriteRegister { register, value: v } => {
+                        assert_ne!(register, Register::PC);
+                        self.set(register, value(&locals, v) as u32);
+                    }
+                    Statement::ReadFlag { dst, flag } => {
+                        locals.insert(dst.id, self.flag(flag) as u64);
+                    }
+                    Statement::WriteFlag { flag, value: v } => {
+                        self.flags.insert(flag, value(&locals, v) != 0);
+                    }
+                    Statement::Load { dst, address } => {
+                        let address = value(&locals, address) as u32;
+                        let mut result = 0;
+                        for byte in (0..dst.width.bits() / 8).rev() {
+                            let byte = self.memory.get(&(address + byte)).unwrap_or(&0);
+                            result = (result << 8) | *byte as u64;
+                        }
+                        locals.insert(dst.id, result);
+                    }
+                    Statement::Store { address, value: v }
+                    | Statement::StoreExclusive {
+                        address, value: v, ..
+                    } => {
+                        let address = value(&locals, address) as u32;
+                        let data = value(&locals, v);
+                        for byte in 0..v.width().bits() / 8 {
+                            self.memory
+                                .insert(address + byte, (data >> (8 * byte)) as u8);
+                        }
+                        if let Statement::StoreExclusive { status, .. } = statement {
+                            locals.insert(status.id, 0);
+                        }
+                    }
+                    Statement::ReadSpecial { dst, .. } => {
+                        locals.insert(dst.id, 0);
+                    }
+                    Statement::WriteSpecial { .. } | Statement::Intrinsic(_) => {}
+                    Statement::Branch { target, kind } => {
+                        exit = Exit::Branch(value(&locals, target) as u32, kind)
+                    }
+                    Statement::ConditionalBranch { condition, target } => {
+                        exit = Exit::Conditional(
+                            value(&locals, target) as u32,
+                            value(&locals, condition) != 0,
+                        )
+                    }
+                }
+            }
+            exit
+        }
+    }
+
+    #[test]
+    fn test_add_flags() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x7fff_ffff);
+        machine.set(Register::R2, 1);
+        let op: Operation = operation::AddRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R2)
+            .set_shift(None)
+            .complete()
+            .into();
+        assert_eq!(machine.run(&op, InstructionSize::Wide), Exit::Next);
+        assert_eq!(machine.reg(Register::R0), 0x8000_0000);
+        assert!(machine.flag(Flag::N));
+        assert!(!machine.flag(Flag::Z));
+        assert!(!machine.flag(Flag::C));
+        assert!(machine.flag(Flag::V));
+
+        let op: Operation = operation::CmpImmediate::builder()
+            .set_rn(Register::R2)
+            .set_imm(1)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert!(!machine.flag(Flag::N));
+        assert!(machine.flag(Flag::Z));
+        assert!(machine.flag(Flag::C));
+        assert!(!machine.flag(Flag::V));
+    }
+
+    #[test]
+    fn test_set_flags_in_it_block() {
+        let op: Operation = operation::AddImmediate::builder()
+            .set_s(Some(SetFlags::InITBlock(false)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_imm(1)
+            .complete()
+            .into();
+        let writes_flags = |statements: Vec<Statement>| {
+            statements
+                .iter()
+                .any(|statement| matches!(statement, Statement::WriteFlag { .. }))
+        };
+        assert!(writes_flags(op.lift(&Context {
+            in_it_block: false,
+            size: InstructionSize::Narrow
+        })));
+        assert!(!writes_flags(op.lift(&Context {
+            in_it_block: true,
+            size: InstructionSize::Narrow
+        })));
+    }
+
+    #[test]
+    fn test_shift_register() {
+        let values = [0x8000_0001, 0x1234_5678, 0xffff_ffff, 0x4000_0000];
+        let amounts = [0, 1, 4, 31, 32, 33, 255, 256, 0x1_0004];
+        for shift in [Shift::Lsl, Shift::Lsr, Shift::Asr, Shift::Ror] {
+            for value in values {
+                for amount in amounts {
+                    for carry in [false, true] {
+                        let mut machine = Machine::default();
+                        machine.set(Register::R1, value);
+                        machine.set(Register::R2, amount);
+                        machine.flags.insert(Flag::C, carry);
+                        let (rd, rn, rm, s) = (
+                            Register::R0,
+                            Register::R1,
+                            Register::R2,
+                            Some(SetFlags::Literal(true)),
+                        );
+                        let op: Operation = match shift {
+                            Shift::Lsl => operation::LslRegister { s, rd, rn, rm }.into(),
+                            Shift::Lsr => operation::LsrRegister { s, rd, rn, rm }.into(),
+                            Shift::Asr => operation::AsrRegister { s, rd, rn, rm }.into(),
+                            _ => operation::RorRegister { s, rd, rn, rm }.into(),
+                        };
+                        machine.run(&op, InstructionSize::Wide);
+
+                        let expected = shift.apply_by_register(value, amount, carry);
+                        assert_eq!(
+                            (machine.reg(Register::R0), machine.flag(Flag::C)),
+                            expected,
+                            "{shift:?} {value:#x} {amount}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_immediate() {
+        let values = [0x8000_0001, 0x1234_5678, 0xffff_ffff, 0x4000_0000];
+        for encoded in 0..=0b111_1111u8 {
+            let shift = ImmShift::from((Shift::try_from(encoded >> 5).unwrap(), encoded & 0b11111));
+            for value in values {
+                for carry in [false, true] {
+                    let mut machine = Machine::default();
+                    machine.set(Register::R2, value);
+                    machine.flags.insert(Flag::C, carry);
+                    let op: Operation = operation::OrrRegister::builder()
+                        .set_s(Some(SetFlags::Literal(true)))
+                        .set_rd(Some(Register::R0))
+                        .set_rn(Register::R1)
+                        .set_rm(Register::R2)
+                        .set_shift(Some(shift.clone()))
+                        .complete()
+                        .into();
+                    machine.run(&op, InstructionSize::Wide);
+                    assert_eq!(
+                        (machine.reg(Register::R0), machine.flag(Flag::C)),
+                        shift.apply(value, carry),
+                        "{shift:?} {value:#x}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_add_sub() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x80ff_7f01);
+        machine.set(Register::R2, 0x8001_0101);
+        let op: Operation = operation::Uadd8::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R2)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0000_8002);
+        let ge: Vec<bool> = (0..4).map(|i| machine.flag(Flag::Ge(i))).collect();
+        assert_eq!(ge, [false, false, true, true]);
+
+        let op: Operation = operation::Ssub16::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R2)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xff02_8200);
+        let ge: Vec<bool> = (0..4).map(|i| machine.flag(Flag::Ge(i))).collect();
+        assert_eq!(ge, [false, false, false, false]);
+
+        let op: Operation = operation::Uqsub8::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R2)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0000_0000);
+
+        let op: Operation = operation::Shadd16::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R2)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x8080_4001);
+
+        machine.set(Register::R3, 0x1122_3344);
+        machine.set(Register::R4, 0xaabb_ccdd);
+        machine.flags.insert(Flag::Ge(0), true);
+        machine.flags.insert(Flag::Ge(1), true);
+        let op: Operation = operation::Sel::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R3)
+            .set_rm(Register::R4)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xaabb_3344);
+    }
+
+    #[test]
+    fn test_saturate() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x7fff_ffff);
+        machine.set(Register::R2, 1);
+        let op: Operation = operation::Qadd::builder()
+            .set_rd(Some(Register::R0))
+            .set_rm(Register::R1)
+            .set_rn(Register::R2)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x7fff_ffff);
+        assert!(machine.flag(Flag::Q));
+
+        let mut machine = Machine::default();
+        machine.set(Register::R1, (-300i32) as u32);
+        let op: Operation = operation::Ssat::builder()
+            .set_rd(Register::R0)
+            .set_imm(8)
+            .set_rn(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), (-128i32) as u32);
+        assert!(machine.flag(Flag::Q));
+
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0xfff0_0100);
+        let op: Operation = operation::Usat16::builder()
+            .set_rd(Register::R0)
+            .set_imm(8)
+            .set_rn(Register::R1)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0000_00ff);
+        assert!(machine.flag(Flag::Q));
+
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x10);
+        let op: Operation = operation::Usat::builder()
+            .set_rd(Register::R0)
+            .set_imm(8)
+            .set_rn(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x10);
+        assert!(!machine.flag(Flag::Q));
+    }
+
+    #[test]
+    fn test_multiply() {
+        let mut machine = Machine::default();
+        machine.set(Register::R2, 0xffff_ffff);
+        machine.set(Register::R3, 0xffff_ffff);
+        let op: Operation = operation::Umull::builder()
+            .set_rdlo(Register::R0)
+            .set_rdhi(Register::R1)
+            .set_rn(Register::R2)
+            .set_rm(Register::R3)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0000_0001);
+        assert_eq!(machine.reg(Register::R1), 0xffff_fffe);
+
+        let op: Operation = operation::Smlal::builder()
+            .set_rdlo(Register::R0)
+            .set_rdhi(Register::R1)
+            .set_rn(Register::R2)
+            .set_rm(Register::R3)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0000_0002);
+        assert_eq!(machine.reg(Register::R1), 0xffff_fffe);
+
+        machine.set(Register::R2, 0x8000_8000);
+        machine.set(Register::R3, 0x8000_8000);
+        machine.set(Register::R4, 0);
+        let op: Operation = operation::Smlad::builder()
+            .set_x(Some(false))
+            .set_rd(Register::R0)
+            .set_rn(Register::R2)
+            .set_rm(Register::R3)
+            .set_ra(Register::R4)
+            .complete()
+            .into();
+        machine.run(&op, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x8000_0000);
+        assert!(machine.flag(Flag::Q));
+    }
+
+    #[test]
+    fn test_bit_manipulation() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x1234_5678);
+        let rev: Operation = operation::Rev::builder()
+            .set_rd(Register::R0)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        machine.run(&rev, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x7856_3412);
+
+        let rbit: Operation = operation::Rbit::builder()
+            .set_rd(Register::R0)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        machine.run(&rbit, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x1234_5678u32.reverse_bits());
+
+        let ubfx: Operation = operation::Ubfx::builder()
+            .set_rd(Register::R0)
+            .set_rn(Register::R1)
+            .set_lsb(4)
+            .set_width(8)
+            .complete()
+            .into();
+        machine.run(&ubfx, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x67);
+
+        let sbfx: Operation = operation::Sbfx::builder()
+            .set_rd(Register::R0)
+            .set_rn(Register::R1)
+            .set_lsb(3)
+            .set_width(4)
+            .complete()
+            .into();
+        machine.run(&sbfx, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xffff_ffff);
+
+        machine.set(Register::R0, 0xffff_ffff);
+        let bfi: Operation = operation::Bfi::builder()
+            .set_rd(Register::R0)
+            .set_rn(Register::R1)
+            .set_lsb(8)
+            .set_msb(15)
+            .complete()
+            .into();
+        machine.run(&bfi, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xffff_78ff);
+    }
+
+    #[test]
+    fn test_memory() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0x100);
+        machine.set(Register::R2, 0xdead_beef);
+        let str: Operation = operation::StrImmediate::builder()
+            .set_w(Some(true))
+            .set_index(Some(false))
+            .set_add(true)
+            .set_rt(Register::R2)
+            .set_rn(Register::R1)
+            .set_imm(4)
+            .complete()
+            .into();
+        machine.run(&str, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R1), 0x104);
+        assert_eq!(machine.memory[&0x100], 0xef);
+
+        let ldrsh: Operation = operation::LdrshImmediate::builder()
+            .set_add(false)
+            .set_index(true)
+            .set_wback(false)
+            .set_rt(Register::R0)
+            .set_rn(Register::R1)
+            .set_imm(Some(2))
+            .complete()
+            .into();
+        machine.run(&ldrsh, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xffff_dead);
+
+        machine.set(Register::SP, 0x100);
+        let pop: Operation = operation::Pop::builder()
+            .set_registers(RegisterList {
+                registers: vec![Register::PC, Register::R4],
+            })
+            .complete()
+            .into();
+        machine.memory.insert(0x104, 0x41);
+        assert_eq!(
+            machine.run(&pop, InstructionSize::Narrow),
+            Exit::Branch(0x41, BranchKind::Return)
+        );
+        assert_eq!(machine.reg(Register::R4), 0xdead_beef);
+        assert_eq!(machine.reg(Register::SP), 0x108);
+
+        let push: Operation = operation::Push::builder()
+            .set_registers(RegisterList {
+                registers: vec![Register::LR, Register::R4],
+            })
+            .complete()
+            .into();
+        machine.set(Register::LR, 0x1234_5679);
+        machine.run(&push, InstructionSize::Narrow);
+        assert_eq!(machine.reg(Register::SP), 0x100);
+        assert_eq!(machine.memory[&0x104], 0x79);
+        assert_eq!(machine.memory[&0x100], 0xef);
+    }
+
+    #[test]
+    fn test_branches() {
+        let mut machine = Machine {
+            address: 0x1000,
+            ..Default::default()
+        };
+        let bl: Operation = operation::Bl::builder().set_imm(0x100).complete().into();
+        assert_eq!(machine.run(&bl, InstructionSize::Wide), Exit::Branch(0x1104, BranchKind::Call));
+        assert_eq!(machine.reg(Register::LR), 0x1005);
+
+        machine.set(Register::R3, 0x2001);
+        let blx: Operation = operation::Blx::builder()
+            .set_rm(Register::R3)
+            .complete()
+            .into();
+        assert_eq!(
+            machine.run(&blx, InstructionSize::Narrow),
+            Exit::Branch(0x2001, BranchKind::Call)
+        );
+        assert_eq!(machine.reg(Register::LR), 0x1003);
+
+        let beq: Operation = operation::B::builder()
+            .set_condition(Condition::Eq)
+            .set_imm((-8i32) as u32)
+            .complete()
+            .into();
+        assert_eq!(machine.run(&beq, InstructionSize::Narrow), Exit::Conditional(0xffc, false));
+        machine.flags.insert(Flag::Z, true);
+        assert_eq!(machine.run(&beq, InstructionSize::Narrow), Exit::Conditional(0xffc, true));
+
+        let cbnz: Operation = operation::Cbz::builder()
+            .set_non(Some(true))
+            .set_rn(Register::R3)
+            .set_imm(0x10)
+            .complete()
+            .into();
+        assert_eq!(machine.run(&cbnz, InstructionSize::Narrow), Exit::Conditional(0x1014, true));
+
+        machine.set(Register::R1, 2);
+        machine.memory.insert(0x1004 + 2, 0x20);
+        let tbb: Operation = operation::Tb::builder()
+            .set_is_tbh(Some(false))
+            .set_rn(Register::PC)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        assert_eq!(
+            machine.run(&tbb, InstructionSize::Wide),
+            Exit::Branch(0x1044, BranchKind::Jump)
+        );
+
+        let mov: Operation = operation::MovRegister::builder()
+            .set_s(Some(false))
+            .set_rd(Register::PC)
+            .set_rm(Register::LR)
+            .complete()
+            .into();
+        assert_eq!(
+            machine.run(&mov, InstructionSize::Narrow),
+            Exit::Branch(0x1002, BranchKind::Return)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_flags() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0);
+        machine.set(Register::R2, 1);
+        let subs: Operation = operation::SubRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R2)
+            .set_shift(None)
+            .complete()
+            .into();
+        machine.run(&subs, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0xffff_ffff);
+        assert!(machine.flag(Flag::N));
+        assert!(!machine.flag(Flag::Z));
+        // A borrow clears the carry flag.
+        assert!(!machine.flag(Flag::C));
+        assert!(!machine.flag(Flag::V));
+
+        // 0xffffffff + 0 + !C
+        machine.flags.insert(Flag::C, true);
+        let adcs: Operation = operation::AdcImmediate::builder()
+            .set_s(Some(true))
+            .set_rd(Some(Register::R3))
+            .set_rn(Register::R0)
+            .set_imm(0)
+            .complete()
+            .into();
+        machine.run(&adcs, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R3), 0);
+        assert!(machine.flag(Flag::Z));
+        assert!(machine.flag(Flag::C));
+        assert!(!machine.flag(Flag::N));
+
+        // 1 - 1 - !C with the carry clear borrows one more.
+        machine.flags.insert(Flag::C, false);
+        let sbcs: Operation = operation::SbcRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R3))
+            .set_rn(Register::R2)
+            .set_rm(Register::R2)
+            .set_shift(None)
+            .complete()
+            .into();
+        machine.run(&sbcs, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R3), 0xffff_ffff);
+        assert!(machine.flag(Flag::N));
+        assert!(!machine.flag(Flag::C));
+
+        machine.set(Register::R1, 0x8000_0000);
+        let rsbs: Operation = operation::RsbImmediate::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_imm(0)
+            .complete()
+            .into();
+        machine.run(&rsbs, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x8000_0000);
+        assert!(machine.flag(Flag::V));
+
+        // Flags are left untouched without the S bit.
+        let flags = machine.flags.clone();
+        let sub: Operation = operation::SubImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R2)
+            .set_imm(1)
+            .complete()
+            .into();
+        machine.run(&sub, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0);
+        assert_eq!(machine.flags, flags);
+    }
+
+    #[test]
+    fn test_logical_flags() {
+        let mut machine = Machine::default();
+        machine.set(Register::R1, 0xf0f0_f0f0);
+        let ands: Operation = operation::AndImmediate::builder()
+            .set_s(Some(true))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_imm(0x0f0f_0f0f)
+            .set_carry(Some(true))
+            .complete()
+            .into();
+        machine.flags.insert(Flag::V, true);
+        machine.run(&ands, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0);
+        assert!(machine.flag(Flag::Z));
+        // The carry comes from the expansion of the immediate, overflow is
+        // not affected.
+        assert!(machine.flag(Flag::C));
+        assert!(machine.flag(Flag::V));
+
+        let tst: Operation = operation::TstImmediate::builder()
+            .set_rn(Register::R1)
+            .set_imm(0x8000_0000)
+            .set_carry(Some(false))
+            .complete()
+            .into();
+        machine.run(&tst, InstructionSize::Wide);
+        assert!(machine.flag(Flag::N));
+        assert!(!machine.flag(Flag::Z));
+        assert!(!machine.flag(Flag::C));
+
+        // Without a carry from the immediate the carry flag is kept.
+        machine.flags.insert(Flag::C, true);
+        let movs: Operation = operation::MovImmediate::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Register::R0)
+            .set_imm(0)
+            .set_carry(None)
+            .complete()
+            .into();
+        machine.run(&movs, InstructionSize::Narrow);
+        assert!(machine.flag(Flag::Z));
+        assert!(machine.flag(Flag::C));
+
+        let mvns: Operation = operation::MvnRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Register::R0)
+            .set_rm(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        machine.run(&mvns, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x0f0f_0f0f);
+        assert!(!machine.flag(Flag::N));
+    }
+
+    #[test]
+    fn test_shift_carry() {
+        let shift = |op: Operation, value: u32, carry: bool| {
+            let mut machine = Machine::default();
+            machine.set(Register::R1, value);
+            machine.flags.insert(Flag::C, carry);
+            machine.run(&op, InstructionSize::Wide);
+            (machine.reg(Register::R0), machine.flag(Flag::C))
+        };
+        let s = Some(SetFlags::Literal(true));
+        let (rd, rm) = (Register::R0, Register::R1);
+
+        let lsls = operation::LslImmediate { s, rd, rm, imm: 1 }.into();
+        assert_eq!(shift(lsls, 0x8000_0001, false), (0x2, true));
+        // A shift by zero keeps the carry flag.
+        let lsls = operation::LslImmediate { s, rd, rm, imm: 0 }.into();
+        assert_eq!(shift(lsls, 0x8000_0001, true), (0x8000_0001, true));
+
+        let lsrs = operation::LsrImmediate { s, rd, rm, imm: 4 }.into();
+        assert_eq!(shift(lsrs, 0x18, false), (0x1, true));
+        let asrs = operation::AsrImmediate { s, rd, rm, imm: 4 }.into();
+        assert_eq!(shift(asrs, 0x8000_0000, true), (0xf800_0000, false));
+
+        let rors = operation::RorImmediate {
+            s: Some(true),
+            rd,
+            rm,
+            imm: 1,
+        }
+        .into();
+        assert_eq!(shift(rors, 0x1, false), (0x8000_0000, true));
+
+        // The carry is shifted in to the most significant bit.
+        let rrxs = operation::Rrx {
+            s: Some(true),
+            rd,
+            rm,
+        }
+        .into();
+        assert_eq!(shift(rrxs, 0x2, true), (0x8000_0001, false));
+
+        // The carry flag is not written without the S bit.
+        let lsl = operation::LslImmediate {
+            s: Some(SetFlags::Literal(false)),
+            rd,
+            rm,
+            imm: 1,
+        }
+        .into();
+        assert_eq!(shift(lsl, 0x8000_0001, false), (0x2, false));
+    }
+
+    #[test]
+    fn test_load_store_multiple() {
+        let list = |registers: &[Register]| RegisterList {
+            registers: registers.to_vec(),
+        };
+        let mut machine = Machine::default();
+        machine.set(Register::R0, 0x100);
+        machine.set(Register::R1, 0x1111_1111);
+        machine.set(Register::R2, 0x2222_2222);
+
+        // The lowest register is stored at the lowest address regardless of
+        // the order of the list.
+        let stm: Operation = operation::Stm::builder()
+            .set_w(Some(true))
+            .set_rn(Register::R0)
+            .set_registers(list(&[Register::R2, Register::R1]))
+            .complete()
+            .into();
+        machine.run(&stm, InstructionSize::Narrow);
+        assert_eq!(machine.reg(Register::R0), 0x108);
+        assert_eq!(machine.memory[&0x100], 0x11);
+        assert_eq!(machine.memory[&0x104], 0x22);
+
+        let ldmdb: Operation = operation::Ldmdb::builder()
+            .set_w(Some(false))
+            .set_rn(Register::R0)
+            .set_registers(list(&[Register::R3, Register::R4]))
+            .complete()
+            .into();
+        machine.run(&ldmdb, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R0), 0x108);
+        assert_eq!(machine.reg(Register::R3), 0x1111_1111);
+        assert_eq!(machine.reg(Register::R4), 0x2222_2222);
+
+        machine.set(Register::R5, 0x100);
+        let ldm: Operation = operation::Ldm::builder()
+            .set_w(Some(true))
+            .set_rn(Register::R5)
+            .set_registers(list(&[Register::R6, Register::R7]))
+            .complete()
+            .into();
+        machine.run(&ldm, InstructionSize::Narrow);
+        assert_eq!(machine.reg(Register::R5), 0x108);
+        assert_eq!(machine.reg(Register::R7), 0x2222_2222);
+
+        machine.set(Register::SP, 0x200);
+        let stmdb: Operation = operation::Stmdb::builder()
+            .set_w(Some(true))
+            .set_rn(Register::SP)
+            .set_registers(list(&[Register::R1, Register::R2]))
+            .complete()
+            .into();
+        machine.run(&stmdb, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::SP), 0x1f8);
+        assert_eq!(machine.memory[&0x1f8], 0x11);
+        assert_eq!(machine.memory[&0x1fc], 0x22);
+
+        let pop: Operation = operation::Pop::builder()
+            .set_registers(list(&[Register::R8, Register::R9]))
+            .complete()
+            .into();
+        assert_eq!(machine.run(&pop, InstructionSize::Wide), Exit::Next);
+        assert_eq!(machine.reg(Register::SP), 0x200);
+        assert_eq!(machine.reg(Register::R8), 0x1111_1111);
+        assert_eq!(machine.reg(Register::R9), 0x2222_2222);
+    }
+
+    #[test]
+    fn test_it_block() {
+        let it: Operation = operation::It::builder()
+            .set_conds(ITCondition {
+                conditions: vec![Condition::Eq, Condition::Ne],
+            })
+            .complete()
+            .into();
+        assert_eq!(it.lift(&Context::default()), vec![Statement::Intrinsic(
+            Intrinsic::IfThen(ITCondition {
+                conditions: vec![Condition::Eq, Condition::Ne]
+            })
+        )]);
+
+        // Instructions in the block are lifted as if their condition passed,
+        // without the flags that are only written outside of IT blocks.
+        let add: Operation = operation::AddImmediate::builder()
+            .set_s(Some(SetFlags::InITBlock(false)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_imm(0)
+            .complete()
+            .into();
+        let context = Context {
+            in_it_block: true,
+            size: InstructionSize::Narrow,
+        };
+        let statements = add.lift(&context);
+        assert!(!statements
+            .iter()
+            .any(|statement| matches!(statement, Statement::WriteFlag { .. })));
+        assert!(matches!(
+            statements.last(),
+            Some(Statement::WriteRegister {
+                register: Register::R0,
+                ..
+            })
+        ));
+
+        // A branch in the block takes the condition of the block, so it is
+        // lifted as an unconditional branch.
+        let b: Operation = operation::B::builder()
+            .set_condition(Condition::None)
+            .set_imm(8)
+            .complete()
+            .into();
+        assert!(matches!(
+            b.lift(&context).last(),
+            Some(Statement::Branch {
+                kind: BranchKind::Jump,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_pc_relative() {
+        // The PC is aligned to a word before the offset is applied.
+        let mut machine = Machine {
+            address: 0x1002,
+            ..Default::default()
+        };
+        for (address, byte) in (0x100c..).zip([0x78, 0x56, 0x34, 0x12]) {
+            machine.memory.insert(address, byte);
+        }
+        let ldr: Operation = operation::LdrLiteral::builder()
+            .set_add(true)
+            .set_rt(Register::R0)
+            .set_imm(8)
+            .complete()
+            .into();
+        machine.run(&ldr, InstructionSize::Narrow);
+        assert_eq!(machine.reg(Register::R0), 0x1234_5678);
+
+        let ldrb: Operation = operation::LdrbLiteral::builder()
+            .set_add(Some(true))
+            .set_rt(Register::R1)
+            .set_imm(9)
+            .complete()
+            .into();
+        machine.run(&ldrb, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R1), 0x56);
+
+        let adr: Operation = operation::Adr::builder()
+            .set_rd(Register::R2)
+            .set_add(false)
+            .set_imm(4)
+            .complete()
+            .into();
+        machine.run(&adr, InstructionSize::Wide);
+        assert_eq!(machine.reg(Register::R2), 0x1000);
+
+        // Loading the PC from a literal pool is an interworking branch.
+        machine.memory.insert(0x100c, 0x79);
+        let ldr_pc: Operation = operation::LdrLiteral::builder()
+            .set_add(true)
+            .set_rt(Register::PC)
+            .set_imm(8)
+            .complete()
+            .into();
+        assert_eq!(
+            machine.run(&ldr_pc, InstructionSize::Wide),
+            Exit::Branch(0x1234_5679, BranchKind::Exchange)
+        );
+    }
+
+    #[test]
+    fn test_lift_decoded() {
+        // Lifts and evaluates a large number of pseudo random encodings to ensure
+        // that every decodable instruction can be lifted.
+        let mut state = 0x2545_f491_u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut lifted = 0;
+        for _ in 0..200_000 {
+            let word = next();
+            let (first, second) = ((word >> 16) as u16, word as u16);
+            let bytes = [
+                first as u8,
+                (first >> 8) as u8,
+                second as u8,
+                (second >> 8) as u8,
+            ];
+            let mut stream = PeekableBuffer::from(bytes.into_iter());
+            if let Ok(decoded) = Operation::decode(&mut stream, Policy::Permissive) {
+                let mut machine = Machine {
+                    registers: core::array::from_fn(|i| next() ^ i as u32),
+                    ..Default::default()
+                };
+                machine.run(&decoded.op, decoded.size);
+                lifted += 1;
+            }
+        }
+        assert!(lifted > 100_000);
+    }
+}
@@ -0,0 +1,575 @@
+//! Defines the intermediate representation that [`Operation`]s are lifted in
+//! to.
+//!
+//! The representation is a list of [`Statement`]s in static single
+//! assignment form, every [`Local`] is assigned exactly once. All side
+//! effects, register and flag accesses, memory accesses and branches, are
+//! explicit statements while all computations are side effect free
+//! [`Expression`]s.
+//!
+//! [`Operation`]: crate::operation::Operation
+
+use crate::{
+    arch::{ApsrMask, BarrierOption, ITCondition, Register, SpecialRegister},
+    operation::Operation,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Enumerates the bit widths of the values in the representation.
+pub enum Width {
+    /// A single bit, used for booleans.
+    Bit,
+    /// 8 bits.
+    Byte,
+    /// 16 bits.
+    Half,
+    /// 32 bits.
+    Word,
+    /// 64 bits, used for long multiplications.
+    Double,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A value that is assigned exactly once.
+pub struct Local {
+    /// Unique identifier of the value.
+    pub id: usize,
+    /// Width of the value.
+    pub width: Width,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// An operand of an [`Expression`] or [`Statement`].
+pub enum Value {
+    /// A previously assigned value.
+    Local(Local),
+    /// A constant of the given width.
+    ///
+    /// Only the lower bits that fit in the width are used.
+    Constant(u64, Width),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The status flags that instructions can read or write.
+pub enum Flag {
+    /// Negative flag.
+    N,
+    /// Zero flag.
+    Z,
+    /// Carry flag.
+    C,
+    /// Overflow flag.
+    V,
+    /// Saturation flag.
+    Q,
+    /// One of the four greater than or equal flags used by the parallel
+    /// add and subtract instructions.
+    Ge(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Operations that take a single operand.
+pub enum UnaryOp {
+    /// Bitwise not.
+    Not,
+    /// Two's complement negation.
+    Neg,
+    /// Number of leading zeros.
+    Clz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Operations that take two operands of the same width.
+///
+/// Shifts by an amount that is greater than or equal to the width result in
+/// zero, or the sign bit for [`BinaryOp::AShr`]. Divisions by zero result in
+/// zero. The comparison operations produce a [`Width::Bit`].
+pub enum BinaryOp {
+    /// Wrapping addition.
+    Add,
+    /// Wrapping subtraction.
+    Sub,
+    /// Wrapping multiplication.
+    Mul,
+    /// Unsigned division, rounds towards zero.
+    UDiv,
+    /// Signed division, rounds towards zero.
+    SDiv,
+    /// Bitwise and.
+    And,
+    /// Bitwise or.
+    Or,
+    /// Bitwise exclusive or.
+    Xor,
+    /// Logical left shift.
+    Shl,
+    /// Logical right shift.
+    LShr,
+    /// Arithmetic right shift.
+    AShr,
+    /// Equal.
+    Eq,
+    /// Not equal.
+    Ne,
+    /// Unsigned less than.
+    ULt,
+    /// Unsigned less than or equal.
+    ULe,
+    /// Signed less than.
+    SLt,
+    /// Signed less than or equal.
+    SLe,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A side effect free computation.
+pub enum Expression {
+    /// Copies the value.
+    Value(Value),
+    /// Applies the operation to the value.
+    Unary(UnaryOp, Value),
+    /// Applies the operation to the values.
+    Binary(BinaryOp, Value, Value),
+    /// If the first value is set the second value is selected, otherwise the
+    /// third value is selected.
+    Ite(Value, Value, Value),
+    /// Extracts bits `low` to `high`, inclusive.
+    Extract {
+        /// The value to extract bits from.
+        value: Value,
+        /// Index of the most significant bit to extract.
+        high: u8,
+        /// Index of the least significant bit to extract.
+        low: u8,
+    },
+    /// Zero extends the value to the given width.
+    ZeroExtend(Value, Width),
+    /// Sign extends the value to the given width.
+    SignExtend(Value, Width),
+    /// Concatenates the values, the first value is placed in the most
+    /// significant bits.
+    Concat(Value, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Describes why the program counter is written.
+pub enum BranchKind {
+    /// A plain jump.
+    Jump,
+    /// A call that writes the return address to the link register.
+    Call,
+    /// A return from a function, such as `bx lr` or `pop {.., pc}`.
+    Return,
+    /// A branch that selects the execution state from the least significant
+    /// bit of the target, such as `bx r0` or `ldr pc, [..]`.
+    ///
+    /// The target might also be an exception return value.
+    Exchange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerates the memory barriers.
+pub enum Barrier {
+    /// Data memory barrier.
+    DataMemory,
+    /// Data synchronization barrier.
+    DataSynchronization,
+    /// Instruction synchronization barrier.
+    InstructionSynchronization,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Operations that can not be expressed in terms of registers and memory.
+pub enum Intrinsic {
+    /// Starts an if then block, the following instructions are executed
+    /// conditionally.
+    IfThen(ITCondition),
+    /// Marks the address for exclusive access.
+    SetExclusive(Value),
+    /// Clears the local exclusive monitor.
+    ClearExclusive,
+    /// A memory barrier with its option field.
+    Barrier(Barrier, BarrierOption),
+    /// Hints that the data or instructions at the address will be accessed.
+    Preload {
+        /// The address that will be accessed.
+        address: Value,
+        /// True if the preload is for instructions.
+        instruction: bool,
+    },
+    /// Changes the interrupt masks.
+    ChangeProcessorState {
+        /// True if the masks are cleared, i.e. interrupts are enabled.
+        enable: bool,
+        /// Affects PRIMASK.
+        affect_pri: bool,
+        /// Affects FAULTMASK.
+        affect_fault: bool,
+    },
+    /// Hint to the debug system.
+    Debug(u8),
+    /// Send event.
+    SendEvent,
+    /// Wait for event.
+    WaitForEvent,
+    /// Wait for interrupt.
+    WaitForInterrupt,
+    /// Yield hint.
+    Yield,
+    /// Supervisor call with the given immediate.
+    SupervisorCall(u8),
+    /// Breakpoint with the given immediate.
+    Breakpoint(u32),
+    /// Permanently undefined instruction with the given immediate.
+    Undefined(u32),
+    /// A co processor instruction, these are not lifted.
+    Coprocessor(Box<Operation>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A single statement in the intermediate representation.
+pub enum Statement {
+    /// Assigns the result of the expression to the local.
+    Assign {
+        /// The local to assign to.
+        dst: Local,
+        /// The value to assign.
+        expression: Expression,
+    },
+    /// Reads a register.
+    ///
+    /// Reading [`Register::PC`] yields the address of the current instruction
+    /// plus four.
+    ReadRegister {
+        /// The local to assign to.
+        dst: Local,
+        /// The register to read.
+        register: Register,
+    },
+    /// Writes a register, this is never [`Register::PC`], see
+    /// [`Statement::Branch`].
+    WriteRegister {
+        /// The register to write.
+        register: Register,
+        /// The value to write.
+        value: Value,
+    },
+    /// Reads a status flag.
+    ReadFlag {
+        /// The local to assign to.
+        dst: Local,
+        /// The flag to read.
+        flag: Flag,
+    },
+    /// Writes a status flag.
+    WriteFlag {
+        /// The flag to write.
+        flag: Flag,
+        /// The value to write.
+        value: Value,
+    },
+    /// Loads `dst.width` bits from memory in little endian order.
+    Load {
+        /// The local to assign to.
+        dst: Local,
+        /// Address to load from.
+        address: Value,
+    },
+    /// Stores the value to memory in little endian order.
+    Store {
+        /// Address to store to.
+        address: Value,
+        /// The value to store.
+        value: Value,
+    },
+    /// Stores the value if the address is marked for exclusive access.
+    ///
+    /// The status is zero if the store was performed.
+    StoreExclusive {
+        /// The local to assign the status to.
+        status: Local,
+        /// Address to store to.
+        address: Value,
+        /// The value to store.
+        value: Value,
+    },
+    /// Reads a special register.
+    ReadSpecial {
+        /// The local to assign to.
+        dst: Local,
+        /// The special register to read.
+        sysm: SpecialRegister,
+    },
+    /// Writes a special register.
+    WriteSpecial {
+        /// The special register to write.
+        sysm: SpecialRegister,
+        /// The APSR flags that are written.
+        mask: ApsrMask,
+        /// The value to write.
+        value: Value,
+    },
+    /// Writes the program counter, this is always the last statement of an
+    /// instruction.
+    Branch {
+        /// The new program counter.
+        target: Value,
+        /// Why the program counter is written.
+        kind: BranchKind,
+    },
+    /// Writes the program counter if the condition is set, this is always the
+    /// last statement of an instruction.
+    ConditionalBranch {
+        /// The condition, a [`Width::Bit`].
+        condition: Value,
+        /// The new program counter.
+        target: Value,
+    },
+    /// An operation that can not be expressed in the representation.
+    Intrinsic(Intrinsic),
+}
+
+impl Width {
+    /// Returns the number of bits in the width.
+    pub const fn bits(&self) -> u32 {
+        match self {
+            Self::Bit => 1,
+            Self::Byte => 8,
+            Self::Half => 16,
+            Self::Word => 32,
+            Self::Double => 64,
+        }
+    }
+
+    /// Returns a mask with all of the bits in the width set.
+    pub const fn mask(&self) -> u64 {
+        match self {
+            Self::Double => u64::MAX,
+            _ => (1 << self.bits()) - 1,
+        }
+    }
+}
+
+impl Value {
+    /// Returns the width of the value.
+    pub fn width(&self) -> Width {
+        match self {
+            Self::Local(local) => local.width,
+            Self::Constant(_, width) => *width,
+        }
+    }
+
+    /// Returns the value, or None if it is a local that `lookup` does not
+    /// know the value of.
+    pub fn evaluate<F: Fn(Local) -> Option<u64>>(&self, lookup: F) -> Option<u64> {
+        match self {
+            Self::Constant(value, width) => Some(value & width.mask()),
+            Self::Local(local) => lookup(*local).map(|value| value & local.width.mask()),
+        }
+    }
+}
+
+impl Expression {
+    /// Computes the result of the expression, or None if any of the operands
+    /// is a local that `lookup` does not know the value of.
+    ///
+    /// The result is truncated to the width of the expression.
+    pub fn evaluate<F: Fn(Local) -> Option<u64>>(&self, lookup: F) -> Option<u64> {
+        let value = |value: &Value| value.evaluate(&lookup);
+        Some(match self {
+            Self::Value(v) => value(v)?,
+            Self::Unary(op, v) => {
+                let width = v.width();
+                let v = value(v)?;
+                match op {
+                    UnaryOp::Not => !v & width.mask(),
+                    UnaryOp::Neg => v.wrapping_neg() & width.mask(),
+                    UnaryOp::Clz => (v.leading_zeros() - (64 - width.bits())) as u64,
+                }
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let width = lhs.width();
+                let (lhs, rhs) = (value(lhs)?, value(rhs)?);
+                let bits = width.bits() as u64;
+                let signed = |value: u64| signed(value, width);
+                let result = match op {
+                    BinaryOp::Add => lhs.wrapping_add(rhs),
+                    BinaryOp::Sub => lhs.wrapping_sub(rhs),
+                    BinaryOp::Mul => lhs.wrapping_mul(rhs),
+                    BinaryOp::UDiv => lhs.checked_div(rhs).unwrap_or(0),
+                    BinaryOp::SDiv => signed(lhs).checked_div(signed(rhs)).unwrap_or(0) as u64,
+                    BinaryOp::And => lhs & rhs,
+                    BinaryOp::Or => lhs | rhs,
+                    BinaryOp::Xor => lhs ^ rhs,
+                    BinaryOp::Shl if rhs >= bits => 0,
+                    BinaryOp::Shl => lhs << rhs,
+                    BinaryOp::LShr if rhs >= bits => 0,
+                    BinaryOp::LShr => lhs >> rhs,
+                    BinaryOp::AShr => (signed(lhs) >> rhs.min(bits - 1)) as u64,
+                    BinaryOp::Eq => return Some((lhs == rhs) as u64),
+                    BinaryOp::Ne => return Some((lhs != rhs) as u64),
+                    BinaryOp::ULt => return Some((lhs < rhs) as u64),
+                    BinaryOp::ULe => return Some((lhs <= rhs) as u64),
+                    BinaryOp::SLt => return Some((signed(lhs) < signed(rhs)) as u64),
+                    BinaryOp::SLe => return Some((signed(lhs) <= signed(rhs)) as u64),
+                };
+                result & width.mask()
+            }
+            Self::Ite(condition, then, otherwise) => match value(condition)? {
+                0 => value(otherwise)?,
+                _ => value(then)?,
+            },
+            Self::Extract {
+                value: v,
+                high,
+                low,
+            } => {
+                let bits = (high - low + 1) as u32;
+                (value(v)? >> low) & (u64::MAX >> (64 - bits))
+            }
+            Self::ZeroExtend(v, _) => value(v)?,
+            Self::SignExtend(v, width) => signed(value(v)?, v.width()) as u64 & width.mask(),
+            Self::Concat(high, low) => (value(high)? << low.width().bits()) | value(low)?,
+        })
+    }
+}
+
+/// Interprets the lower `width` bits of the value as a two's complement
+/// number.
+fn signed(value: u64, width: Width) -> i64 {
+    let shift = 64 - width.bits();
+    ((value << shift) as i64) >> shift
+}
+
+impl From<Local> for Value {
+    fn from(value: Local) -> Self {
+        Self::Local(value)
+    }
+}
+
+impl From<crate::arch::Flag> for Flag {
+    fn from(value: crate::arch::Flag) -> Self {
+        match value {
+            crate::arch::Flag::N => Self::N,
+            crate::arch::Flag::Z => Self::Z,
+            crate::arch::Flag::C => Self::C,
+            crate::arch::Flag::V => Self::V,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{BinaryOp, Expression, Local, UnaryOp, Value, Width};
+
+    fn word(value: u32) -> Value {
+        Value::Constant(value as u64, Width::Word)
+    }
+
+    fn evaluate(expression: Expression) -> Option<u64> {
+        expression.evaluate(|_| None)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let binary = |op, lhs, rhs| evaluate(Expression::Binary(op, word(lhs), word(rhs)));
+        assert_eq!(binary(BinaryOp::Add, 0xffff_ffff, 2), Some(1));
+        assert_eq!(binary(BinaryOp::Sub, 0, 1), Some(0xffff_ffff));
+        assert_eq!(binary(BinaryOp::Mul, 0x1_0000, 0x1_0000), Some(0));
+        assert_eq!(binary(BinaryOp::UDiv, 7, 0), Some(0));
+        assert_eq!(
+            binary(BinaryOp::SDiv, (-7i32) as u32, 2),
+            Some((-3i32) as u32 as u64)
+        );
+        assert_eq!(
+            binary(BinaryOp::SDiv, 0x8000_0000, 0xffff_ffff),
+            Some(0x8000_0000)
+        );
+        assert_eq!(binary(BinaryOp::SLt, 0xffff_ffff, 0), Some(1));
+        assert_eq!(binary(BinaryOp::ULt, 0xffff_ffff, 0), Some(0));
+        assert_eq!(
+            evaluate(Expression::Unary(UnaryOp::Neg, word(1))),
+            Some(0xffff_ffff)
+        );
+        assert_eq!(evaluate(Expression::Unary(UnaryOp::Clz, word(1))), Some(31));
+        assert_eq!(evaluate(Expression::Unary(UnaryOp::Clz, word(0))), Some(32));
+    }
+
+    #[test]
+    fn test_shifts() {
+        let binary = |op, lhs, rhs| evaluate(Expression::Binary(op, word(lhs), word(rhs)));
+        assert_eq!(binary(BinaryOp::Shl, 0x8000_0001, 1), Some(2));
+        assert_eq!(binary(BinaryOp::Shl, 1, 32), Some(0));
+        assert_eq!(binary(BinaryOp::LShr, 0x8000_0000, 31), Some(1));
+        assert_eq!(binary(BinaryOp::LShr, 0x8000_0000, 32), Some(0));
+        assert_eq!(binary(BinaryOp::AShr, 0x8000_0000, 4), Some(0xf800_0000));
+        assert_eq!(binary(BinaryOp::AShr, 0x8000_0000, 255), Some(0xffff_ffff));
+    }
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(
+            evaluate(Expression::Extract {
+                value: word(0x1234_5678),
+                high: 15,
+                low: 8
+            }),
+            Some(0x56)
+        );
+        assert_eq!(
+            evaluate(Expression::Extract {
+                value: Value::Constant(u64::MAX, Width::Double),
+                high: 63,
+                low: 0
+            }),
+            Some(u64::MAX)
+        );
+        assert_eq!(
+            evaluate(Expression::SignExtend(
+                Value::Constant(0x80, Width::Byte),
+                Width::Word
+            )),
+            Some(0xffff_ff80)
+        );
+        assert_eq!(
+            evaluate(Expression::ZeroExtend(
+                Value::Constant(0x1ff, Width::Byte),
+                Width::Word
+            )),
+            Some(0xff)
+        );
+        assert_eq!(
+            evaluate(Expression::Concat(word(1), word(2))),
+            Some(0x1_0000_0002)
+        );
+        assert_eq!(
+            evaluate(Expression::Ite(
+                Value::Constant(1, Width::Bit),
+                word(1),
+                word(2)
+            )),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_locals() {
+        let known = Local {
+            id: 0,
+            width: Width::Byte,
+        };
+        let unknown = Local {
+            id: 1,
+            width: Width::Byte,
+        };
+        let locals = HashMap::from([(0, 0x1ff)]);
+        let lookup = |local: Local| locals.get(&local.id).copied();
+        let add = |rhs: Local| {
+            Expression::Binary(BinaryOp::Add, known.into(), rhs.into()).evaluate(lookup)
+        };
+        assert_eq!(add(known), Some(0xfe));
+        assert_eq!(add(unknown), None);
+    }
+}
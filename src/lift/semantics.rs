@@ -0,0 +1,2030 @@
+//! Defines the semantics of each [`Operation`].
+//!
+//! The semantics follow the pseudo code in chapter A7 of the Armv7-M
+//! architecture reference manual.
+
+use super::{
+    ir::{Barrier, BinaryOp, BranchKind, Flag, Intrinsic, Statement, UnaryOp, Value, Width},
+    Context,
+    Lifter,
+};
+use crate::{
+    arch::{
+        set_flags::LocalUnwrap,
+        BarrierOption,
+        Condition,
+        ImmShift,
+        Register,
+        RegisterList,
+        SetFlags,
+        Shift,
+    },
+    operation::Operation,
+    InstructionSize,
+};
+
+/// The second operand of a data processing instruction.
+enum Operand<'a> {
+    /// An expanded immediate and the carry produced by the expansion.
+    Immediate(u32, Option<bool>),
+    /// A register with an optional constant shift.
+    Register(Register, &'a Option<ImmShift>),
+}
+
+#[derive(Clone, Copy)]
+enum Arithmetic {
+    Add,
+    Adc,
+    Sub,
+    Sbc,
+    Rsb,
+}
+
+#[derive(Clone, Copy)]
+enum Logical {
+    And,
+    Bic,
+    Orr,
+    Orn,
+    Eor,
+    Mov,
+    Mvn,
+}
+
+#[derive(Clone, Copy)]
+enum Lanes {
+    Add8,
+    Sub8,
+    Add16,
+    Sub16,
+    Asx,
+    Sax,
+}
+
+#[derive(Clone, Copy)]
+enum Parallel {
+    Signed,
+    Unsigned,
+    SignedSaturating,
+    UnsignedSaturating,
+    SignedHalving,
+    UnsignedHalving,
+}
+
+/// Describes how the address of a load or store is computed.
+#[derive(Clone, Copy)]
+struct Addressing {
+    rn: Register,
+    add: bool,
+    index: bool,
+    wback: bool,
+}
+
+impl Addressing {
+    const fn new(rn: Register, add: bool, index: bool, wback: bool) -> Self {
+        Self {
+            rn,
+            add,
+            index,
+            wback,
+        }
+    }
+
+    const fn offset(rn: Register) -> Self {
+        Self::new(rn, true, true, false)
+    }
+}
+
+/// Sorts the registers in the order that they are transferred to memory.
+fn sorted(registers: &RegisterList) -> Vec<Register> {
+    let mut registers = registers.registers.clone();
+    registers.sort_by_key(|register| u8::from(*register));
+    registers
+}
+
+impl Lifter {
+    pub(super) fn operation(&mut self, operation: &Operation, context: &Context) {
+        let flags = |s: &Option<SetFlags>| (*s).local_unwrap(context.in_it_block);
+        let literal = |s: &Option<bool>| s.unwrap_or(false);
+        match operation {
+            // ============================ Arithmetic ============================
+            Operation::AdcImmediate(el) => self.arithmetic(
+                Arithmetic::Adc,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                literal(&el.s),
+            ),
+            Operation::AdcRegister(el) => self.arithmetic(
+                Arithmetic::Adc,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::AddImmediate(el) => self.arithmetic(
+                Arithmetic::Add,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                flags(&el.s),
+            ),
+            Operation::AddRegister(el) => self.arithmetic(
+                Arithmetic::Add,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::AddSPImmediate(el) => self.arithmetic(
+                Arithmetic::Add,
+                Some(el.rd.unwrap_or(Register::SP)),
+                Register::SP,
+                Operand::Immediate(el.imm, None),
+                literal(&el.s),
+            ),
+            Operation::AddSPRegister(el) => self.arithmetic(
+                Arithmetic::Add,
+                Some(el.rd.unwrap_or(Register::SP)),
+                Register::SP,
+                Operand::Register(el.rm, &el.shift),
+                literal(&el.s),
+            ),
+            Operation::CmnImmediate(el) => self.arithmetic(
+                Arithmetic::Add,
+                None,
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                true,
+            ),
+            Operation::CmnRegister(el) => self.arithmetic(
+                Arithmetic::Add,
+                None,
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                true,
+            ),
+            Operation::CmpImmediate(el) => self.arithmetic(
+                Arithmetic::Sub,
+                None,
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                true,
+            ),
+            Operation::CmpRegister(el) => self.arithmetic(
+                Arithmetic::Sub,
+                None,
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                true,
+            ),
+            Operation::RsbImmediate(el) => self.arithmetic(
+                Arithmetic::Rsb,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                flags(&el.s),
+            ),
+            Operation::RsbRegister(el) => self.arithmetic(
+                Arithmetic::Rsb,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                literal(&el.s),
+            ),
+            Operation::SbcImmediate(el) => self.arithmetic(
+                Arithmetic::Sbc,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                literal(&el.s),
+            ),
+            Operation::SbcRegister(el) => self.arithmetic(
+                Arithmetic::Sbc,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::SubImmediate(el) => self.arithmetic(
+                Arithmetic::Sub,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Immediate(el.imm, None),
+                flags(&el.s),
+            ),
+            Operation::SubRegister(el) => self.arithmetic(
+                Arithmetic::Sub,
+                Some(el.rd.unwrap_or(el.rn)),
+                el.rn,
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::SubSpMinusImmediate(el) => self.arithmetic(
+                Arithmetic::Sub,
+                Some(el.rd.unwrap_or(Register::SP)),
+                Register::SP,
+                Operand::Immediate(el.imm, None),
+                literal(&el.s),
+            ),
+            Operation::SubSpMinusRegister(el) => self.arithmetic(
+                Arithmetic::Sub,
+                Some(el.rd.unwrap_or(Register::SP)),
+                Register::SP,
+                Operand::Register(el.rm, &el.shift),
+                literal(&el.s),
+            ),
+            Operation::Adr(el) => {
+                let base = self.literal_base();
+                let imm = Self::word(el.imm);
+                let result = match el.add {
+                    true => self.add(base, imm),
+                    false => self.sub(base, imm),
+                };
+                self.write(el.rd, result);
+            }
+
+            // ============================= Logical ==============================
+            Operation::AndImmediate(el) => self.logical(
+                Logical::And,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::AndRegister(el) => self.logical(
+                Logical::And,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::BicImmediate(el) => self.logical(
+                Logical::Bic,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::BicRegister(el) => self.logical(
+                Logical::Bic,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::EorImmediate(el) => self.logical(
+                Logical::Eor,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::EorRegister(el) => self.logical(
+                Logical::Eor,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::OrnImmediate(el) => self.logical(
+                Logical::Orn,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::OrnRegister(el) => self.logical(
+                Logical::Orn,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                literal(&el.s),
+            ),
+            Operation::OrrImmediate(el) => self.logical(
+                Logical::Orr,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::OrrRegister(el) => self.logical(
+                Logical::Orr,
+                Some(el.rd.unwrap_or(el.rn)),
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::TeqImmediate(el) => self.logical(
+                Logical::Eor,
+                None,
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                true,
+            ),
+            Operation::TeqRegister(el) => self.logical(
+                Logical::Eor,
+                None,
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                true,
+            ),
+            Operation::TstImmediate(el) => self.logical(
+                Logical::And,
+                None,
+                Some(el.rn),
+                Operand::Immediate(el.imm, el.carry),
+                true,
+            ),
+            Operation::TstRegister(el) => self.logical(
+                Logical::And,
+                None,
+                Some(el.rn),
+                Operand::Register(el.rm, &el.shift),
+                true,
+            ),
+            Operation::MovImmediate(el) => self.logical(
+                Logical::Mov,
+                Some(el.rd),
+                None,
+                Operand::Immediate(el.imm, el.carry),
+                flags(&el.s),
+            ),
+            Operation::MovRegister(el) if el.rd == Register::PC => {
+                let value = self.read(el.rm);
+                let target = self.and(value, Self::word(!1));
+                let kind = match el.rm {
+                    Register::LR => BranchKind::Return,
+                    _ => BranchKind::Jump,
+                };
+                self.branch(target, kind);
+            }
+            Operation::MovRegister(el) => self.logical(
+                Logical::Mov,
+                Some(el.rd),
+                None,
+                Operand::Register(el.rm, &None),
+                literal(&el.s),
+            ),
+            Operation::MvnImmediate(el) => self.logical(
+                Logical::Mvn,
+                Some(el.rd),
+                None,
+                Operand::Immediate(el.imm, el.carry),
+                literal(&el.s),
+            ),
+            Operation::MvnRegister(el) => self.logical(
+                Logical::Mvn,
+                Some(el.rd),
+                None,
+                Operand::Register(el.rm, &el.shift),
+                flags(&el.s),
+            ),
+            Operation::Movt(el) => {
+                let rd = self.read(el.rd);
+                let low = self.and(rd, Self::word(0xffff));
+                let result = self.or(low, Self::word((el.imm as u32) << 16));
+                self.write(el.rd, result);
+            }
+
+            // ============================== Shifts ==============================
+            Operation::AsrImmediate(el) => {
+                self.shift_immediate(el.rd, el.rm, Shift::Asr, el.imm, flags(&el.s))
+            }
+            Operation::LslImmediate(el) => {
+                self.shift_immediate(el.rd, el.rm, Shift::Lsl, el.imm as u32, flags(&el.s))
+            }
+            Operation::LsrImmediate(el) => {
+                self.shift_immediate(el.rd, el.rm, Shift::Lsr, el.imm as u32, flags(&el.s))
+            }
+            Operation::RorImmediate(el) => {
+                self.shift_immediate(el.rd, el.rm, Shift::Ror, el.imm, literal(&el.s))
+            }
+            Operation::Rrx(el) => self.shift_immediate(el.rd, el.rm, Shift::Rrx, 1, literal(&el.s)),
+            Operation::AsrRegister(el) => {
+                self.shift_register(el.rd, el.rn, el.rm, Shift::Asr, flags(&el.s))
+            }
+            Operation::LslRegister(el) => {
+                self.shift_register(el.rd, el.rn, el.rm, Shift::Lsl, flags(&el.s))
+            }
+            Operation::LsrRegister(el) => {
+                self.shift_register(el.rd, el.rn, el.rm, Shift::Lsr, flags(&el.s))
+            }
+            Operation::RorRegister(el) => {
+                self.shift_register(el.rd, el.rn, el.rm, Shift::Ror, flags(&el.s))
+            }
+
+            // ============================ Bit fields ============================
+            Operation::Bfc(el) => {
+                let mask = Self::field_mask(el.lsb, el.msb);
+                let rd = self.read(el.rd);
+                let result = self.and(rd, Self::word(!mask));
+                self.write(el.rd, result);
+            }
+            Operation::Bfi(el) => {
+                let mask = Self::field_mask(el.lsb, el.msb);
+                let rd = self.read(el.rd);
+                let rn = self.read(el.rn);
+                let kept = self.and(rd, Self::word(!mask));
+                let inserted = self.binary(BinaryOp::Shl, rn, Self::word(el.lsb));
+                let inserted = self.and(inserted, Self::word(mask));
+                let result = self.or(kept, inserted);
+                self.write(el.rd, result);
+            }
+            Operation::Sbfx(el) => {
+                let rn = self.read(el.rn);
+                let left = 32 - (el.lsb + el.width).min(32);
+                let result = self.binary(BinaryOp::Shl, rn, Self::word(left));
+                let result = self.binary(BinaryOp::AShr, result, Self::word(32 - el.width));
+                self.write(el.rd, result);
+            }
+            Operation::Ubfx(el) => {
+                let rn = self.read(el.rn);
+                let result = self.binary(BinaryOp::LShr, rn, Self::word(el.lsb));
+                let mask = Self::field_mask(0, el.width - 1);
+                let result = self.and(result, Self::word(mask));
+                self.write(el.rd, result);
+            }
+            Operation::Clz(el) => {
+                let rm = self.read(el.rm);
+                let result = self.unary(UnaryOp::Clz, rm);
+                self.write(el.rd, result);
+            }
+            Operation::Rbit(el) => {
+                let mut result = self.read(el.rm);
+                for (shift, mask) in [
+                    (1, 0x5555_5555),
+                    (2, 0x3333_3333),
+                    (4, 0x0f0f_0f0f),
+                    (8, 0x00ff_00ff),
+                ] {
+                    let high = self.binary(BinaryOp::LShr, result, Self::word(shift));
+                    let high = self.and(high, Self::word(mask));
+                    let low = self.and(result, Self::word(mask));
+                    let low = self.binary(BinaryOp::Shl, low, Self::word(shift));
+                    result = self.or(high, low);
+                }
+                let result = self.ror(result, 16);
+                self.write(el.rd, result);
+            }
+            Operation::Rev(el) => {
+                let bytes = self.bytes(el.rm);
+                let result = self.concat_lanes(&[bytes[0], bytes[1], bytes[2], bytes[3]]);
+                self.write(el.rd, result);
+            }
+            Operation::Rev16(el) => {
+                let bytes = self.bytes(el.rm);
+                let result = self.concat_lanes(&[bytes[2], bytes[3], bytes[0], bytes[1]]);
+                self.write(el.rd, result);
+            }
+            Operation::Revsh(el) => {
+                let bytes = self.bytes(el.rm);
+                let result = self.concat_lanes(&[bytes[0], bytes[1]]);
+                let result = self.sign_extend(result, Width::Word);
+                self.write(el.rd, result);
+            }
+            Operation::Pkh(el) => {
+                let rn = self.read(el.rn);
+                let rm = self.read(el.rm);
+                let shifted = self.shift(rm, &el.shift);
+                let (high, low) = match el.tb {
+                    true => (rn, shifted),
+                    false => (shifted, rn),
+                };
+                let high = self.extract(high, 31, 16);
+                let low = self.extract(low, 15, 0);
+                let result = self.concat(high, low);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+
+            // ============================= Extends ==============================
+            Operation::Sxtb(el) => self.extend(el.rd, None, el.rm, el.rotation, Width::Byte, true),
+            Operation::Sxth(el) => self.extend(el.rd, None, el.rm, el.rotation, Width::Half, true),
+            Operation::Uxtb(el) => self.extend(el.rd, None, el.rm, el.rotation, Width::Byte, false),
+            Operation::Uxth(el) => self.extend(el.rd, None, el.rm, el.rotation, Width::Half, false),
+            Operation::Sxtab(el) => self.extend(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                Width::Byte,
+                true,
+            ),
+            Operation::Sxtah(el) => self.extend(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                Width::Half,
+                true,
+            ),
+            Operation::Uxtab(el) => self.extend(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                Width::Byte,
+                false,
+            ),
+            Operation::Uxtah(el) => self.extend(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                Width::Half,
+                false,
+            ),
+            Operation::Sxtb16(el) => {
+                self.extend16(el.rd.unwrap_or(el.rm), None, el.rm, el.rotation, true)
+            }
+            Operation::Uxtb16(el) => {
+                self.extend16(el.rd.unwrap_or(el.rm), None, el.rm, el.rotation, false)
+            }
+            Operation::Sxtab16(el) => self.extend16(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                true,
+            ),
+            Operation::Uxtab16(el) => self.extend16(
+                el.rd.unwrap_or(el.rn),
+                Some(el.rn),
+                el.rm,
+                el.rotation,
+                false,
+            ),
+
+            // ============================= Multiply =============================
+            Operation::Mul(el) => {
+                let rn = self.read(el.rn);
+                let rm = self.read(el.rm);
+                let result = self.binary(BinaryOp::Mul, rn, rm);
+                self.write(el.rd.unwrap_or(el.rm), result);
+                if flags(&el.s) {
+                    self.set_nz(result);
+                }
+            }
+            Operation::Mla(el) => {
+                let (rn, rm, ra) = (self.read(el.rn), self.read(el.rm), self.read(el.ra));
+                let product = self.binary(BinaryOp::Mul, rn, rm);
+                let result = self.add(ra, product);
+                self.write(el.rd, result);
+            }
+            Operation::Mls(el) => {
+                let (rn, rm, ra) = (self.read(el.rn), self.read(el.rm), self.read(el.ra));
+                let product = self.binary(BinaryOp::Mul, rn, rm);
+                let result = self.sub(ra, product);
+                self.write(el.rd, result);
+            }
+            Operation::Sdiv(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let result = self.binary(BinaryOp::SDiv, rn, rm);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::Udiv(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let result = self.binary(BinaryOp::UDiv, rn, rm);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::Smull(el) => {
+                let product = self.long_product(el.rn, el.rm, true);
+                self.write_long(el.rdlo, el.rdhi, product);
+            }
+            Operation::Umull(el) => {
+                let product = self.long_product(el.rn, el.rm, false);
+                self.write_long(el.rdlo, el.rdhi, product);
+            }
+            Operation::Smlal(el) => {
+                let product = self.long_product(el.rn, el.rm, true);
+                let accumulator = self.read_long(el.rdlo, el.rdhi);
+                let result = self.add(product, accumulator);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Umlal(el) => {
+                let product = self.long_product(el.rn, el.rm, false);
+                let accumulator = self.read_long(el.rdlo, el.rdhi);
+                let result = self.add(product, accumulator);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Umaal(el) => {
+                let product = self.long_product(el.rn, el.rm, false);
+                let (lo, hi) = (self.read(el.rdlo), self.read(el.rdhi));
+                let lo = self.zero_extend(lo, Width::Double);
+                let hi = self.zero_extend(hi, Width::Double);
+                let result = self.add(product, lo);
+                let result = self.add(result, hi);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Smla(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let product = self.halfword_product(rn, el.n_high, rm, el.m_high);
+                let ra = self.read(el.ra);
+                let result = self.sum_q(&[product, ra]);
+                self.write(el.rd, result);
+            }
+            Operation::Smul(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let result = self.halfword_product(rn, el.n_high, rm, el.m_high);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::SmlalSelective(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let product = self.halfword_product(rn, el.n_high, rm, el.m_high);
+                let product = self.sign_extend(product, Width::Double);
+                let accumulator = self.read_long(el.rdlo, el.rdhi);
+                let result = self.add(product, accumulator);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Smlaw(el) => {
+                let product = self.word_halfword_product(el.rn, el.rm, el.m_high);
+                let ra = self.read(el.ra);
+                let result = self.sum_q(&[product, ra]);
+                self.write(el.rd, result);
+            }
+            Operation::Smulw(el) => {
+                let result = self.word_halfword_product(el.rn, el.rm, el.m_high);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::Smlad(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.x));
+                let ra = self.read(el.ra);
+                let result = self.sum_q(&[low, high, ra]);
+                self.write(el.rd, result);
+            }
+            Operation::Smuad(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.m_swap));
+                let result = self.sum_q(&[low, high]);
+                self.write(el.rd, result);
+            }
+            Operation::Smlsd(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.m_swap));
+                let difference = self.sub(low, high);
+                let ra = self.read(el.ra);
+                let result = self.sum_q(&[difference, ra]);
+                self.write(el.rd, result);
+            }
+            Operation::Smusd(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.m_swap));
+                let result = self.sub(low, high);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::Smlald(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.x));
+                let low = self.sign_extend(low, Width::Double);
+                let high = self.sign_extend(high, Width::Double);
+                let accumulator = self.read_long(el.rdlo, el.rdhi);
+                let result = self.add(low, high);
+                let result = self.add(result, accumulator);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Smlsld(el) => {
+                let (low, high) = self.dual_product(el.rn, el.rm, literal(&el.m_swap));
+                let low = self.sign_extend(low, Width::Double);
+                let high = self.sign_extend(high, Width::Double);
+                let accumulator = self.read_long(el.rdlo, el.rdhi);
+                let result = self.sub(low, high);
+                let result = self.add(result, accumulator);
+                self.write_long(el.rdlo, el.rdhi, result);
+            }
+            Operation::Smmla(el) => {
+                self.most_significant_multiply(el.rd, el.rn, el.rm, Some((el.ra, true)), el.round)
+            }
+            Operation::Smmls(el) => {
+                self.most_significant_multiply(el.rd, el.rn, el.rm, Some((el.ra, false)), el.round)
+            }
+            Operation::Smmul(el) => {
+                self.most_significant_multiply(el.rd, el.rn, el.rm, None, el.round)
+            }
+            Operation::Usad8(el) => {
+                let result = self.sum_absolute_differences(el.rn, el.rm);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            // Not an instruction, this is never produced by the decoder. Treat it as a
+            // Usad8 as that is the closest match.
+            Operation::Uqsad8(el) => {
+                let result = self.sum_absolute_differences(el.rn, el.rm);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+            Operation::Usada8(el) => {
+                let result = self.sum_absolute_differences(el.rn, el.rm);
+                let ra = self.read(el.ra);
+                let result = self.add(result, ra);
+                self.write(el.rd, result);
+            }
+
+            // ============================ Saturating ============================
+            Operation::Qadd(el) => {
+                self.saturating(el.rd.unwrap_or(el.rm), el.rm, el.rn, true, false)
+            }
+            Operation::Qsub(el) => {
+                self.saturating(el.rd.unwrap_or(el.rm), el.rm, el.rn, false, false)
+            }
+            Operation::Qdadd(el) => {
+                self.saturating(el.rd.unwrap_or(el.rm), el.rm, el.rn, true, true)
+            }
+            Operation::Qdsub(el) => {
+                self.saturating(el.rd.unwrap_or(el.rm), el.rm, el.rn, false, true)
+            }
+            Operation::Ssat(el) => {
+                let rn = self.read(el.rn);
+                let operand = self.shift(rn, &el.shift);
+                let (result, saturated) = self.signed_saturate(operand, el.imm);
+                self.write(el.rd, result);
+                self.saturate_q(saturated);
+            }
+            Operation::Usat(el) => {
+                let rn = self.read(el.rn);
+                let operand = self.shift(rn, &el.shift);
+                let (result, saturated) = self.unsigned_saturate(operand, el.imm);
+                self.write(el.rd, result);
+                self.saturate_q(saturated);
+            }
+            Operation::Ssat16(el) => self.saturate16(el.rd, el.rn, el.imm, true),
+            Operation::Usat16(el) => self.saturate16(el.rd, el.rn, el.imm, false),
+
+            // ====================== Parallel add and subtract ======================
+            Operation::Sadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::Signed,
+            ),
+            Operation::Sadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::Signed,
+            ),
+            Operation::Sasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::Signed,
+            ),
+            Operation::Ssax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::Signed,
+            ),
+            Operation::Ssub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::Signed,
+            ),
+            Operation::Ssub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::Signed,
+            ),
+            Operation::Uadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::Unsigned,
+            ),
+            Operation::Uadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::Unsigned,
+            ),
+            Operation::Uasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::Unsigned,
+            ),
+            Operation::Usax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::Unsigned,
+            ),
+            Operation::Usub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::Unsigned,
+            ),
+            Operation::Usub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::Unsigned,
+            ),
+            Operation::Qadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Qadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Qasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Qsax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Qsub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Qsub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::SignedSaturating,
+            ),
+            Operation::Uqadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Uqadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Uqasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Uqsax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Uqsub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Uqsub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::UnsignedSaturating,
+            ),
+            Operation::Shadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::SignedHalving,
+            ),
+            Operation::Shadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::SignedHalving,
+            ),
+            Operation::Shasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::SignedHalving,
+            ),
+            Operation::Shsax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::SignedHalving,
+            ),
+            Operation::Shsub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::SignedHalving,
+            ),
+            Operation::Shsub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::SignedHalving,
+            ),
+            Operation::Uhadd16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add16,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Uhadd8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Add8,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Uhasx(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Asx,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Uhsax(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sax,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Uhsub16(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub16,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Uhsub8(el) => self.parallel(
+                el.rd.unwrap_or(el.rn),
+                el.rn,
+                el.rm,
+                Lanes::Sub8,
+                Parallel::UnsignedHalving,
+            ),
+            Operation::Sel(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let mut lanes = Vec::with_capacity(4);
+                for lane in 0..4u8 {
+                    let high = lane * 8 + 7;
+                    let n = self.extract(rn, high, lane * 8);
+                    let m = self.extract(rm, high, lane * 8);
+                    let ge = self.read_flag(Flag::Ge(lane));
+                    lanes.push(self.ite(ge, n, m));
+                }
+                lanes.reverse();
+                let result = self.concat_lanes(&lanes);
+                self.write(el.rd.unwrap_or(el.rn), result);
+            }
+
+            // ========================== Loads and stores ==========================
+            Operation::LdrImmediate(el) => self.load_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index, literal(&el.w)),
+                Self::word(el.imm),
+                Width::Word,
+                false,
+            ),
+            Operation::LdrLiteral(el) => self.load_single(
+                el.rt,
+                Addressing::new(Register::PC, el.add, true, false),
+                Self::word(el.imm),
+                Width::Word,
+                false,
+            ),
+            Operation::LdrRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.load_single(el.rt, Addressing::offset(el.rn), offset, Width::Word, false)
+            }
+            Operation::Ldrt(el) => self.load_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Word,
+                false,
+            ),
+            Operation::LdrbImmediate(el) => self.load_single(
+                el.rt,
+                Addressing::new(el.rn, el.add.unwrap_or(true), el.index, literal(&el.w)),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Byte,
+                false,
+            ),
+            Operation::LdrbLiteral(el) => self.load_single(
+                el.rt,
+                Addressing::new(Register::PC, el.add.unwrap_or(true), true, false),
+                Self::word(el.imm),
+                Width::Byte,
+                false,
+            ),
+            Operation::LdrbRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                let addressing = Addressing::new(el.rn, el.add.unwrap_or(true), true, false);
+                self.load_single(el.rt, addressing, offset, Width::Byte, false)
+            }
+            Operation::Ldrbt(el) => self.load_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Byte,
+                false,
+            ),
+            Operation::LdrhImmediate(el) => self.load_single(
+                el.rt,
+                Addressing::new(
+                    el.rn,
+                    el.add.unwrap_or(true),
+                    el.index.unwrap_or(true),
+                    literal(&el.w),
+                ),
+                Self::word(el.imm),
+                Width::Half,
+                false,
+            ),
+            Operation::LdrhLiteral(el) => self.load_single(
+                el.rt,
+                Addressing::new(Register::PC, el.add.unwrap_or(true), true, false),
+                Self::word(el.imm),
+                Width::Half,
+                false,
+            ),
+            Operation::LdrhRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.load_single(el.rt, Addressing::offset(el.rn), offset, Width::Half, false)
+            }
+            Operation::Ldrht(el) => self.load_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Half,
+                false,
+            ),
+            Operation::LdrsbImmediate(el) => self.load_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index, el.wback),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Byte,
+                true,
+            ),
+            Operation::LdrsbLiteral(el) => self.load_single(
+                el.rt,
+                Addressing::new(Register::PC, el.add, true, false),
+                Self::word(el.imm),
+                Width::Byte,
+                true,
+            ),
+            Operation::LdrsbRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.load_single(el.rt, Addressing::offset(el.rn), offset, Width::Byte, true)
+            }
+            Operation::Ldrsbt(el) => self.load_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm),
+                Width::Byte,
+                true,
+            ),
+            Operation::LdrshImmediate(el) => self.load_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index, el.wback),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Half,
+                true,
+            ),
+            Operation::LdrshLiteral(el) => self.load_single(
+                el.rt,
+                Addressing::new(Register::PC, el.add, true, false),
+                Self::word(el.imm),
+                Width::Half,
+                true,
+            ),
+            Operation::LdrshRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.load_single(el.rt, Addressing::offset(el.rn), offset, Width::Half, true)
+            }
+            Operation::Ldrsht(el) => self.load_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Half,
+                true,
+            ),
+            Operation::LdrdImmediate(el) => self.load_dual(
+                el.rt,
+                el.rt2,
+                Addressing::new(
+                    el.rn,
+                    el.add.unwrap_or(true),
+                    el.index.unwrap_or(true),
+                    literal(&el.w),
+                ),
+                el.imm,
+            ),
+            Operation::LdrdLiteral(el) => self.load_dual(
+                el.rt,
+                el.rt2,
+                Addressing::new(Register::PC, el.add.unwrap_or(true), true, false),
+                el.imm,
+            ),
+            Operation::StrImmediate(el) => self.store_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index.unwrap_or(true), literal(&el.w)),
+                Self::word(el.imm),
+                Width::Word,
+            ),
+            Operation::StrRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.store_single(el.rt, Addressing::offset(el.rn), offset, Width::Word)
+            }
+            Operation::Strt(el) => self.store_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Word,
+            ),
+            Operation::StrbImmediate(el) => self.store_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index.unwrap_or(true), literal(&el.w)),
+                Self::word(el.imm),
+                Width::Byte,
+            ),
+            Operation::StrbRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.store_single(el.rt, Addressing::offset(el.rn), offset, Width::Byte)
+            }
+            Operation::Strbt(el) => self.store_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Byte,
+            ),
+            Operation::StrhImmediate(el) => self.store_single(
+                el.rt,
+                Addressing::new(el.rn, el.add, el.index, el.w),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Half,
+            ),
+            Operation::StrhRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.store_single(el.rt, Addressing::offset(el.rn), offset, Width::Half)
+            }
+            Operation::Strht(el) => self.store_single(
+                el.rt,
+                Addressing::offset(el.rn),
+                Self::word(el.imm.unwrap_or(0)),
+                Width::Half,
+            ),
+            Operation::StrdImmediate(el) => {
+                let addressing =
+                    Addressing::new(el.rn, el.add, el.index.unwrap_or(true), literal(&el.w));
+                let (address, writeback) =
+                    self.address(addressing, Self::word(el.imm.unwrap_or(0)));
+                let (rt, rt2) = (self.read(el.rt), self.read(el.rt2));
+                self.store(address, rt);
+                let address = self.add(address, Self::word(4));
+                self.store(address, rt2);
+                self.writeback(el.rn, writeback);
+            }
+            Operation::Ldm(el) => self.load_multiple(el.rn, &el.registers, literal(&el.w), false),
+            Operation::Ldmdb(el) => self.load_multiple(el.rn, &el.registers, literal(&el.w), true),
+            Operation::Pop(el) => self.load_multiple(Register::SP, &el.registers, true, false),
+            Operation::Stm(el) => self.store_multiple(el.rn, &el.registers, literal(&el.w), false),
+            Operation::Stmdb(el) => self.store_multiple(el.rn, &el.registers, literal(&el.w), true),
+            Operation::Push(el) => self.store_multiple(Register::SP, &el.registers, true, true),
+            Operation::Ldrex(el) => self.load_exclusive(el.rt, el.rn, el.imm, Width::Word),
+            Operation::Ldrexb(el) => self.load_exclusive(el.rt, el.rn, 0, Width::Byte),
+            Operation::Ldrexh(el) => self.load_exclusive(el.rt, el.rn, 0, Width::Half),
+            Operation::Strex(el) => {
+                self.store_exclusive(el.rd, el.rt, el.rn, el.imm.unwrap_or(0), Width::Word)
+            }
+            Operation::Strexb(el) => self.store_exclusive(el.rd, el.rt, el.rn, 0, Width::Byte),
+            Operation::Strexh(el) => self.store_exclusive(el.rd, el.rt, el.rn, 0, Width::Half),
+            Operation::Clrex(_) => self.emit(Statement::Intrinsic(Intrinsic::ClearExclusive)),
+            Operation::PldImmediate(el) => {
+                let addressing = Addressing::new(el.rn, el.add.unwrap_or(true), true, false);
+                self.preload(addressing, Self::word(el.imm), false)
+            }
+            Operation::PldLiteral(el) => {
+                let addressing = Addressing::new(Register::PC, el.add.unwrap_or(true), true, false);
+                self.preload(addressing, Self::word(el.imm), false)
+            }
+            Operation::PldRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.preload(Addressing::offset(el.rn), offset, false)
+            }
+            Operation::PliImmediate(el) => {
+                let rn = el.rn.unwrap_or(Register::PC);
+                let addressing = Addressing::new(rn, el.add.unwrap_or(true), true, false);
+                self.preload(addressing, Self::word(el.imm), true)
+            }
+            Operation::PliRegister(el) => {
+                let offset = self.shifted_register(el.rm, &el.shift);
+                self.preload(Addressing::offset(el.rn), offset, true)
+            }
+
+            // ============================= Branches =============================
+            Operation::B(el) => {
+                let pc = self.read(Register::PC);
+                let target = self.add(pc, Self::word(el.imm));
+                match el.condition {
+                    Condition::None => self.branch(target, BranchKind::Jump),
+                    ref condition => {
+                        let condition = self.condition(condition);
+                        self.conditional_branch(condition, target)
+                    }
+                }
+            }
+            Operation::Bl(el) => {
+                let next = self.next_instruction(context);
+                let lr = self.or(next, Self::word(1));
+                let pc = self.read(Register::PC);
+                let target = self.add(pc, Self::word(el.imm));
+                self.write(Register::LR, lr);
+                self.branch(target, BranchKind::Call);
+            }
+            Operation::Blx(el) => {
+                let target = self.read(el.rm);
+                let next = self.next_instruction(context);
+                let lr = self.or(next, Self::word(1));
+                self.write(Register::LR, lr);
+                self.branch(target, BranchKind::Call);
+            }
+            Operation::Bx(el) => {
+                let target = self.read(el.rm);
+                let kind = match el.rm {
+                    Register::LR => BranchKind::Return,
+                    _ => BranchKind::Exchange,
+                };
+                self.branch(target, kind);
+            }
+            Operation::Cbz(el) => {
+                let rn = self.read(el.rn);
+                let condition = match literal(&el.non) {
+                    true => self.binary(BinaryOp::Ne, rn, Self::word(0)),
+                    false => self.is_zero(rn),
+                };
+                let pc = self.read(Register::PC);
+                let target = self.add(pc, Self::word(el.imm));
+                self.conditional_branch(condition, target)
+            }
+            Operation::Tb(el) => {
+                let (rn, rm) = (self.read(el.rn), self.read(el.rm));
+                let (offset, width) = match literal(&el.is_tbh) {
+                    true => (self.binary(BinaryOp::Shl, rm, Self::word(1)), Width::Half),
+                    false => (rm, Width::Byte),
+                };
+                let address = self.add(rn, offset);
+                let entry = self.load(address, width);
+                let entry = self.zero_extend(entry, Width::Word);
+                let offset = self.binary(BinaryOp::Shl, entry, Self::word(1));
+                let pc = self.read(Register::PC);
+                let target = self.add(pc, offset);
+                self.branch(target, BranchKind::Jump);
+            }
+
+            // ========================== System and hints ==========================
+            Operation::It(el) => {
+                self.emit(Statement::Intrinsic(Intrinsic::IfThen(el.conds.clone())))
+            }
+            Operation::Nop(_) | Operation::UnallocatedHint(_) => {}
+            Operation::Mrs(el) => {
+                let dst = self.local(Width::Word);
+                self.emit(Statement::ReadSpecial { dst, sysm: el.sysm });
+                self.write(el.rd, dst.into());
+            }
+            Operation::Msr(el) => {
+                let value = self.read(el.rn);
+                self.emit(Statement::WriteSpecial {
+                    sysm: el.sysm,
+                    mask: el.mask,
+                    value,
+                })
+            }
+            Operation::Cps(el) => {
+                self.emit(Statement::Intrinsic(Intrinsic::ChangeProcessorState {
+                    enable: el.enable && !el.disable,
+                    affect_pri: el.affect_pri,
+                    affect_fault: el.affect_fault,
+                }))
+            }
+            Operation::Dmb(el) => self.emit(Statement::Intrinsic(Intrinsic::Barrier(
+                Barrier::DataMemory,
+                el.option.unwrap_or(BarrierOption::Sy),
+            ))),
+            Operation::Dsb(el) => self.emit(Statement::Intrinsic(Intrinsic::Barrier(
+                Barrier::DataSynchronization,
+                el.option.unwrap_or(BarrierOption::Sy),
+            ))),
+            Operation::Isb(el) => self.emit(Statement::Intrinsic(Intrinsic::Barrier(
+                Barrier::InstructionSynchronization,
+                el.option.unwrap_or(BarrierOption::Sy),
+            ))),
+            Operation::Dbg(el) => self.emit(Statement::Intrinsic(Intrinsic::Debug(el.option))),
+            Operation::Sev(_) => self.emit(Statement::Intrinsic(Intrinsic::SendEvent)),
+            Operation::Wfe(_) => self.emit(Statement::Intrinsic(Intrinsic::WaitForEvent)),
+            Operation::Wfi(_) => self.emit(Statement::Intrinsic(Intrinsic::WaitForInterrupt)),
+            Operation::Yield(_) => self.emit(Statement::Intrinsic(Intrinsic::Yield)),
+            Operation::Svc(el) => {
+                self.emit(Statement::Intrinsic(Intrinsic::SupervisorCall(el.imm)))
+            }
+            Operation::Bkpt(el) => self.emit(Statement::Intrinsic(Intrinsic::Breakpoint(el.imm))),
+            Operation::Udf(el) => self.emit(Statement::Intrinsic(Intrinsic::Undefined(el.imm))),
+
+            // ============================ Co processor ============================
+            Operation::Cdp(_)
+            | Operation::LdcImmediate(_)
+            | Operation::LdcLiteral(_)
+            | Operation::Mcrr(_)
+            | Operation::Mcr(_)
+            | Operation::Mrrc(_)
+            | Operation::Mrc(_)
+            | Operation::Stc(_) => self.emit(Statement::Intrinsic(Intrinsic::Coprocessor(
+                Box::new(operation.clone()),
+            ))),
+        }
+    }
+
+    /// Returns the address of the next instruction.
+    fn next_instruction(&mut self, context: &Context) -> Value {
+        let pc = self.read(Register::PC);
+        match context.size {
+            InstructionSize::Wide => pc,
+            InstructionSize::Narrow => self.sub(pc, Self::word(2)),
+        }
+    }
+
+    /// Returns `Align(PC, 4)`, the base address for literal accesses.
+    fn literal_base(&mut self) -> Value {
+        let pc = self.read(Register::PC);
+        self.and(pc, Self::word(!0b11))
+    }
+
+    /// Returns a mask with bits `lsb` to `msb` set.
+    fn field_mask(lsb: u32, msb: u32) -> u32 {
+        if msb < lsb {
+            return 0;
+        }
+        let width = msb - lsb + 1;
+        match width {
+            32.. => u32::MAX,
+            width => ((1 << width) - 1) << lsb,
+        }
+    }
+
+    /// Reads the register and applies the shift, the carry is ignored.
+    fn shifted_register(&mut self, rm: Register, shift: &Option<ImmShift>) -> Value {
+        let rm = self.read(rm);
+        self.shift(rm, shift)
+    }
+
+    /// Computes the value of the operand and the carry out, if the carry is
+    /// affected.
+    fn operand(&mut self, operand: Operand) -> (Value, Option<Value>) {
+        match operand {
+            Operand::Immediate(imm, carry) => (Self::word(imm), carry.map(Self::bit)),
+            Operand::Register(rm, shift) => {
+                let rm = self.read(rm);
+                match shift {
+                    None
+                    | Some(ImmShift {
+                        shift_n: 0,
+                        shift_t: Shift::Lsl,
+                    }) => (rm, None),
+                    Some(ImmShift {
+                        shift_t: Shift::Rrx,
+                        ..
+                    }) => {
+                        let carry_in = self.read_flag(Flag::C);
+                        let (result, carry) = self.shift_c(rm, shift, carry_in);
+                        (result, Some(carry))
+                    }
+                    _ => {
+                        let (result, carry) = self.shift_c(rm, shift, Self::bit(false));
+                        (result, Some(carry))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lifts an instruction that uses `AddWithCarry`, if no destination
+    /// register is given only the flags are written.
+    fn arithmetic(
+        &mut self,
+        op: Arithmetic,
+        rd: Option<Register>,
+        rn: Register,
+        operand: Operand,
+        set_flags: bool,
+    ) {
+        let rn = self.read(rn);
+        let (operand, _) = self.operand(operand);
+        let (x, y, carry) = match op {
+            Arithmetic::Add => (rn, operand, Self::bit(false)),
+            Arithmetic::Adc => (rn, operand, self.read_flag(Flag::C)),
+            Arithmetic::Sub => (rn, self.not(operand), Self::bit(true)),
+            Arithmetic::Sbc => (rn, self.not(operand), self.read_flag(Flag::C)),
+            Arithmetic::Rsb => (self.not(rn), operand, Self::bit(true)),
+        };
+        let result = self.add_with_carry(x, y, carry);
+        match rd {
+            Some(rd) => self.write_arithmetic(rd, result, set_flags),
+            None => {
+                let (result, carry, overflow) = result;
+                self.set_nz(result);
+                self.write_flag(Flag::C, carry);
+                self.write_flag(Flag::V, overflow);
+            }
+        }
+    }
+
+    /// Lifts a bitwise instruction, if no destination register is given only
+    /// the flags are written.
+    fn logical(
+        &mut self,
+        op: Logical,
+        rd: Option<Register>,
+        rn: Option<Register>,
+        operand: Operand,
+        set_flags: bool,
+    ) {
+        let rn = rn.map(|rn| self.read(rn));
+        let (operand, carry) = self.operand(operand);
+        let rn = rn.unwrap_or(Self::word(0));
+        let result = match op {
+            Logical::And => self.and(rn, operand),
+            Logical::Bic => {
+                let operand = self.not(operand);
+                self.and(rn, operand)
+            }
+            Logical::Orr => self.or(rn, operand),
+            Logical::Orn => {
+                let operand = self.not(operand);
+                self.or(rn, operand)
+            }
+            Logical::Eor => self.xor(rn, operand),
+            Logical::Mov => operand,
+            Logical::Mvn => self.not(operand),
+        };
+        match rd {
+            Some(rd) => self.write_logical(rd, result, carry, set_flags),
+            None => {
+                self.set_nz(result);
+                if let Some(carry) = carry {
+                    self.write_flag(Flag::C, carry);
+                }
+            }
+        }
+    }
+
+    fn shift_immediate(
+        &mut self,
+        rd: Register,
+        rm: Register,
+        shift_t: Shift,
+        amount: u32,
+        set_flags: bool,
+    ) {
+        let shift = Some(ImmShift {
+            shift_n: amount as u8,
+            shift_t,
+        });
+        let (result, carry) = self.operand(Operand::Register(rm, &shift));
+        self.write_logical(rd, result, carry, set_flags);
+    }
+
+    fn shift_register(
+        &mut self,
+        rd: Register,
+        rn: Register,
+        rm: Register,
+        shift_t: Shift,
+        set_flags: bool,
+    ) {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let carry_in = self.read_flag(Flag::C);
+        let (result, carry) = self.shift_c_register(rn, shift_t, rm, carry_in);
+        self.write_logical(rd, result, Some(carry), set_flags);
+    }
+
+    /// Returns the bytes of the register, least significant byte first.
+    fn bytes(&mut self, register: Register) -> [Value; 4] {
+        let value = self.read(register);
+        [0u8, 1, 2, 3].map(|byte| self.extract(value, byte * 8 + 7, byte * 8))
+    }
+
+    /// Concatenates the lanes, the first lane is placed in the most
+    /// significant bits.
+    fn concat_lanes(&mut self, lanes: &[Value]) -> Value {
+        match lanes {
+            [lane] => *lane,
+            lanes => {
+                let (high, low) = lanes.split_at(lanes.len() / 2);
+                let high = self.concat_lanes(high);
+                let low = self.concat_lanes(low);
+                self.concat(high, low)
+            }
+        }
+    }
+
+    /// Extracts lane `index` of the value and extends it to a word.
+    fn lane(&mut self, value: Value, index: u8, bits: u8, signed: bool) -> Value {
+        let lane = self.extract(value, (index + 1) * bits - 1, index * bits);
+        match signed {
+            true => self.sign_extend(lane, Width::Word),
+            false => self.zero_extend(lane, Width::Word),
+        }
+    }
+
+    fn extend(
+        &mut self,
+        rd: Register,
+        rn: Option<Register>,
+        rm: Register,
+        rotation: Option<u32>,
+        width: Width,
+        signed: bool,
+    ) {
+        let rn = rn.map(|rn| self.read(rn));
+        let rm = self.read(rm);
+        let rotated = self.ror(rm, rotation.unwrap_or(0));
+        let extracted = self.extract(rotated, (width.bits() - 1) as u8, 0);
+        let extended = match signed {
+            true => self.sign_extend(extracted, Width::Word),
+            false => self.zero_extend(extracted, Width::Word),
+        };
+        let result = match rn {
+            Some(rn) => self.add(rn, extended),
+            None => extended,
+        };
+        self.write(rd, result);
+    }
+
+    fn extend16(
+        &mut self,
+        rd: Register,
+        rn: Option<Register>,
+        rm: Register,
+        rotation: Option<u32>,
+        signed: bool,
+    ) {
+        let rn = rn.map(|rn| self.read(rn));
+        let rm = self.read(rm);
+        let rotated = self.ror(rm, rotation.unwrap_or(0));
+        let mut halves = [Self::word(0); 2];
+        for (index, half) in halves.iter_mut().enumerate() {
+            let low = (index * 16) as u8;
+            let byte = self.extract(rotated, low + 7, low);
+            let extended = match signed {
+                true => self.sign_extend(byte, Width::Half),
+                false => self.zero_extend(byte, Width::Half),
+            };
+            *half = match rn {
+                Some(rn) => {
+                    let rn = self.extract(rn, low + 15, low);
+                    self.add(rn, extended)
+                }
+                None => extended,
+            };
+        }
+        let result = self.concat(halves[1], halves[0]);
+        self.write(rd, result);
+    }
+
+    /// Multiplies the signed halfwords of the values.
+    fn halfword_product(&mut self, rn: Value, n_high: bool, rm: Value, m_high: bool) -> Value {
+        let n = self.lane(rn, n_high as u8, 16, true);
+        let m = self.lane(rm, m_high as u8, 16, true);
+        self.binary(BinaryOp::Mul, n, m)
+    }
+
+    /// Returns the product of the bottom halfwords and the top halfwords, the
+    /// halfwords of `rm` are swapped if `swap` is set.
+    fn dual_product(&mut self, rn: Register, rm: Register, swap: bool) -> (Value, Value) {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let rm = match swap {
+            true => self.ror(rm, 16),
+            false => rm,
+        };
+        let low = self.halfword_product(rn, false, rm, false);
+        let high = self.halfword_product(rn, true, rm, true);
+        (low, high)
+    }
+
+    /// Returns the top 32 bits of the 48 bit product of `rn` and a halfword of
+    /// `rm`.
+    fn word_halfword_product(&mut self, rn: Register, rm: Register, m_high: bool) -> Value {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let m = self.lane(rm, m_high as u8, 16, true);
+        let n = self.sign_extend(rn, Width::Double);
+        let m = self.sign_extend(m, Width::Double);
+        let product = self.binary(BinaryOp::Mul, n, m);
+        self.extract(product, 47, 16)
+    }
+
+    /// Sums the signed words and sets the Q flag if the result does not fit in
+    /// a word.
+    fn sum_q(&mut self, terms: &[Value]) -> Value {
+        let mut sum = Self::constant(0, Width::Double);
+        for term in terms {
+            let term = self.sign_extend(*term, Width::Double);
+            sum = self.add(sum, term);
+        }
+        let result = self.extract(sum, 31, 0);
+        let extended = self.sign_extend(result, Width::Double);
+        let overflow = self.binary(BinaryOp::Ne, extended, sum);
+        self.saturate_q(overflow);
+        result
+    }
+
+    fn long_product(&mut self, rn: Register, rm: Register, signed: bool) -> Value {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let (rn, rm) = match signed {
+            true => (
+                self.sign_extend(rn, Width::Double),
+                self.sign_extend(rm, Width::Double),
+            ),
+            false => (
+                self.zero_extend(rn, Width::Double),
+                self.zero_extend(rm, Width::Double),
+            ),
+        };
+        self.binary(BinaryOp::Mul, rn, rm)
+    }
+
+    fn read_long(&mut self, rdlo: Register, rdhi: Register) -> Value {
+        let lo = self.read(rdlo);
+        let hi = self.read(rdhi);
+        self.concat(hi, lo)
+    }
+
+    fn write_long(&mut self, rdlo: Register, rdhi: Register, value: Value) {
+        let lo = self.extract(value, 31, 0);
+        let hi = self.extract(value, 63, 32);
+        self.write(rdlo, lo);
+        self.write(rdhi, hi);
+    }
+
+    /// Lifts `SMMUL`, `SMMLA` and `SMMLS`, the accumulator is added if the
+    /// flag is set and subtracted otherwise.
+    fn most_significant_multiply(
+        &mut self,
+        rd: Register,
+        rn: Register,
+        rm: Register,
+        accumulator: Option<(Register, bool)>,
+        round: Option<bool>,
+    ) {
+        let product = self.long_product(rn, rm, true);
+        let result = match accumulator {
+            Some((ra, add)) => {
+                let ra = self.read(ra);
+                let ra = self.concat(ra, Self::word(0));
+                match add {
+                    true => self.add(ra, product),
+                    false => self.sub(ra, product),
+                }
+            }
+            None => product,
+        };
+        let result = match round.unwrap_or(false) {
+            true => self.add(result, Self::constant(0x8000_0000, Width::Double)),
+            false => result,
+        };
+        let result = self.extract(result, 63, 32);
+        self.write(rd, result);
+    }
+
+    fn sum_absolute_differences(&mut self, rn: Register, rm: Register) -> Value {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let mut sum = Self::word(0);
+        for index in 0..4 {
+            let n = self.lane(rn, index, 8, false);
+            let m = self.lane(rm, index, 8, false);
+            let difference = self.sub(n, m);
+            let negative = self.binary(BinaryOp::SLt, difference, Self::word(0));
+            let negated = self.unary(UnaryOp::Neg, difference);
+            let absolute = self.ite(negative, negated, difference);
+            sum = self.add(sum, absolute);
+        }
+        sum
+    }
+
+    /// Lifts `QADD`, `QSUB`, `QDADD` and `QDSUB`.
+    fn saturating(&mut self, rd: Register, rm: Register, rn: Register, add: bool, double: bool) {
+        let rm = self.read(rm);
+        let rn = self.read(rn);
+        let rm = self.sign_extend(rm, Width::Double);
+        let rn = self.sign_extend(rn, Width::Double);
+        let rn = match double {
+            true => {
+                let doubled = self.add(rn, rn);
+                let (doubled, saturated) = self.signed_saturate(doubled, 32);
+                self.saturate_q(saturated);
+                self.sign_extend(doubled, Width::Double)
+            }
+            false => rn,
+        };
+        let result = match add {
+            true => self.add(rm, rn),
+            false => self.sub(rm, rn),
+        };
+        let (result, saturated) = self.signed_saturate(result, 32);
+        self.write(rd, result);
+        self.saturate_q(saturated);
+    }
+
+    fn saturate16(&mut self, rd: Register, rn: Register, bits: u32, signed: bool) {
+        let rn = self.read(rn);
+        let mut halves = [Self::word(0); 2];
+        let mut saturated = Self::bit(false);
+        for (index, half) in halves.iter_mut().enumerate() {
+            let value = self.lane(rn, index as u8, 16, true);
+            let (result, lane_saturated) = match signed {
+                true => self.signed_saturate(value, bits),
+                false => self.unsigned_saturate(value, bits),
+            };
+            *half = self.extract(result, 15, 0);
+            saturated = self.or(saturated, lane_saturated);
+        }
+        let result = self.concat(halves[1], halves[0]);
+        self.write(rd, result);
+        self.saturate_q(saturated);
+    }
+
+    /// Lifts the parallel add and subtract instructions.
+    fn parallel(&mut self, rd: Register, rn: Register, rm: Register, lanes: Lanes, mode: Parallel) {
+        let rn = self.read(rn);
+        let rm = self.read(rm);
+        let signed = matches!(
+            mode,
+            Parallel::Signed | Parallel::SignedSaturating | Parallel::SignedHalving
+        );
+        // Each lane is described as (lane of rn, lane of rm, add).
+        let (bits, operations): (u8, &[(u8, u8, bool)]) = match lanes {
+            Lanes::Add8 => (8, &[(0, 0, true), (1, 1, true), (2, 2, true), (3, 3, true)]),
+            Lanes::Sub8 => (8, &[
+                (0, 0, false),
+                (1, 1, false),
+                (2, 2, false),
+                (3, 3, false),
+            ]),
+            Lanes::Add16 => (16, &[(0, 0, true), (1, 1, true)]),
+            Lanes::Sub16 => (16, &[(0, 0, false), (1, 1, false)]),
+            Lanes::Asx => (16, &[(0, 1, false), (1, 0, true)]),
+            Lanes::Sax => (16, &[(0, 1, true), (1, 0, false)]),
+        };
+        let flags_per_lane = 4 / operations.len() as u8;
+
+        let mut results = Vec::with_capacity(operations.len());
+        for (index, (n, m, add)) in operations.iter().enumerate() {
+            let n = self.lane(rn, *n, bits, signed);
+            let m = self.lane(rm, *m, bits, signed);
+            let value = match add {
+                true => self.add(n, m),
+                false => self.sub(n, m),
+            };
+            let result = match mode {
+                Parallel::Signed | Parallel::Unsigned => {
+                    let ge = match (signed, add) {
+                        (false, true) => self.binary(BinaryOp::ULe, Self::word(1 << bits), value),
+                        _ => self.binary(BinaryOp::SLe, Self::word(0), value),
+                    };
+                    for flag in 0..flags_per_lane {
+                        self.write_flag(Flag::Ge(index as u8 * flags_per_lane + flag), ge);
+                    }
+                    value
+                }
+                Parallel::SignedSaturating => self.signed_saturate(value, bits as u32).0,
+                Parallel::UnsignedSaturating => self.unsigned_saturate(value, bits as u32).0,
+                Parallel::SignedHalving | Parallel::UnsignedHalving => {
+                    self.binary(BinaryOp::AShr, value, Self::word(1))
+                }
+            };
+            results.push(self.extract(result, bits - 1, 0));
+        }
+        results.reverse();
+        let result = self.concat_lanes(&results);
+        self.write(rd, result);
+    }
+
+    /// Computes the address and the value to write back, if any.
+    fn address(&mut self, addressing: Addressing, offset: Value) -> (Value, Option<Value>) {
+        let base = match addressing.rn {
+            Register::PC => self.literal_base(),
+            rn => self.read(rn),
+        };
+        let offset_address = match addressing.add {
+            true => self.add(base, offset),
+            false => self.sub(base, offset),
+        };
+        let address = match addressing.index {
+            true => offset_address,
+            false => base,
+        };
+        let writeback = match addressing.wback {
+            true => Some(offset_address),
+            false => None,
+        };
+        (address, writeback)
+    }
+
+    fn writeback(&mut self, rn: Register, writeback: Option<Value>) {
+        if let Some(value) = writeback {
+            self.write(rn, value);
+        }
+    }
+
+    fn load_single(
+        &mut self,
+        rt: Register,
+        addressing: Addressing,
+        offset: Value,
+        width: Width,
+        signed: bool,
+    ) {
+        let (address, writeback) = self.address(addressing, offset);
+        let value = self.load(address, width);
+        let value = match signed {
+            true => self.sign_extend(value, Width::Word),
+            false => self.zero_extend(value, Width::Word),
+        };
+        self.writeback(addressing.rn, writeback);
+        let kind = match addressing.rn {
+            Register::SP => BranchKind::Return,
+            _ => BranchKind::Exchange,
+        };
+        self.write_loaded(rt, value, kind);
+    }
+
+    fn load_dual(&mut self, rt: Register, rt2: Register, addressing: Addressing, imm: u32) {
+        let (address, writeback) = self.address(addressing, Self::word(imm));
+        let first = self.load(address, Width::Word);
+        let address = self.add(address, Self::word(4));
+        let second = self.load(address, Width::Word);
+        self.writeback(addressing.rn, writeback);
+        self.write(rt, first);
+        self.write(rt2, second);
+    }
+
+    fn store_single(&mut self, rt: Register, addressing: Addressing, offset: Value, width: Width) {
+        let (address, writeback) = self.address(addressing, offset);
+        let value = self.read(rt);
+        let value = match width {
+            Width::Word => value,
+            width => self.extract(value, (width.bits() - 1) as u8, 0),
+        };
+        self.store(address, value);
+        self.writeback(addressing.rn, writeback);
+    }
+
+    fn load_multiple(
+        &mut self,
+        rn: Register,
+        registers: &RegisterList,
+        wback: bool,
+        decrement: bool,
+    ) {
+        let registers = sorted(registers);
+        let size = Self::word(4 * registers.len() as u32);
+        let base = self.read(rn);
+        let start = match decrement {
+            true => self.sub(base, size),
+            false => base,
+        };
+        let mut values = Vec::with_capacity(registers.len());
+        for index in 0..registers.len() {
+            let address = self.add(start, Self::word(4 * index as u32));
+            values.push(self.load(address, Width::Word));
+        }
+        if wback && !registers.contains(&rn) {
+            let end = match decrement {
+                true => start,
+                false => self.add(base, size),
+            };
+            self.write(rn, end);
+        }
+        let mut target = None;
+        for (register, value) in registers.into_iter().zip(values) {
+            match register {
+                Register::PC => target = Some(value),
+                register => self.write(register, value),
+            }
+        }
+        if let Some(target) = target {
+            let kind = match rn {
+                Register::SP => BranchKind::Return,
+                _ => BranchKind::Exchange,
+            };
+            self.branch(target, kind);
+        }
+    }
+
+    fn store_multiple(
+        &mut self,
+        rn: Register,
+        registers: &RegisterList,
+        wback: bool,
+        decrement: bool,
+    ) {
+        let registers = sorted(registers);
+        let size = Self::word(4 * registers.len() as u32);
+        let base = self.read(rn);
+        let start = match decrement {
+            true => self.sub(base, size),
+            false => base,
+        };
+        for (index, register) in registers.iter().enumerate() {
+            let value = self.read(*register);
+            let address = self.add(start, Self::word(4 * index as u32));
+            self.store(address, value);
+        }
+        if wback {
+            let end = match decrement {
+                true => start,
+                false => self.add(base, size),
+            };
+            self.write(rn, end);
+        }
+    }
+
+    fn load_exclusive(&mut self, rt: Register, rn: Register, imm: u32, width: Width) {
+        let rn = self.read(rn);
+        let address = self.add(rn, Self::word(imm));
+        let value = self.load(address, width);
+        let value = self.zero_extend(value, Width::Word);
+        self.emit(Statement::Intrinsic(Intrinsic::SetExclusive(address)));
+        self.write(rt, value);
+    }
+
+    fn store_exclusive(
+        &mut self,
+        rd: Register,
+        rt: Register,
+        rn: Register,
+        imm: u32,
+        width: Width,
+    ) {
+        let rn = self.read(rn);
+        let address = self.add(rn, Self::word(imm));
+        let value = self.read(rt);
+        let value = match width {
+            Width::Word => value,
+            width => self.extract(value, (width.bits() - 1) as u8, 0),
+        };
+        let status = self.local(Width::Word);
+        self.emit(Statement::StoreExclusive {
+            status,
+            address,
+            value,
+        });
+        self.write(rd, status.into());
+    }
+
+    fn preload(&mut self, addressing: Addressing, offset: Value, instruction: bool) {
+        let (address, _) = self.address(addressing, offset);
+        self.emit(Statement::Intrinsic(Intrinsic::Preload {
+            address,
+            instruction,
+        }));
+    }
+}
@@ -0,0 +1,386 @@
+//! Formats decoded operations as a symbol annotated listing.
+//!
+//! The listing is similar to the output of `objdump -d`. Every function
+//! starts with a `<name>:` label, every instruction is preceded by the
+//! halfwords that it was decoded from and the targets of direct branches are
+//! resolved to `<symbol+offset>`. The operations themselves are printed using
+//! their [`Debug`] representation as this crate does not provide a textual
+//! representation of the assembly.
+//!
+//! The symbols are provided by a [`SymbolTable`], which is implemented for
+//! the [`Elf`](crate::loader::elf::Elf) files when the `loader` feature is
+//! enabled and for [`Symbols`] which can be populated from any other source
+//! such as map files.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{
+//!     listing::{Listing, Symbols},
+//!     prelude::*,
+//! };
+//!
+//! let mut symbols = Symbols::new();
+//! symbols.insert("main", 0x8001, 4);
+//! symbols.insert("helper", 0x8005, 2);
+//!
+//! // bl 0x8004; bx lr
+//! let bin = vec![0x00, 0xf0, 0x00, 0xf8, 0x70, 0x47];
+//! let asm = ASM::parse(&mut PeekableBuffer::from(bin.into_iter())).unwrap();
+//! let operations: Vec<_> = [0x8000, 0x8004]
+//!     .into_iter()
+//!     .zip(asm.statements().iter().cloned())
+//!     .collect();
+//!
+//! let listing = Listing::new(&symbols).format(&operations);
+//! assert!(listing.contains("00008000 <main>:"));
+//! assert!(listing.contains("8000:\tf000 f800\t"));
+//! assert!(listing.contains("8004 <helper>"));
+//! ```
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{operation::Operation, DecodedInstruction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A function symbol as returned by a [`SymbolTable`].
+pub struct FunctionSymbol<'a> {
+    /// The name of the function.
+    pub name: &'a str,
+    /// The address of the first instruction in the function, without the
+    /// Thumb bit.
+    pub address: u32,
+    /// The size of the function in bytes, zero if it is not known.
+    pub size: u32,
+}
+
+/// A source of function symbols.
+pub trait SymbolTable {
+    /// Returns the function that contains the address.
+    ///
+    /// Functions with an unknown size are assumed to extend until the next
+    /// function.
+    fn lookup(&self, address: u32) -> Option<FunctionSymbol<'_>>;
+
+    /// Describes the address relative to the enclosing function, i.e.
+    /// `main` or `main+0x4`.
+    fn describe(&self, address: u32) -> Option<String> {
+        let function = self.lookup(address)?;
+        Some(match address.checked_sub(function.address)? {
+            0 => function.name.to_string(),
+            offset => format!("{}+{offset:#x}", function.name),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// A symbol table that is populated manually.
+pub struct Symbols {
+    functions: BTreeMap<u32, (String, u32)>,
+}
+
+#[derive(Debug)]
+/// Formats operations using the symbols in the table.
+pub struct Listing<'a, S: SymbolTable> {
+    symbols: &'a S,
+}
+
+/// Returns the function that contains the address out of the functions
+/// sorted by address.
+fn enclosing<'a, I>(functions: I, address: u32) -> Option<FunctionSymbol<'a>>
+where
+    I: DoubleEndedIterator<Item = FunctionSymbol<'a>>,
+{
+    let function = functions
+        .rev()
+        .find(|function| function.address <= address)?;
+    match function.size {
+        0 => Some(function),
+        size if (address - function.address) < size => Some(function),
+        _ => None,
+    }
+}
+
+impl Symbols {
+    /// Creates an empty symbol table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a function to the table.
+    ///
+    /// The Thumb bit of the address is cleared.
+    pub fn insert<S: Into<String>>(&mut self, name: S, address: u32, size: u32) {
+        self.functions.insert(address & !1, (name.into(), size));
+    }
+}
+
+impl SymbolTable for Symbols {
+    fn lookup(&self, address: u32) -> Option<FunctionSymbol<'_>> {
+        enclosing(
+            self.functions
+                .range(..=address)
+                .map(|(address, (name, size))| FunctionSymbol {
+                    name,
+                    address: *address,
+                    size: *size,
+                }),
+            address,
+        )
+    }
+}
+
+impl<T: SymbolTable> SymbolTable for &T {
+    fn lookup(&self, address: u32) -> Option<FunctionSymbol<'_>> {
+        (*self).lookup(address)
+    }
+}
+
+#[cfg(feature = "loader")]
+impl SymbolTable for crate::loader::elf::Elf<'_> {
+    fn lookup(&self, address: u32) -> Option<FunctionSymbol<'_>> {
+        let symbol = self.function_before(address)?;
+        enclosing(
+            std::iter::once(FunctionSymbol {
+                name: &symbol.name,
+                address: symbol.value & !1,
+                size: symbol.size,
+            }),
+            address,
+        )
+    }
+}
+
+impl<'a, S: SymbolTable> Listing<'a, S> {
+    /// Creates a new listing that resolves addresses using the symbols.
+    pub const fn new(symbols: &'a S) -> Self {
+        Self { symbols }
+    }
+
+    /// Formats a single operation located at `address` along with the
+    /// halfwords that it was decoded from, without the function label.
+    pub fn line(&self, address: u32, halfwords: &[u16], operation: &Operation) -> String {
+        let encoding: Vec<String> = halfwords
+            .iter()
+            .map(|halfword| format!("{halfword:04x}"))
+            .collect();
+        let mut line = format!("{address:8x}:\t{:<9}\t{operation:?}", encoding.join(" "));
+        if let Some(target) = operation.branch_target(address) {
+            let _ = write!(line, "\t{target:x}");
+            if let Some(description) = self.symbols.describe(target) {
+                let _ = write!(line, " <{description}>");
+            }
+        }
+        line
+    }
+
+    /// Formats the decoded instructions, which are given as
+    /// `(address, instruction)` in increasing address order.
+    ///
+    /// A label is emitted before the first operation of each function.
+    pub fn format<'b, I>(&self, operations: I) -> String
+    where
+        I: IntoIterator<Item = &'b (u32, DecodedInstruction)>,
+    {
+        self.format_commented(
+            operations
+                .into_iter()
+                .map(|(address, decoded)| {
+                    (*address, decoded.halfwords().collect(), &decoded.op, None)
+                }),
+        )
+    }
+
+    /// Formats the reachable instructions in the disassembly.
+    ///
+    /// The values of the [`Literal`](crate::disassembler::Literal)s are added
+    /// as comments, i.e. `; =0x40021000`.
+    #[cfg(feature = "disassembler")]
+    pub fn disassembly(&self, disassembly: &crate::disassembler::Disassembly) -> String {
+        self.format_commented(
+            disassembly
+                .instructions
+                .iter()
+                .map(|(address, instruction)| {
+                    (
+                        *address,
+                        instruction.halfwords().collect(),
+                        &instruction.operation,
+                        disassembly
+                            .literals
+                            .get(address)
+                            .map(|literal| literal.to_string()),
+                    )
+                }),
+        )
+    }
+
+    fn format_commented<'b, I>(&self, operations: I) -> String
+    where
+        I: Iterator<Item = (u32, Vec<u16>, &'b Operation, Option<String>)>,
+    {
+        let mut listing = String::new();
+        for (address, halfwords, operation, comment) in operations {
+            if let Some(function) = self.symbols.lookup(address) {
+                if function.address == address {
+                    let _ = writeln!(listing, "\n{address:08x} <{}>:", function.name);
+                }
+            }
+            listing.push_str(&self.line(address, &halfwords, operation));
+            if let Some(comment) = comment {
+                let _ = write!(listing, "\t; {comment}");
+            }
+            listing.push('\n');
+        }
+        listing
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        helpers::test::code,
+        listing::{FunctionSymbol, Listing, SymbolTable, Symbols},
+        prelude::*,
+        Policy,
+    };
+
+    fn symbols() -> Symbols {
+        let mut symbols = Symbols::new();
+        symbols.insert("main", 0x8001, 0x10);
+        symbols.insert("sized", 0x8021, 0x4);
+        symbols.insert("unsized", 0x8031, 0);
+        symbols
+    }
+
+    #[test]
+    fn test_lookup() {
+        let symbols = symbols();
+        assert_eq!(symbols.lookup(0x7ffe), None);
+        assert_eq!(symbols.lookup(0x8000).unwrap().name, "main");
+        assert_eq!(symbols.lookup(0x800e).unwrap().address, 0x8000);
+        assert_eq!(symbols.lookup(0x8010), None);
+        assert_eq!(symbols.lookup(0x8022).unwrap().name, "sized");
+        assert_eq!(symbols.lookup(0x8024), None);
+        assert_eq!(symbols.lookup(0x9000).unwrap().name, "unsized");
+
+        assert_eq!(symbols.describe(0x8000).unwrap(), "main");
+        assert_eq!(symbols.describe(0x800c).unwrap(), "main+0xc");
+        assert_eq!(symbols.describe(0x8010), None);
+    }
+
+    #[test]
+    fn test_describe_below_symbol() {
+        struct Above;
+        impl SymbolTable for Above {
+            fn lookup(&self, address: u32) -> Option<FunctionSymbol<'_>> {
+                Some(FunctionSymbol {
+                    name: "above",
+                    address: address + 4,
+                    size: 0,
+                })
+            }
+        }
+        assert_eq!(Above.describe(0x8000), None);
+    }
+
+    #[test]
+    fn test_branch_targets() {
+        let b: Operation = operation::B::builder()
+            .set_condition(Condition::Ne)
+            .set_imm((-8i32) as u32)
+            .complete()
+            .into();
+        assert_eq!(b.branch_target(0x8008), Some(0x8004));
+        let cbz: Operation = operation::Cbz::builder()
+            .set_non(Some(true))
+            .set_rn(Register::R0)
+            .set_imm(0x10)
+            .complete()
+            .into();
+        assert_eq!(cbz.branch_target(0x8000), Some(0x8014));
+        let bx: Operation = operation::Bx::builder()
+            .set_rm(Register::LR)
+            .complete()
+            .into();
+        assert_eq!(bx.branch_target(0x8000), None);
+    }
+
+    #[test]
+    fn test_listing() {
+        let symbols = symbols();
+        let decode = |address: u32, halfwords: &[u16]| {
+            let mut stream = PeekableBuffer::from(code(halfwords).into_iter());
+            (address, Operation::decode(&mut stream, Policy::Permissive).unwrap())
+        };
+        let operations = [
+            decode(0x8000, &[0xf000, 0xf80e]), // bl 0x8020
+            decode(0x8004, &[0xe002]),         // b 0x800c
+            decode(0x8020, &[0x4770]),         // bx lr
+            decode(0x8030, &[0xe002]),         // b 0x8038
+            decode(0x8032, &[0x4770]),         // bx lr
+        ];
+        let listing = Listing::new(&symbols).format(&operations);
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines, vec![
+            "",
+            "00008000 <main>:",
+            "    8000:\tf000 f80e\tBl(Bl { imm: 28 })\t8020 <sized>",
+            "    8004:\te002     \tB(B { condition: None, imm: 4 })\t800c <main+0xc>",
+            "",
+            "00008020 <sized>:",
+            "    8020:\t4770     \tBx(Bx { rm: LR })",
+            "",
+            "00008030 <unsized>:",
+            "    8030:\te002     \tB(B { condition: None, imm: 4 })\t8038 <unsized+0x8>",
+            "    8032:\t4770     \tBx(Bx { rm: LR })",
+        ]);
+    }
+
+    #[cfg(feature = "disassembler")]
+    #[test]
+    fn test_literal_comments() {
+        use crate::{disassembler::Disassembler, loader::Region};
+
+        let code = [
+            0x00, 0x48, // ldr r0, [pc, #0]
+            0x70, 0x47, // bx lr
+            0x00, 0x10, 0x02, 0x40, // .word 0x40021000
+        ];
+        let disassembly = Disassembler::new([Region::new(0x8000, &code)])
+            .with_entry(0x8000)
+            .run();
+        let listing = Listing::new(&symbols()).disassembly(&disassembly);
+        let lines: Vec<&str> = listing.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "00008000 <main>:");
+        assert!(lines[2].starts_with("    8000:\t4800     \t"));
+        assert!(lines[2].ends_with("\t; =0x40021000"));
+        assert!(!lines[3].contains(';'));
+    }
+
+    #[cfg(feature = "loader")]
+    #[test]
+    fn test_elf_symbols() {
+        use crate::loader::elf::{test::image, Elf};
+
+        let data = image(0x8000, &[0x70, 0x47, 0x70, 0x47], &[
+            ("$t", 0x8000, 0, 0),
+            ("first", 0x8001, 2, 2),
+            ("second", 0x8003, 2, 2),
+            ("object", 0x8002, 2, 1),
+        ]);
+        let elf = Elf::parse(&data).unwrap();
+        assert_eq!(elf.describe(0x8000).unwrap(), "first");
+        assert_eq!(elf.describe(0x8002).unwrap(), "second");
+        assert_eq!(elf.describe(0x8004), None);
+
+        let operations: Vec<_> = elf
+            .code()
+            .iter()
+            .flat_map(|region| region.decode().unwrap())
+            .collect();
+        let listing = Listing::new(&elf).format(&operations);
+        assert!(listing.contains("00008002 <second>:"));
+    }
+}
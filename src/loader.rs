@@ -0,0 +1,477 @@
+//! Loads program images so that they can be decoded.
+//!
+//! The crate itself decodes [`Stream`]s of bytes, this module locates the
+//! bytes that should be decoded in an image and tags them with the address
+//! that they are loaded at. Each image format is parsed in to a set of
+//! [`Region`]s which can be turned in to a [`RegionStream`] that tracks the
+//! address of the next instruction.
+//!
+//! The supported formats are
+//!
+//! - [`elf`], 32 bit little endian ELF files.
+//! - [`hex`], Intel HEX files.
+//! - [`srec`], Motorola S-record files.
+//!
+//! The record based formats are collected in to an [`Image`] which merges
+//! the records in to contiguous regions and reports the gaps between them.
+//! The entry points of an image can be found by parsing its
+//! [`vector_table`].
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{loader::Region, prelude::*};
+//!
+//! // movs r0, #1; bx lr
+//! let bytes = [0x01, 0x20, 0x70, 0x47];
+//! let region = Region::new(0x0800_0000, &bytes);
+//!
+//! let decoded = region.decode().unwrap();
+//! assert_eq!(decoded[0].0, 0x0800_0000);
+//! assert_eq!(decoded[1].0, 0x0800_0002);
+//! assert!(matches!(decoded[1].1.op, Operation::Bx(_)));
+//! ```
+
+pub mod elf;
+pub mod hex;
+pub mod srec;
+pub mod vector_table;
+
+use std::{collections::BTreeMap, iter::Copied, slice::Iter};
+
+use crate::{
+    buffer::PeekableBuffer,
+    operation::Operation,
+    Consume,
+    DecodedInstruction,
+    ParseError,
+    Peek,
+    Policy,
+    Stream,
+};
+
+/// A decoded instruction, tagged with the address that it is loaded at.
+pub type AddressedOperation = (u32, DecodedInstruction);
+
+#[derive(Debug)]
+/// Enumerates the errors that might occur while loading an image.
+pub enum LoaderError {
+    /// Thrown when the image ends before a structure that it refers to.
+    Truncated,
+
+    /// Thrown when the image does not start with the expected magic number.
+    InvalidMagic,
+
+    /// Thrown when the image is not a 32 bit image.
+    UnsupportedClass(u8),
+
+    /// Thrown when the image is not little endian.
+    UnsupportedEncoding(u8),
+
+    /// Thrown when the image is not built for the Arm architecture.
+    UnsupportedMachine(u16),
+
+    /// Thrown when a structure in the image is malformed.
+    Malformed(&'static str),
+
+    /// Thrown when a record on the given line is malformed.
+    InvalidRecord(usize, &'static str),
+
+    /// Thrown when the checksum of the record on the given line does not
+    /// match its contents.
+    InvalidChecksum(usize),
+
+    /// Thrown when data is written to an address that already holds data.
+    Overlap(u32),
+
+    /// Thrown when an entry in the vector table does not have the Thumb bit
+    /// set.
+    InvalidHandler(vector_table::Exception, u32),
+
+    /// Thrown when a vector table has more external interrupts than
+    /// Armv7-m supports.
+    TooManyInterrupts(usize),
+}
+
+impl std::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "the image is truncated"),
+            Self::InvalidMagic => write!(f, "invalid magic number"),
+            Self::UnsupportedClass(class) => {
+                write!(f, "unsupported class {class}, expected 32 bit")
+            }
+            Self::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported encoding {encoding}, expected little endian")
+            }
+            Self::UnsupportedMachine(machine) => {
+                write!(f, "unsupported machine {machine:#x}, expected Arm")
+            }
+            Self::Malformed(message) => write!(f, "malformed image, {message}"),
+            Self::InvalidRecord(line, message) => {
+                write!(f, "invalid record on line {line}, {message}")
+            }
+            Self::InvalidChecksum(line) => write!(f, "invalid checksum on line {line}"),
+            Self::Overlap(address) => write!(f, "data written twice to {address:#x}"),
+            Self::InvalidHandler(exception, address) => write!(
+                f,
+                "the handler {address:#x} for {exception:?} does not have the Thumb bit set"
+            ),
+            Self::TooManyInterrupts(irqs) => write!(
+                f,
+                "{irqs} external interrupts, at most {} are supported",
+                vector_table::MAX_IRQS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Enumerates the kinds of content that the Arm mapping symbols mark.
+pub enum Mapping {
+    /// Thumb instructions, marked by `$t`.
+    Thumb,
+    /// Arm instructions, marked by `$a`.
+    ///
+    /// These can not be executed on Armv7-m and are not decoded.
+    Arm,
+    /// Data such as literal pools, marked by `$d`.
+    Data,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A contiguous range of bytes that is loaded at a known address.
+pub struct Region<'a> {
+    /// The address of the first byte.
+    pub address: u32,
+    /// The contents of the region.
+    pub data: &'a [u8],
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A sparse memory image that is built from address tagged records.
+///
+/// Adjacent writes are merged in to a single region.
+pub struct Image {
+    regions: BTreeMap<u32, Vec<u8>>,
+    entry: Option<u32>,
+}
+
+#[derive(Debug)]
+/// A [`Stream`] over the bytes of a [`Region`].
+///
+/// The stream keeps track of the address of the next element, i.e. the
+/// address of the next instruction while decoding.
+pub struct RegionStream<'a> {
+    address: u32,
+    buffer: PeekableBuffer<u8, Copied<Iter<'a, u8>>>,
+}
+
+/// Decodes a string of hexadecimal digit pairs, as used by the record based
+/// formats.
+fn bytes_from_hex(text: &str, line: usize) -> Result<Vec<u8>, LoaderError> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return Err(LoaderError::InvalidRecord(
+            line,
+            "Expected pairs of hex digits",
+        ));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&text[index..index + 2], 16)
+                .map_err(|_| LoaderError::InvalidRecord(line, "Expected pairs of hex digits"))
+        })
+        .collect()
+}
+
+impl Mapping {
+    /// Parses the name of a mapping symbol, such as `$t` or `$d.literal`.
+    ///
+    /// Returns None if the name is not a mapping symbol.
+    pub fn from_symbol(name: &str) -> Option<Self> {
+        let (Some(mapping), Some(suffix)) = (name.get(..2), name.get(2..)) else {
+            return None;
+        };
+        if !(suffix.is_empty() || suffix.starts_with('.')) {
+            return None;
+        }
+        match mapping {
+            "$t" => Some(Self::Thumb),
+            "$a" => Some(Self::Arm),
+            "$d" => Some(Self::Data),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Region<'a> {
+    /// Creates a new region starting at `address`.
+    pub const fn new(address: u32, data: &'a [u8]) -> Self {
+        Self { address, data }
+    }
+
+    /// Returns the address after the last byte in the region.
+    pub fn end(&self) -> u64 {
+        self.address as u64 + self.data.len() as u64
+    }
+
+    /// Returns true if the address is in the region.
+    pub fn contains(&self, address: u32) -> bool {
+        address >= self.address && (address as u64) < self.end()
+    }
+
+    /// Returns the sub region that starts at `start` and ends before `end`.
+    ///
+    /// The range is clamped to the region.
+    pub fn slice(&self, start: u32, end: u64) -> Self {
+        let start = (start as u64).clamp(self.address as u64, self.end());
+        let end = end.clamp(start, self.end());
+        let offset = (start - self.address as u64) as usize;
+        Self {
+            address: start as u32,
+            data: &self.data[offset..(offset + (end - start) as usize)],
+        }
+    }
+
+    /// Returns a [`Stream`] over the region.
+    pub fn stream(&self) -> RegionStream<'a> {
+        RegionStream {
+            address: self.address,
+            buffer: self.data.iter().copied().into(),
+        }
+    }
+
+    /// Decodes the entire region in to operations tagged with their
+    /// addresses.
+    ///
+    /// Errors are reported in the same way as for [`ASM`](crate::ASM).
+    pub fn decode(&self) -> Result<Vec<AddressedOperation>, ParseError> {
+        let mut stream = self.stream();
+        let mut statements = Vec::new();
+        while let Some(_halfword) = stream.peek::<1>() as Option<u16> {
+            let address = stream.address();
+            match Operation::decode(&mut stream, Policy::Permissive) {
+                Ok(decoded) => statements.push((address, decoded)),
+                Err(e) => {
+                    return Err(ParseError::partially_parsed(
+                        e.at(address),
+                        statements.into_iter().map(|el| el.1).collect(),
+                    ))
+                }
+            }
+        }
+        Ok(statements)
+    }
+}
+
+impl Image {
+    /// Creates an empty image.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the data to the image at `address`.
+    ///
+    /// Returns an error if any of the bytes have already been written.
+    pub fn write(&mut self, address: u32, data: &[u8]) -> Result<(), LoaderError> {
+        let end = address as u64 + data.len() as u64;
+        if data.is_empty() {
+            return Ok(());
+        }
+        if end > 1 << 32 {
+            return Err(LoaderError::Malformed(
+                "Data extends past the end of the address space",
+            ));
+        }
+        if let Some((start, previous)) = self.regions.range(..=address).next_back() {
+            if *start as u64 + previous.len() as u64 > address as u64 {
+                return Err(LoaderError::Overlap(address));
+            }
+        }
+        if let Some((start, _)) = self.regions.range(address..).next() {
+            if (*start as u64) < end {
+                return Err(LoaderError::Overlap(*start));
+            }
+        }
+
+        let previous = self
+            .regions
+            .range(..address)
+            .next_back()
+            .filter(|(start, contents)| **start as u64 + contents.len() as u64 == address as u64)
+            .map(|(start, _)| *start);
+        let (start, mut merged) = match previous {
+            Some(start) => (start, self.regions.remove(&start).unwrap_or_default()),
+            None => (address, Vec::new()),
+        };
+        merged.extend_from_slice(data);
+        if end < 1 << 32 {
+            if let Some(mut next) = self.regions.remove(&(end as u32)) {
+                merged.append(&mut next);
+            }
+        }
+        self.regions.insert(start, merged);
+        Ok(())
+    }
+
+    /// Returns the contiguous regions in the image, in increasing address
+    /// order.
+    pub fn regions(&self) -> Vec<Region<'_>> {
+        self.regions
+            .iter()
+            .map(|(address, data)| Region::new(*address, data))
+            .collect()
+    }
+
+    /// Returns the gaps between the regions as `(start, end)` pairs, where
+    /// `end` is exclusive.
+    pub fn gaps(&self) -> Vec<(u64, u64)> {
+        let regions = self.regions();
+        regions
+            .windows(2)
+            .map(|pair| (pair[0].end(), pair[1].address as u64))
+            .collect()
+    }
+
+    /// Returns the start address given in the file, if any.
+    pub const fn entry(&self) -> Option<u32> {
+        self.entry
+    }
+
+    /// Sets the start address of the image.
+    pub fn set_entry(&mut self, entry: u32) {
+        self.entry = Some(entry);
+    }
+}
+
+impl RegionStream<'_> {
+    /// Returns the address of the next element in the stream.
+    pub const fn address(&self) -> u32 {
+        self.address
+    }
+}
+
+macro_rules! delegate {
+    ($($t:ty),*) => {
+        $(
+            impl Peek<$t> for RegionStream<'_> {
+                fn peek<const N: usize>(&mut self) -> Option<$t> {
+                    self.buffer.peek::<N>()
+                }
+            }
+
+            impl Consume<$t> for RegionStream<'_> {
+                fn consume<const N: usize>(&mut self) -> Option<[$t; N]> {
+                    let ret = self.buffer.consume::<N>()?;
+                    self.address = self
+                        .address
+                        .wrapping_add((N * std::mem::size_of::<$t>()) as u32);
+                    Some(ret)
+                }
+            }
+        )*
+    };
+}
+
+delegate!(u8, u16, u32);
+
+impl Stream for RegionStream<'_> {}
+
+#[cfg(test)]
+mod test {
+    use crate::{loader::*, operation::Operation, Parse};
+
+    #[test]
+    fn test_mapping_symbols() {
+        assert_eq!(Mapping::from_symbol("$t"), Some(Mapping::Thumb));
+        assert_eq!(Mapping::from_symbol("$a"), Some(Mapping::Arm));
+        assert_eq!(Mapping::from_symbol("$d.realdata"), Some(Mapping::Data));
+        assert_eq!(Mapping::from_symbol("$dollar"), None);
+        assert_eq!(Mapping::from_symbol("$x"), None);
+        assert_eq!(Mapping::from_symbol("main"), None);
+        assert_eq!(Mapping::from_symbol(""), None);
+    }
+
+    #[test]
+    fn test_stream_address() {
+        // mov.w r0, #1; movs r0, #1
+        let bytes = [0x4f, 0xf0, 0x01, 0x00, 0x01, 0x20];
+        let region = Region::new(0x100, &bytes);
+        let mut stream = region.stream();
+        assert_eq!(stream.address(), 0x100);
+
+        let (size, _) = Operation::parse(&mut stream).unwrap();
+        assert_eq!(size, 32);
+        assert_eq!(stream.address(), 0x104);
+
+        let (size, _) = Operation::parse(&mut stream).unwrap();
+        assert_eq!(size, 16);
+        assert_eq!(stream.address(), 0x106);
+        assert!(Operation::parse(&mut stream).is_err());
+        assert_eq!(stream.address(), 0x106);
+    }
+
+    #[test]
+    fn test_slice() {
+        let bytes = [0, 1, 2, 3, 4, 5];
+        let region = Region::new(0x100, &bytes);
+        assert_eq!(region.slice(0x102, 0x104), Region::new(0x102, &bytes[2..4]));
+        assert_eq!(region.slice(0x0, 0x200), region);
+        assert_eq!(region.slice(0x200, 0x300).data, &[] as &[u8]);
+        assert!(region.contains(0x105));
+        assert!(!region.contains(0x106));
+    }
+
+    #[test]
+    fn test_image() {
+        let mut image = Image::new();
+        image.write(0x10, &[2, 3]).unwrap();
+        image.write(0x0e, &[0, 1]).unwrap();
+        image.write(0x14, &[6]).unwrap();
+        assert_eq!(image.regions(), vec![
+            Region::new(0x0e, &[0, 1, 2, 3]),
+            Region::new(0x14, &[6])
+        ]);
+        assert_eq!(image.gaps(), vec![(0x12, 0x14)]);
+
+        // Fills the gap and merges all of the regions.
+        image.write(0x12, &[4, 5]).unwrap();
+        assert_eq!(image.regions(), vec![Region::new(0x0e, &[
+            0, 1, 2, 3, 4, 5, 6
+        ])]);
+        assert!(image.gaps().is_empty());
+
+        assert!(matches!(
+            image.write(0x0c, &[0; 3]),
+            Err(LoaderError::Overlap(0x0e))
+        ));
+        assert!(matches!(
+            image.write(0x14, &[0]),
+            Err(LoaderError::Overlap(0x14))
+        ));
+        assert!(image.write(0xffff_ffff, &[0, 0]).is_err());
+        image.write(0xffff_ffff, &[0]).unwrap();
+        assert_eq!(image.entry(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let error: Box<dyn std::error::Error> = Box::new(LoaderError::InvalidChecksum(3));
+        assert_eq!(error.to_string(), "invalid checksum on line 3");
+        assert_eq!(
+            LoaderError::UnsupportedMachine(0x3e).to_string(),
+            "unsupported machine 0x3e, expected Arm"
+        );
+        assert_eq!(
+            LoaderError::InvalidHandler(vector_table::Exception::HardFault, 0x100).to_string(),
+            "the handler 0x100 for HardFault does not have the Thumb bit set"
+        );
+        assert_eq!(
+            LoaderError::TooManyInterrupts(500).to_string(),
+            "500 external interrupts, at most 496 are supported"
+        );
+    }
+}
@@ -0,0 +1,719 @@
+//! Parses 32 bit little endian ELF files.
+//!
+//! Only the parts of the file that are needed to locate the code are parsed,
+//! i.e. the section headers, the program headers and the symbol table. The
+//! executable sections are split in to [`Region`]s using the Arm mapping
+//! symbols, such that literal pools and other data in the `.text` section
+//! are not decoded as instructions.
+//!
+//! ## Usage
+//!
+//! ```no_run
+//! use disarmv7::loader::elf::Elf;
+//!
+//! let file = std::fs::read("firmware.elf").unwrap();
+//! let elf = Elf::parse(&file).unwrap();
+//! for region in elf.code() {
+//!     for (address, decoded) in region.decode().unwrap() {
+//!         println!("{address:#010x}: {:?}", decoded.op);
+//!     }
+//! }
+//! ```
+
+use super::{LoaderError, Mapping, Region};
+
+const MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+const CLASS_32: u8 = 1;
+const DATA_LITTLE_ENDIAN: u8 = 1;
+const MACHINE_ARM: u16 = 40;
+
+const HEADER_SIZE: usize = 52;
+const SECTION_HEADER_SIZE: usize = 40;
+const PROGRAM_HEADER_SIZE: usize = 32;
+const SYMBOL_SIZE: usize = 16;
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_NOBITS: u32 = 8;
+const SHF_ALLOC: u32 = 0x2;
+const SHF_EXECINSTR: u32 = 0x4;
+const PT_LOAD: u32 = 1;
+const PF_X: u32 = 0x1;
+
+#[derive(Debug, Clone)]
+/// A parsed ELF file.
+pub struct Elf<'a> {
+    entry: u32,
+    sections: Vec<Section<'a>>,
+    segments: Vec<Segment<'a>>,
+    symbols: Vec<Symbol>,
+    /// The indices of the defined function symbols, sorted by address.
+    functions: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+/// A section in the ELF file.
+pub struct Section<'a> {
+    /// The index of the section in the section header table.
+    pub index: usize,
+    /// The name of the section, such as `.text`.
+    pub name: String,
+    /// The `sh_type` field of the section.
+    pub kind: u32,
+    /// The `sh_flags` field of the section.
+    pub flags: u32,
+    /// The virtual address of the section.
+    pub address: u32,
+    /// The contents of the section, empty for sections that occupy no
+    /// space in the file such as `.bss`.
+    pub data: &'a [u8],
+}
+
+#[derive(Debug, Clone)]
+/// A segment in the ELF file.
+pub struct Segment<'a> {
+    /// The `p_type` field of the segment.
+    pub kind: u32,
+    /// The `p_flags` field of the segment.
+    pub flags: u32,
+    /// The virtual address of the segment.
+    pub address: u32,
+    /// The physical address of the segment, i.e. the load address.
+    pub physical_address: u32,
+    /// The size of the segment in memory, this might be larger than the
+    /// data.
+    pub memory_size: u32,
+    /// The contents of the segment in the file.
+    pub data: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Enumerates the types of symbols.
+pub enum SymbolKind {
+    /// `STT_NOTYPE`, used for the mapping symbols among other things.
+    NoType,
+    /// `STT_OBJECT`, a data object.
+    Object,
+    /// `STT_FUNC`, a function.
+    ///
+    /// The value of Thumb functions has the least significant bit set.
+    Function,
+    /// `STT_SECTION`, a section.
+    Section,
+    /// `STT_FILE`, a source file.
+    File,
+    /// Any other symbol type.
+    Other(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A symbol in the symbol table.
+pub struct Symbol {
+    /// The name of the symbol.
+    pub name: String,
+    /// The value of the symbol, usually an address.
+    pub value: u32,
+    /// The size of the symbol in bytes.
+    pub size: u32,
+    /// The type of the symbol.
+    pub kind: SymbolKind,
+    /// The index of the section that the symbol is defined in.
+    pub section: u16,
+}
+
+fn bytes(data: &[u8], offset: usize, size: usize) -> Result<&[u8], LoaderError> {
+    data.get(offset..offset.checked_add(size).ok_or(LoaderError::Truncated)?)
+        .ok_or(LoaderError::Truncated)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, LoaderError> {
+    let bytes = bytes(data, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, LoaderError> {
+    let bytes = bytes(data, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads the null terminated string at `offset` in the string table.
+fn string(table: &[u8], offset: usize) -> Result<String, LoaderError> {
+    let tail = table.get(offset..).ok_or(LoaderError::Truncated)?;
+    let end = tail
+        .iter()
+        .position(|byte| *byte == 0)
+        .ok_or(LoaderError::Malformed("Unterminated string"))?;
+    Ok(String::from_utf8_lossy(&tail[..end]).into_owned())
+}
+
+impl<'a> Elf<'a> {
+    /// Parses the ELF file.
+    pub fn parse(data: &'a [u8]) -> Result<Self, LoaderError> {
+        let ident = bytes(data, 0, HEADER_SIZE)?;
+        if ident[0..4] != MAGIC {
+            return Err(LoaderError::InvalidMagic);
+        }
+        if ident[4] != CLASS_32 {
+            return Err(LoaderError::UnsupportedClass(ident[4]));
+        }
+        if ident[5] != DATA_LITTLE_ENDIAN {
+            return Err(LoaderError::UnsupportedEncoding(ident[5]));
+        }
+        let machine = u16_at(data, 0x12)?;
+        if machine != MACHINE_ARM {
+            return Err(LoaderError::UnsupportedMachine(machine));
+        }
+        let entry = u32_at(data, 0x18)?;
+        let program_offset = u32_at(data, 0x1c)? as usize;
+        let section_offset = u32_at(data, 0x20)? as usize;
+        let program_entry_size = u16_at(data, 0x2a)? as usize;
+        let program_count = u16_at(data, 0x2c)? as usize;
+        let section_entry_size = u16_at(data, 0x2e)? as usize;
+        let section_count = u16_at(data, 0x30)? as usize;
+        let names_index = u16_at(data, 0x32)? as usize;
+
+        if program_count != 0 && program_entry_size < PROGRAM_HEADER_SIZE {
+            return Err(LoaderError::Malformed(
+                "Program header entries are too small",
+            ));
+        }
+        if section_count != 0 && section_entry_size < SECTION_HEADER_SIZE {
+            return Err(LoaderError::Malformed(
+                "Section header entries are too small",
+            ));
+        }
+
+        let mut segments = Vec::with_capacity(program_count);
+        for index in 0..program_count {
+            let header = bytes(
+                data,
+                program_offset + index * program_entry_size,
+                PROGRAM_HEADER_SIZE,
+            )?;
+            let offset = u32_at(header, 4)? as usize;
+            let file_size = u32_at(header, 16)? as usize;
+            segments.push(Segment {
+                kind: u32_at(header, 0)?,
+                address: u32_at(header, 8)?,
+                physical_address: u32_at(header, 12)?,
+                memory_size: u32_at(header, 20)?,
+                flags: u32_at(header, 24)?,
+                data: bytes(data, offset, file_size)?,
+            });
+        }
+
+        // The names are resolved once the string table is known.
+        let mut headers = Vec::with_capacity(section_count);
+        for index in 0..section_count {
+            let header = bytes(
+                data,
+                section_offset + index * section_entry_size,
+                SECTION_HEADER_SIZE,
+            )?;
+            let kind = u32_at(header, 4)?;
+            let offset = u32_at(header, 16)? as usize;
+            let size = u32_at(header, 20)? as usize;
+            let section = Section {
+                index,
+                name: String::new(),
+                kind,
+                flags: u32_at(header, 8)?,
+                address: u32_at(header, 12)?,
+                data: match kind {
+                    SHT_NOBITS | 0 => &[],
+                    _ => bytes(data, offset, size)?,
+                },
+            };
+            headers.push((
+                u32_at(header, 0)? as usize,
+                u32_at(header, 24)? as usize,
+                section,
+            ));
+        }
+        let names = headers
+            .get(names_index)
+            .map(|(_, _, section)| section.data)
+            .unwrap_or(&[]);
+        let mut sections = Vec::with_capacity(section_count);
+        let mut symbols = Vec::new();
+        for (name, link, mut section) in headers.iter().cloned() {
+            if names_index != 0 {
+                section.name = string(names, name)?;
+            }
+            if section.kind == SHT_SYMTAB {
+                let strings = headers
+                    .get(link)
+                    .map(|(_, _, section)| section.data)
+                    .ok_or(LoaderError::Malformed("Symbol table without string table"))?;
+                for entry in section.data.chunks_exact(SYMBOL_SIZE) {
+                    symbols.push(Symbol {
+                        name: string(strings, u32_at(entry, 0)? as usize)?,
+                        value: u32_at(entry, 4)?,
+                        size: u32_at(entry, 8)?,
+                        kind: SymbolKind::from(entry[12] & 0xf),
+                        section: u16_at(entry, 14)?,
+                    });
+                }
+            }
+            sections.push(section);
+        }
+
+        let mut functions: Vec<usize> = symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, symbol)| symbol.is_function())
+            .map(|(index, _)| index)
+            .collect();
+        functions.sort_by_key(|index| symbols[*index].value & !1);
+
+        Ok(Self {
+            entry,
+            sections,
+            segments,
+            symbols,
+            functions,
+        })
+    }
+
+    /// Returns the entry point of the program.
+    ///
+    /// For Thumb code the least significant bit is set.
+    pub const fn entry(&self) -> u32 {
+        self.entry
+    }
+
+    /// Returns all of the sections in the file.
+    pub fn sections(&self) -> &[Section<'a>] {
+        &self.sections
+    }
+
+    /// Returns the section with the given name, if any.
+    pub fn section(&self, name: &str) -> Option<&Section<'a>> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns all of the segments in the file.
+    pub fn segments(&self) -> &[Segment<'a>] {
+        &self.segments
+    }
+
+    /// Returns all of the symbols in the symbol table.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Returns the function symbols that are defined in a section, sorted by
+    /// address.
+    pub fn functions(&self) -> impl DoubleEndedIterator<Item = &Symbol> + '_ {
+        self.functions.iter().map(|index| &self.symbols[*index])
+    }
+
+    /// Returns the function symbol with the highest address that is less
+    /// than or equal to `address`, the Thumb bits are ignored.
+    ///
+    /// If several functions start at the same address the last one in the
+    /// symbol table is returned.
+    pub fn function_before(&self, address: u32) -> Option<&Symbol> {
+        let count = self
+            .functions
+            .partition_point(|index| self.symbols[*index].value & !1 <= address);
+        Some(&self.symbols[*self.functions[..count].last()?])
+    }
+
+    /// Returns the mapping symbols in the address range of the region,
+    /// sorted by address.
+    ///
+    /// If `section` is given only the symbols defined in that section are
+    /// returned.
+    fn mappings(&self, region: &Region, section: Option<usize>) -> Vec<(u32, Mapping)> {
+        let mut mappings: Vec<(u32, Mapping)> = self
+            .symbols
+            .iter()
+            .filter(|symbol| section.is_none_or(|index| symbol.section as usize == index))
+            .filter_map(|symbol| Some((symbol.value, symbol.mapping()?)))
+            .filter(|(address, _)| region.contains(*address))
+            .collect();
+        mappings.sort();
+        mappings
+    }
+
+    /// Returns the regions that contain Thumb code.
+    ///
+    /// The regions are taken from the executable sections, or from the
+    /// executable `PT_LOAD` segments if there are no section headers. Parts
+    /// that are marked as data or Arm code by the mapping symbols are
+    /// excluded, code that is not preceded by any mapping symbol is assumed
+    /// to be Thumb code.
+    pub fn code(&self) -> Vec<Region<'a>> {
+        let sections: Vec<(Region<'a>, Option<usize>)> = self
+            .sections
+            .iter()
+            .filter(|section| section.is_executable())
+            .map(|section| (section.region(), Some(section.index)))
+            .collect();
+        let regions = match sections.is_empty() {
+            false => sections,
+            true => self
+                .segments
+                .iter()
+                .filter(|segment| segment.is_load() && segment.is_executable())
+                .map(|segment| (segment.region(), None))
+                .collect(),
+        };
+
+        let mut code = Vec::new();
+        for (region, section) in regions {
+            let mut start = region.address;
+            let mut mapping = Mapping::Thumb;
+            for (address, next) in self.mappings(&region, section) {
+                if mapping == Mapping::Thumb {
+                    code.push(region.slice(start, address as u64));
+                }
+                start = address;
+                mapping = next;
+            }
+            if mapping == Mapping::Thumb {
+                code.push(region.slice(start, region.end()));
+            }
+        }
+        code.retain(|region| !region.data.is_empty());
+        code
+    }
+}
+
+impl<'a> Section<'a> {
+    /// Returns true if the section is loaded and contains instructions.
+    pub const fn is_executable(&self) -> bool {
+        self.flags & (SHF_ALLOC | SHF_EXECINSTR) == SHF_ALLOC | SHF_EXECINSTR
+            && self.kind != SHT_NOBITS
+    }
+
+    /// Returns true if the section occupies memory when the program runs.
+    pub const fn is_allocated(&self) -> bool {
+        self.flags & SHF_ALLOC != 0
+    }
+
+    /// Returns the contents of the section at its virtual address.
+    pub const fn region(&self) -> Region<'a> {
+        Region::new(self.address, self.data)
+    }
+}
+
+impl<'a> Segment<'a> {
+    /// Returns true if the segment is loaded in to memory.
+    pub const fn is_load(&self) -> bool {
+        self.kind == PT_LOAD
+    }
+
+    /// Returns true if the segment is executable.
+    pub const fn is_executable(&self) -> bool {
+        self.flags & PF_X != 0
+    }
+
+    /// Returns the contents of the segment at its virtual address.
+    pub const fn region(&self) -> Region<'a> {
+        Region::new(self.address, self.data)
+    }
+}
+
+impl Symbol {
+    /// Returns true if the symbol is a function that is defined in a
+    /// section.
+    pub fn is_function(&self) -> bool {
+        self.kind == SymbolKind::Function && self.section != 0
+    }
+
+    /// Returns the mapping that the symbol marks, if it is a mapping
+    /// symbol.
+    pub fn mapping(&self) -> Option<Mapping> {
+        match self.kind {
+            SymbolKind::NoType => Mapping::from_symbol(&self.name),
+            _ => None,
+        }
+    }
+}
+
+impl From<u8> for SymbolKind {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::NoType,
+            1 => Self::Object,
+            2 => Self::Function,
+            3 => Self::Section,
+            4 => Self::File,
+            value => Self::Other(value),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use crate::{
+        loader::{elf::*, LoaderError, Region},
+        prelude::*,
+        InstructionSize,
+    };
+
+    /// A symbol in a test image, `(name, value, size, kind)`.
+    pub(crate) type TestSymbol = (&'static str, u32, u32, u8);
+
+    /// Builds an executable with a single `.text` section at `address` and
+    /// the given symbols, all of which are defined in the `.text` section.
+    pub(crate) fn image(address: u32, text: &[u8], symbols: &[TestSymbol]) -> Vec<u8> {
+        let mut strings = vec![0];
+        let mut symtab = vec![0; SYMBOL_SIZE];
+        for (name, value, size, kind) in symbols {
+            symtab.extend((strings.len() as u32).to_le_bytes());
+            symtab.extend(value.to_le_bytes());
+            symtab.extend(size.to_le_bytes());
+            symtab.extend([*kind, 0]);
+            symtab.extend(1u16.to_le_bytes());
+            strings.extend(name.bytes());
+            strings.push(0);
+        }
+        let names = b"\0.text\0.symtab\0.strtab\0.shstrtab\0";
+
+        let text_offset = HEADER_SIZE + PROGRAM_HEADER_SIZE;
+        let symtab_offset = text_offset + text.len();
+        let strings_offset = symtab_offset + symtab.len();
+        let names_offset = strings_offset + strings.len();
+        let sections_offset = names_offset + names.len();
+
+        let mut data = vec![];
+        data.extend(MAGIC);
+        data.extend([CLASS_32, DATA_LITTLE_ENDIAN, 1]);
+        data.resize(16, 0);
+        data.extend(2u16.to_le_bytes());
+        data.extend(MACHINE_ARM.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend((address | 1).to_le_bytes());
+        data.extend((HEADER_SIZE as u32).to_le_bytes());
+        data.extend((sections_offset as u32).to_le_bytes());
+        data.extend(0x0500_0000u32.to_le_bytes());
+        for half in [
+            HEADER_SIZE,
+            PROGRAM_HEADER_SIZE,
+            1,
+            SECTION_HEADER_SIZE,
+            5,
+            4,
+        ] {
+            data.extend((half as u16).to_le_bytes());
+        }
+
+        for word in [
+            PT_LOAD,
+            text_offset as u32,
+            address,
+            address,
+            text.len() as u32,
+            text.len() as u32,
+            PF_X | 0x4,
+            4,
+        ] {
+            data.extend(word.to_le_bytes());
+        }
+
+        data.extend(text);
+        data.extend(&symtab);
+        data.extend(&strings);
+        data.extend(names);
+
+        let headers: [[u32; 10]; 5] = [
+            [0; 10],
+            [
+                1,
+                1,
+                SHF_ALLOC | SHF_EXECINSTR,
+                address,
+                text_offset as u32,
+                text.len() as u32,
+                0,
+                0,
+                4,
+                0,
+            ],
+            [
+                7,
+                SHT_SYMTAB,
+                0,
+                0,
+                symtab_offset as u32,
+                symtab.len() as u32,
+                3,
+                1,
+                4,
+                SYMBOL_SIZE as u32,
+            ],
+            [
+                15,
+                3,
+                0,
+                0,
+                strings_offset as u32,
+                strings.len() as u32,
+                0,
+                0,
+                1,
+                0,
+            ],
+            [
+                23,
+                3,
+                0,
+                0,
+                names_offset as u32,
+                names.len() as u32,
+                0,
+                0,
+                1,
+                0,
+            ],
+        ];
+        for word in headers.iter().flatten() {
+            data.extend(word.to_le_bytes());
+        }
+        data
+    }
+
+    /// A function that loads a literal that does not decode as instructions.
+    const TEXT: [u8; 16] = [
+        0x01, 0x20, // movs r0, #1
+        0x01, 0x49, // ldr r1, [pc, #4]
+        0x70, 0x47, // bx lr
+        0x00, 0xbf, // nop
+        0xff, 0xff, // literal
+        0xff, 0xff, // literal
+        0x01, 0x20, // movs r0, #1
+        0x70, 0x47, // bx lr
+    ];
+
+    const SYMBOLS: [TestSymbol; 4] = [
+        ("$t", 0x8000, 0, 0),
+        ("main", 0x8001, 8, 2),
+        ("$d", 0x8008, 0, 0),
+        ("$t.1", 0x800c, 0, 0),
+    ];
+
+    #[test]
+    fn test_parse() {
+        let data = image(0x8000, &TEXT, &SYMBOLS);
+        let elf = Elf::parse(&data).unwrap();
+        assert_eq!(elf.entry(), 0x8001);
+
+        let text = elf.section(".text").unwrap();
+        assert_eq!(text.index, 1);
+        assert_eq!(text.address, 0x8000);
+        assert_eq!(text.data, &TEXT);
+        assert!(text.is_executable());
+        assert!(!elf.section(".symtab").unwrap().is_executable());
+
+        assert_eq!(elf.segments().len(), 1);
+        assert!(elf.segments()[0].is_load());
+        assert!(elf.segments()[0].is_executable());
+        assert_eq!(elf.segments()[0].region(), text.region());
+
+        // The first symbol is the null symbol.
+        assert_eq!(elf.symbols().len(), 5);
+        let main = &elf.symbols()[2];
+        assert_eq!(main.name, "main");
+        assert_eq!(main.kind, SymbolKind::Function);
+        assert_eq!(main.mapping(), None);
+        assert_eq!(elf.symbols()[4].mapping(), Some(Mapping::Thumb));
+    }
+
+    #[test]
+    fn test_functions() {
+        let data = image(0x8000, &TEXT, &[
+            ("second", 0x800d, 4, 2),
+            ("object", 0x8008, 4, 1),
+            ("main", 0x8001, 8, 2),
+            ("alias", 0x8001, 8, 2),
+        ]);
+        let elf = Elf::parse(&data).unwrap();
+        let names: Vec<&str> = elf.functions().map(|symbol| symbol.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "alias", "second"]);
+
+        assert!(elf.function_before(0x7ffe).is_none());
+        assert_eq!(elf.function_before(0x8000).unwrap().name, "alias");
+        assert_eq!(elf.function_before(0x800a).unwrap().name, "alias");
+        assert_eq!(elf.function_before(0x800c).unwrap().name, "second");
+        assert_eq!(elf.function_before(0xffff_ffff).unwrap().name, "second");
+    }
+
+    #[test]
+    fn test_mapping_symbols_skip_data() {
+        // Decoding the entire section fails on the literal.
+        let data = image(0x8000, &TEXT, &SYMBOLS);
+        let elf = Elf::parse(&data).unwrap();
+        assert!(elf.section(".text").unwrap().region().decode().is_err());
+
+        let code = elf.code();
+        assert_eq!(code, vec![
+            Region::new(0x8000, &TEXT[..8]),
+            Region::new(0x800c, &TEXT[12..])
+        ]);
+        let decoded: Vec<(u32, InstructionSize)> = code
+            .iter()
+            .flat_map(|region| region.decode().unwrap())
+            .map(|(address, decoded)| (address, decoded.size))
+            .collect();
+        assert_eq!(decoded, vec![
+            (0x8000, InstructionSize::Narrow),
+            (0x8002, InstructionSize::Narrow),
+            (0x8004, InstructionSize::Narrow),
+            (0x8006, InstructionSize::Narrow),
+            (0x800c, InstructionSize::Narrow),
+            (0x800e, InstructionSize::Narrow)
+        ]);
+    }
+
+    #[test]
+    fn test_no_mapping_symbols() {
+        let data = image(0x100, &TEXT[..8], &[]);
+        let elf = Elf::parse(&data).unwrap();
+        assert_eq!(elf.code(), vec![Region::new(0x100, &TEXT[..8])]);
+        let decoded = elf.code()[0].decode().unwrap();
+        assert_eq!(decoded[2].0, 0x104);
+        assert!(matches!(decoded[2].1.op, Operation::Bx(_)));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let data = image(0x8000, &TEXT, &SYMBOLS);
+
+        let mut invalid = data.clone();
+        invalid[0] = 0;
+        assert!(matches!(
+            Elf::parse(&invalid),
+            Err(LoaderError::InvalidMagic)
+        ));
+
+        let mut invalid = data.clone();
+        invalid[4] = 2;
+        assert!(matches!(
+            Elf::parse(&invalid),
+            Err(LoaderError::UnsupportedClass(2))
+        ));
+
+        let mut invalid = data.clone();
+        invalid[5] = 2;
+        assert!(matches!(
+            Elf::parse(&invalid),
+            Err(LoaderError::UnsupportedEncoding(2))
+        ));
+
+        let mut invalid = data.clone();
+        invalid[0x12] = 3;
+        assert!(matches!(
+            Elf::parse(&invalid),
+            Err(LoaderError::UnsupportedMachine(3))
+        ));
+
+        for length in [0, 20, HEADER_SIZE + 4, data.len() - 1] {
+            assert!(matches!(
+                Elf::parse(&data[..length]),
+                Err(LoaderError::Truncated)
+            ));
+        }
+    }
+}
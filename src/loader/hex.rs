@@ -0,0 +1,150 @@
+//! Parses Intel HEX files.
+//!
+//! All record types are supported, the extended segment and extended linear
+//! address records set the base address of the following data records and
+//! the start address records set the [`entry`](Image::entry) of the image.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{loader::hex, prelude::*};
+//!
+//! let file = "\
+//! :020000040800F2
+//! :040000000120704724
+//! :00000001FF
+//! ";
+//! let image = hex::parse(file).unwrap();
+//! let regions = image.regions();
+//! assert_eq!(regions[0].address, 0x0800_0000);
+//!
+//! let decoded = regions[0].decode().unwrap();
+//! assert_eq!(decoded[1].0, 0x0800_0002);
+//! assert!(matches!(decoded[1].1.op, Operation::Bx(_)));
+//! ```
+
+use super::{bytes_from_hex, Image, LoaderError};
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Parses the Intel HEX file in to an [`Image`].
+///
+/// The checksum of every record is validated and the file must be
+/// terminated by an end of file record.
+pub fn parse(text: &str) -> Result<Image, LoaderError> {
+    let mut image = Image::new();
+    let mut base = 0u32;
+    let mut lines = 0;
+    for (index, record) in text.lines().enumerate() {
+        let line = index + 1;
+        lines = line;
+        let record = record.trim();
+        if record.is_empty() {
+            continue;
+        }
+        let Some(record) = record.strip_prefix(':') else {
+            return Err(LoaderError::InvalidRecord(line, "Expected a start code"));
+        };
+        let bytes = bytes_from_hex(record, line)?;
+        if bytes.len() < 5 || bytes.len() != bytes[0] as usize + 5 {
+            return Err(LoaderError::InvalidRecord(line, "Invalid byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(LoaderError::InvalidChecksum(line));
+        }
+
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as u32;
+        let data = &bytes[4..bytes.len() - 1];
+        let value = |size: usize| match data.len() == size {
+            true => Ok(data
+                .iter()
+                .fold(0u32, |acc, byte| (acc << 8) | *byte as u32)),
+            false => Err(LoaderError::InvalidRecord(line, "Invalid record length")),
+        };
+        match bytes[3] {
+            DATA => image.write(base.wrapping_add(offset), data)?,
+            END_OF_FILE => return Ok(image),
+            EXTENDED_SEGMENT_ADDRESS => base = value(2)? << 4,
+            START_SEGMENT_ADDRESS => {
+                let address = value(4)?;
+                image.set_entry(((address >> 16) << 4).wrapping_add(address & 0xffff));
+            }
+            EXTENDED_LINEAR_ADDRESS => base = value(2)? << 16,
+            START_LINEAR_ADDRESS => image.set_entry(value(4)?),
+            _ => return Err(LoaderError::InvalidRecord(line, "Unknown record type")),
+        }
+    }
+    Err(LoaderError::InvalidRecord(
+        lines,
+        "Missing end of file record",
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::loader::{hex, LoaderError, Region};
+
+    #[test]
+    fn test_parse() {
+        let file = "\
+:020000040800F2\r
+:100000000000022009010008090100080901000898\r
+:040010000120704714\r
+:04002000704770476E\r
+:0400000508000101ED\r
+:00000001FF\r
+";
+        let image = hex::parse(file).unwrap();
+        let regions = image.regions();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].address, 0x0800_0000);
+        assert_eq!(regions[0].data.len(), 0x14);
+        assert_eq!(&regions[0].data[0x10..], &[0x01, 0x20, 0x70, 0x47]);
+        assert_eq!(
+            regions[1],
+            Region::new(0x0800_0020, &[0x70, 0x47, 0x70, 0x47])
+        );
+        assert_eq!(image.gaps(), vec![(0x0800_0014, 0x0800_0020)]);
+        assert_eq!(image.entry(), Some(0x0800_0101));
+
+        let decoded = regions[1].decode().unwrap();
+        assert_eq!(decoded[1].0, 0x0800_0022);
+    }
+
+    #[test]
+    fn test_segment_address() {
+        let file = ":020000021000EC\n:0200080070473F\n:0400000312340008AB\n:00000001FF\n";
+        let image = hex::parse(file).unwrap();
+        assert_eq!(image.regions()[0].address, 0x1_0008);
+        assert_eq!(image.entry(), Some(0x1_2348));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            hex::parse(":040010000120704715\n:00000001FF\n"),
+            Err(LoaderError::InvalidChecksum(1))
+        ));
+        assert!(matches!(
+            hex::parse("\n:040010000120704714\n"),
+            Err(LoaderError::InvalidRecord(2, _))
+        ));
+        assert!(matches!(
+            hex::parse("040010000120704714\n:00000001FF\n"),
+            Err(LoaderError::InvalidRecord(1, _))
+        ));
+        assert!(matches!(
+            hex::parse(":0500100001207047E4\n:00000001FF\n"),
+            Err(LoaderError::InvalidRecord(1, _))
+        ));
+        assert!(matches!(
+            hex::parse(":040010000120704714\n:02001200704735\n:00000001FF\n"),
+            Err(LoaderError::Overlap(0x12))
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+//! Parses Motorola S-record files, such as `.s19`, `.s28` and `.s37` files.
+//!
+//! The header record is ignored, the data records are written to the image,
+//! the count records are validated against the number of data records and
+//! the termination records set the [`entry`](Image::entry) of the image.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{loader::srec, prelude::*};
+//!
+//! let file = "\
+//! S309080000000120704716
+//! S70508000001F1
+//! ";
+//! let image = srec::parse(file).unwrap();
+//! assert_eq!(image.entry(), Some(0x0800_0001));
+//!
+//! let decoded = image.regions()[0].decode().unwrap();
+//! assert_eq!(decoded[1].0, 0x0800_0002);
+//! assert!(matches!(decoded[1].1.op, Operation::Bx(_)));
+//! ```
+
+use super::{bytes_from_hex, Image, LoaderError};
+
+/// Parses the S-record file in to an [`Image`].
+///
+/// The checksum of every record is validated.
+pub fn parse(text: &str) -> Result<Image, LoaderError> {
+    let mut image = Image::new();
+    let mut count = 0u32;
+    for (index, record) in text.lines().enumerate() {
+        let line = index + 1;
+        let record = record.trim();
+        if record.is_empty() {
+            continue;
+        }
+        let Some((kind, record)) = record
+            .strip_prefix('S')
+            .and_then(|record| record.split_at_checked(1))
+        else {
+            return Err(LoaderError::InvalidRecord(line, "Expected a start code"));
+        };
+        let Ok(kind) = kind.parse::<u8>() else {
+            return Err(LoaderError::InvalidRecord(line, "Unknown record type"));
+        };
+        let bytes = bytes_from_hex(record, line)?;
+        if bytes.len() < 2 || bytes.len() != bytes[0] as usize + 1 {
+            return Err(LoaderError::InvalidRecord(line, "Invalid byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+            return Err(LoaderError::InvalidChecksum(line));
+        }
+
+        let address_size = match kind {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            3 | 7 => 4,
+            _ => return Err(LoaderError::InvalidRecord(line, "Unknown record type")),
+        };
+        let Some((address, data)) = bytes[1..bytes.len() - 1].split_at_checked(address_size) else {
+            return Err(LoaderError::InvalidRecord(line, "Invalid byte count"));
+        };
+        let address = address
+            .iter()
+            .fold(0u32, |acc, byte| (acc << 8) | *byte as u32);
+        match kind {
+            0 => {}
+            1..=3 => {
+                image.write(address, data)?;
+                count += 1;
+            }
+            5 | 6 => {
+                if address != count {
+                    return Err(LoaderError::InvalidRecord(
+                        line,
+                        "Record count does not match the number of data records",
+                    ));
+                }
+            }
+            _ => image.set_entry(address),
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::loader::{srec, LoaderError, Region};
+
+    #[test]
+    fn test_parse() {
+        let file = "\
+S00700007465737438
+S309080000000120704716
+S30708000004704735
+
+S30708000010704729
+S5030003F9
+S70508000001F1
+";
+        let image = srec::parse(file).unwrap();
+        let regions = image.regions();
+        assert_eq!(regions, vec![
+            Region::new(0x0800_0000, &[0x01, 0x20, 0x70, 0x47, 0x70, 0x47]),
+            Region::new(0x0800_0010, &[0x70, 0x47])
+        ]);
+        assert_eq!(image.gaps(), vec![(0x0800_0006, 0x0800_0010)]);
+        assert_eq!(image.entry(), Some(0x0800_0001));
+
+        let decoded: Vec<u32> = regions
+            .iter()
+            .flat_map(|region| region.decode().unwrap())
+            .map(|(address, _)| address)
+            .collect();
+        assert_eq!(decoded, vec![
+            0x0800_0000,
+            0x0800_0002,
+            0x0800_0004,
+            0x0800_0010
+        ]);
+    }
+
+    #[test]
+    fn test_address_sizes() {
+        let image = srec::parse("S10710000120704710\nS206010000704741\nS9031001EB\n").unwrap();
+        assert_eq!(image.regions()[0].address, 0x1000);
+        assert_eq!(image.regions()[1].address, 0x01_0000);
+        assert_eq!(image.entry(), Some(0x1001));
+
+        let image = srec::parse("S804010001F9").unwrap();
+        assert_eq!(image.entry(), Some(0x01_0001));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(matches!(
+            srec::parse("S10710000120704711"),
+            Err(LoaderError::InvalidChecksum(1))
+        ));
+        assert!(matches!(
+            srec::parse("S10710000120704710\nS5030002FA"),
+            Err(LoaderError::InvalidRecord(2, _))
+        ));
+        assert!(matches!(
+            srec::parse(":10710000120704710"),
+            Err(LoaderError::InvalidRecord(1, _))
+        ));
+        assert!(matches!(
+            srec::parse("S40710000120704710"),
+            Err(LoaderError::InvalidRecord(1, _))
+        ));
+        assert!(matches!(
+            srec::parse("S10810000120704710"),
+            Err(LoaderError::InvalidRecord(1, _))
+        ));
+        assert!(matches!(
+            srec::parse("S10710000120704710\nS10710000120704710"),
+            Err(LoaderError::Overlap(0x1000))
+        ));
+    }
+}
@@ -0,0 +1,296 @@
+//! Parses the Armv7-m exception vector table.
+//!
+//! The vector table starts with the initial value of the main stack pointer
+//! followed by the addresses of the exception handlers, indexed by the
+//! exception number. The handlers are the entry points of the program, the
+//! reset handler being the first code that runs.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::loader::{
+//!     vector_table::{Exception, VectorTable},
+//!     Region,
+//! };
+//!
+//! let mut table = vec![];
+//! for entry in [0x2000_8000u32, 0x0800_0101, 0x0800_0201] {
+//!     table.extend(entry.to_le_bytes());
+//! }
+//! table.resize(16 * 4, 0);
+//!
+//! let table = VectorTable::parse(&Region::new(0x0800_0000, &table), 0).unwrap();
+//! assert_eq!(table.initial_sp, 0x2000_8000);
+//! assert_eq!(table.reset().unwrap().address, 0x0800_0100);
+//! assert_eq!(table.handlers[1].exception, Exception::Nmi);
+//! ```
+
+use super::{LoaderError, Region};
+
+/// The number of entries in the vector table before the external
+/// interrupts, including the initial stack pointer.
+const SYSTEM_ENTRIES: usize = 16;
+
+/// The maximum number of external interrupts supported by Armv7-m.
+pub const MAX_IRQS: usize = 496;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Enumerates the exceptions in the vector table.
+pub enum Exception {
+    /// Reset, exception number 1.
+    Reset,
+    /// Non maskable interrupt, exception number 2.
+    Nmi,
+    /// Hard fault, exception number 3.
+    HardFault,
+    /// Memory management fault, exception number 4.
+    MemManage,
+    /// Bus fault, exception number 5.
+    BusFault,
+    /// Usage fault, exception number 6.
+    UsageFault,
+    /// Supervisor call, exception number 11.
+    SvCall,
+    /// Debug monitor, exception number 12.
+    DebugMonitor,
+    /// Pendable service request, exception number 14.
+    PendSv,
+    /// System tick timer, exception number 15.
+    SysTick,
+    /// External interrupt `n`, exception number `16 + n`.
+    Irq(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An entry in the vector table.
+pub struct ExceptionHandler {
+    /// The exception that the handler handles.
+    pub exception: Exception,
+    /// The address of the first instruction in the handler, i.e. the entry
+    /// without the Thumb bit.
+    pub address: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A parsed vector table.
+pub struct VectorTable {
+    /// The initial value of the main stack pointer.
+    pub initial_sp: u32,
+    /// The handlers in the table, in exception number order.
+    ///
+    /// Entries that are zero, i.e. unused, are not included.
+    pub handlers: Vec<ExceptionHandler>,
+}
+
+impl Exception {
+    /// Returns the exception number, this is also the index of the
+    /// exception in the vector table.
+    pub const fn number(&self) -> u32 {
+        match self {
+            Self::Reset => 1,
+            Self::Nmi => 2,
+            Self::HardFault => 3,
+            Self::MemManage => 4,
+            Self::BusFault => 5,
+            Self::UsageFault => 6,
+            Self::SvCall => 11,
+            Self::DebugMonitor => 12,
+            Self::PendSv => 14,
+            Self::SysTick => 15,
+            Self::Irq(irq) => 16 + *irq as u32,
+        }
+    }
+}
+
+impl TryFrom<u32> for Exception {
+    type Error = LoaderError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => Self::Reset,
+            2 => Self::Nmi,
+            3 => Self::HardFault,
+            4 => Self::MemManage,
+            5 => Self::BusFault,
+            6 => Self::UsageFault,
+            11 => Self::SvCall,
+            12 => Self::DebugMonitor,
+            14 => Self::PendSv,
+            15 => Self::SysTick,
+            16..=511 => Self::Irq((value - 16) as u16),
+            _ => return Err(LoaderError::Malformed("Reserved exception number")),
+        })
+    }
+}
+
+impl VectorTable {
+    /// Parses a vector table with `irqs` external interrupts that is located
+    /// at the start of the region.
+    ///
+    /// Returns an error if a handler does not have the Thumb bit set or if
+    /// there are more than [`MAX_IRQS`] external interrupts.
+    pub fn parse(region: &Region, irqs: usize) -> Result<Self, LoaderError> {
+        if irqs > MAX_IRQS {
+            return Err(LoaderError::TooManyInterrupts(irqs));
+        }
+        let entries = SYSTEM_ENTRIES + irqs;
+        let words: Vec<u32> = region
+            .data
+            .get(..entries * 4)
+            .ok_or(LoaderError::Truncated)?
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+
+        let mut handlers = Vec::new();
+        for (number, entry) in words.iter().enumerate().skip(1) {
+            let exception = match Exception::try_from(number as u32) {
+                Ok(exception) => exception,
+                // Reserved entries are ignored.
+                Err(_) if number < SYSTEM_ENTRIES => continue,
+                Err(error) => return Err(error),
+            };
+            match entry {
+                0 => {}
+                entry if entry & 1 == 0 => {
+                    return Err(LoaderError::InvalidHandler(exception, *entry))
+                }
+                entry => handlers.push(ExceptionHandler {
+                    exception,
+                    address: entry & !1,
+                }),
+            }
+        }
+        Ok(Self {
+            initial_sp: words[0],
+            handlers,
+        })
+    }
+
+    /// Returns the handler for the exception, if any.
+    pub fn handler(&self, exception: Exception) -> Option<&ExceptionHandler> {
+        self.handlers
+            .iter()
+            .find(|handler| handler.exception == exception)
+    }
+
+    /// Returns the reset handler, the entry point of the program.
+    pub fn reset(&self) -> Option<&ExceptionHandler> {
+        self.handler(Exception::Reset)
+    }
+
+    /// Returns the distinct handler addresses in increasing order, these are
+    /// the entry points of the program.
+    pub fn entry_points(&self) -> Vec<u32> {
+        let mut entry_points: Vec<u32> = self
+            .handlers
+            .iter()
+            .map(|handler| handler.address)
+            .collect();
+        entry_points.sort();
+        entry_points.dedup();
+        entry_points
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::loader::{
+        vector_table::{Exception, ExceptionHandler, VectorTable, MAX_IRQS},
+        LoaderError,
+        Region,
+    };
+
+    fn table(entries: &[u32]) -> Vec<u8> {
+        entries
+            .iter()
+            .flat_map(|entry| entry.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let default = 0x0800_0301;
+        let mut entries = vec![0x2000_8000, 0x0800_0101, default, 0x0800_0201];
+        entries.extend([default; 3]);
+        entries.extend([0x1234_5678; 4]);
+        entries.extend([default, 0, 0x1234_5678, default, 0x0800_0401]);
+        entries.extend([0, 0x0800_0501]);
+        let data = table(&entries);
+
+        let table = VectorTable::parse(&Region::new(0x0800_0000, &data), 2).unwrap();
+        assert_eq!(table.initial_sp, 0x2000_8000);
+        assert_eq!(table.reset().unwrap().address, 0x0800_0100);
+        assert_eq!(
+            table.handler(Exception::HardFault).unwrap().address,
+            0x0800_0200
+        );
+        assert_eq!(table.handler(Exception::DebugMonitor), None);
+        assert_eq!(table.handler(Exception::Irq(0)), None);
+        assert_eq!(table.handlers.len(), 10);
+        assert_eq!(table.handlers[9], ExceptionHandler {
+            exception: Exception::Irq(1),
+            address: 0x0800_0500
+        });
+        assert_eq!(table.entry_points(), vec![
+            0x0800_0100,
+            0x0800_0200,
+            0x0800_0300,
+            0x0800_0400,
+            0x0800_0500
+        ]);
+
+        // The external interrupts are not part of the table.
+        let table = VectorTable::parse(&Region::new(0x0800_0000, &data), 0).unwrap();
+        assert_eq!(table.handlers.len(), 9);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut entries = vec![0x2000_8000, 0x0800_0100];
+        entries.resize(16, 0);
+        let data = table(&entries);
+        assert!(matches!(
+            VectorTable::parse(&Region::new(0, &data), 0),
+            Err(LoaderError::InvalidHandler(Exception::Reset, 0x0800_0100))
+        ));
+        assert!(matches!(
+            VectorTable::parse(&Region::new(0, &data), 1),
+            Err(LoaderError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn test_max_irqs() {
+        let mut entries = vec![0x2000_8000, 0x0800_0101];
+        entries.resize(16 + MAX_IRQS + 1, 0);
+        entries[16 + MAX_IRQS - 1] = 0x0800_0201;
+        let data = table(&entries);
+
+        let table = VectorTable::parse(&Region::new(0, &data), MAX_IRQS).unwrap();
+        assert_eq!(table.handlers.len(), 2);
+        assert_eq!(table.handlers[1], ExceptionHandler {
+            exception: Exception::Irq(495),
+            address: 0x0800_0200
+        });
+        assert_eq!(table.handlers[1].exception.number(), 511);
+
+        assert!(matches!(
+            VectorTable::parse(&Region::new(0, &data), MAX_IRQS + 1),
+            Err(LoaderError::TooManyInterrupts(497))
+        ));
+    }
+
+    #[test]
+    fn test_exception_numbers() {
+        for number in 1..64 {
+            if let Ok(exception) = Exception::try_from(number) {
+                assert_eq!(exception.number(), number);
+            }
+        }
+        assert!(Exception::try_from(0).is_err());
+        assert!(Exception::try_from(7).is_err());
+        assert!(Exception::try_from(13).is_err());
+        assert_eq!(Exception::try_from(20).unwrap(), Exception::Irq(4));
+    }
+}
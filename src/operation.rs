@@ -4,11 +4,13 @@
 use builder_derive::{Builder, Consumer};
 
 use crate::arch::{
+    barrier::BarrierOption,
     condition::{Condition, ITCondition},
     coproc::CoProcessor,
+    hint::Hint,
     register::{Register, RegisterList},
     shift::ImmShift,
-    wrapper_types::*,
+    special_register::{ApsrMask, SpecialRegister},
     SetFlags,
 };
 
@@ -131,9 +133,9 @@ operation!(
 
     Dbg <option:u8>
 
-    Dmb {option: u8}
+    Dmb {option: BarrierOption}
 
-    Dsb {option: u8}
+    Dsb {option: BarrierOption}
 
     // ==================================== D ====================================
 
@@ -143,7 +145,7 @@ operation!(
 
     // ==================================== I ====================================
 
-    Isb {option: Imm4}
+    Isb {option: BarrierOption}
 
     It <conds: ITCondition>/* , <mask: Imm4> */
 
@@ -234,9 +236,9 @@ operation!(
     Mrrc <coproc: CoProcessor>, <opc1: u8>, <rt:Register>, <rt2: Register>, <crm: u8>
     Mrc  <coproc: CoProcessor>, <opc1: u8>, {opc2: u8}, <rt:Register>, <crm: u8>, <crn: u8>
 
-    Mrs <rd: Register>, <sysm: u8>
+    Mrs <rd: Register>, <sysm: SpecialRegister>
 
-    Msr <rn: Register>, <mask:Imm2>, <sysm:u8>
+    Msr <rn: Register>, <mask: ApsrMask>, <sysm: SpecialRegister>
 
     Mul {s: SetFlags}, {rd: Register}, <rn: Register>, <rm: Register>
 
@@ -440,6 +442,9 @@ operation!(
     Umlal       <rdlo: Register>, <rdhi: Register>, <rn: Register>, <rm: Register>
     Umull       <rdlo: Register>, <rdhi: Register>, <rn: Register>, <rm: Register>
 
+    /// A hint that is not allocated in ARMv7-M, it executes as a [`Nop`].
+    UnallocatedHint <hint: u8>
+
     Uqadd16     {rd: Register}, <rn: Register>, <rm: Register>
     Uqadd8      {rd: Register}, <rn: Register>, <rm: Register>
     Uqasx       {rd: Register}, <rn: Register>, <rm: Register>
@@ -478,3 +483,28 @@ operation!(
 
     Yield <>
 );
+
+impl From<Hint> for Operation {
+    fn from(value: Hint) -> Self {
+        match value {
+            Hint::Nop => Nop::builder().complete().into(),
+            Hint::Yield => Yield::builder().complete().into(),
+            Hint::Wfe => Wfe::builder().complete().into(),
+            Hint::Wfi => Wfi::builder().complete().into(),
+            Hint::Sev => Sev::builder().complete().into(),
+            Hint::Dbg(option) => Dbg::builder().set_option(option).complete().into(),
+            Hint::Unallocated(hint) => UnallocatedHint::builder().set_hint(hint).complete().into(),
+        }
+    }
+}
+
+impl Operation {
+    /// Returns the target of a direct branch located at `address`.
+    ///
+    /// This is [`B`], [`Bl`] and [`Cbz`], all other operations, including
+    /// branches to a register, return None. The target is the one given by
+    /// [`control_flow`](Self::control_flow).
+    pub fn branch_target(&self, address: u32) -> Option<u32> {
+        self.control_flow(address).target()
+    }
+}
@@ -0,0 +1,1100 @@
+//! Encodes the semantics of [`Operation`]s as SMT-LIB2 bit-vector formulas.
+//!
+//! The encoding is built on top of the [`lift`](crate::lift) module. Each
+//! instruction is a transition from one [`State`] to the next, where a state
+//! consists of
+//!
+//! - the registers `R0` to `R15` as `(_ BitVec 32)`,
+//! - the flags `N`, `Z`, `C`, `V`, `Q` and `GE[3:0]` as `(_ BitVec 1)` and
+//! - the memory as an `(Array (_ BitVec 32) (_ BitVec 8))`.
+//!
+//! `R15` holds the address of the next instruction to execute, reading the
+//! PC from an instruction yields this address plus four as usual.
+//!
+//! The pre-state is declared by [`declarations`] and is shared between all
+//! [`Encoder`]s, the following states are defined as functions of it. This
+//! allows the post-states of two sequences to be compared, see
+//! [`equivalence`].
+//!
+//! Instructions that can not be expressed in terms of the state, such as
+//! co processor instructions, hints and barriers, do not change the state.
+//! Values read from special registers other than the flags are
+//! unconstrained as is the outcome of exclusive stores. IT blocks are not
+//! modelled, every instruction is encoded as if its condition passed.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{prelude::*, smt, InstructionSize};
+//!
+//! let op: Operation = operation::AddImmediate::builder()
+//!     .set_s(Some(SetFlags::Literal(true)))
+//!     .set_rd(Some(Register::R0))
+//!     .set_rn(Register::R1)
+//!     .set_imm(4)
+//!     .complete()
+//!     .into();
+//!
+//! let script = smt::transition(&[(InstructionSize::Wide, op)]);
+//! assert!(script.contains("(declare-const r1 (_ BitVec 32))"));
+//! assert!(script.contains("(define-fun r0_1 () (_ BitVec 32)"));
+//! ```
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    arch::Register,
+    lift::{
+        ir::{BinaryOp, Expression, Flag, Local, Statement, UnaryOp, Value, Width},
+        Context,
+    },
+    operation::Operation,
+    InstructionSize,
+};
+
+/// All of the flags in the state.
+const FLAGS: [Flag; 9] = [
+    Flag::N,
+    Flag::Z,
+    Flag::C,
+    Flag::V,
+    Flag::Q,
+    Flag::Ge(0),
+    Flag::Ge(1),
+    Flag::Ge(2),
+    Flag::Ge(3),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+/// The names of the variables in a state.
+///
+/// The pre-state is shared between all encoders and has no prefix or
+/// suffix, i.e. `r0`, `n` and `mem`. The following states are named
+/// `<prefix>r0_<version>` and so on.
+pub struct State {
+    prefix: String,
+    version: usize,
+}
+
+#[derive(Debug, Default)]
+/// Encodes a straight line sequence of instructions.
+pub struct Encoder {
+    prefix: String,
+    version: usize,
+    script: String,
+}
+
+/// The values of the state while an instruction is encoded.
+struct Terms {
+    registers: Vec<String>,
+    flags: HashMap<Flag, String>,
+    memory: String,
+}
+
+impl State {
+    /// The shared pre-state.
+    pub fn initial() -> Self {
+        Self {
+            prefix: String::new(),
+            version: 0,
+        }
+    }
+
+    fn name(&self, base: &str) -> String {
+        match self.version {
+            0 => base.to_string(),
+            version => format!("{}{base}_{version}", self.prefix),
+        }
+    }
+
+    /// Returns the name of the register.
+    pub fn register(&self, register: Register) -> String {
+        self.name(&format!("r{}", u8::from(register)))
+    }
+
+    /// Returns the name of the flag.
+    pub fn flag(&self, flag: Flag) -> String {
+        self.name(&match flag {
+            Flag::N => "n".to_string(),
+            Flag::Z => "z".to_string(),
+            Flag::C => "c".to_string(),
+            Flag::V => "v".to_string(),
+            Flag::Q => "q".to_string(),
+            Flag::Ge(index) => format!("ge{index}"),
+        })
+    }
+
+    /// Returns the name of the memory.
+    pub fn memory(&self) -> String {
+        self.name("mem")
+    }
+
+    /// Returns the names of all of the variables in the state.
+    pub fn variables(&self) -> Vec<String> {
+        registers()
+            .map(|register| self.register(register))
+            .chain(FLAGS.iter().map(|flag| self.flag(*flag)))
+            .chain([self.memory()])
+            .collect()
+    }
+}
+
+fn registers() -> impl Iterator<Item = Register> {
+    (0..16u8).map(|register| Register::try_from(register).expect("All registers are valid"))
+}
+
+fn sort(width: Width) -> String {
+    format!("(_ BitVec {})", width.bits())
+}
+
+fn constant(value: u64, width: Width) -> String {
+    format!("(_ bv{} {})", value & width.mask(), width.bits())
+}
+
+fn boolean(term: String) -> String {
+    format!("(ite {term} #b1 #b0)")
+}
+
+/// Concatenates the terms, the first term is placed in the most significant
+/// bits.
+fn concat(terms: Vec<String>) -> String {
+    terms
+        .into_iter()
+        .reduce(|high, low| format!("(concat {high} {low})"))
+        .expect("At least one term is concatenated")
+}
+
+/// Returns the SMT-LIB2 declarations of the pre-state.
+pub fn declarations() -> String {
+    let state = State::initial();
+    let mut script = String::new();
+    for register in registers() {
+        let _ = writeln!(
+            script,
+            "(declare-const {} {})",
+            state.register(register),
+            sort(Width::Word)
+        );
+    }
+    for flag in FLAGS {
+        let _ = writeln!(
+            script,
+            "(declare-const {} {})",
+            state.flag(flag),
+            sort(Width::Bit)
+        );
+    }
+    let _ = writeln!(
+        script,
+        "(declare-const {} (Array (_ BitVec 32) (_ BitVec 8)))",
+        state.memory()
+    );
+    script
+}
+
+/// Returns the [`declarations`] of the pre-state followed by the definitions
+/// of the states after each of the instructions.
+///
+/// The instructions are given as `(size, operation)` pairs.
+pub fn transition(operations: &[(InstructionSize, Operation)]) -> String {
+    let mut encoder = Encoder::new();
+    for (size, operation) in operations {
+        encoder.push(operation, &context(*size));
+    }
+    declarations() + &encoder.finish()
+}
+
+/// Returns a script that is unsatisfiable if and only if the two sequences
+/// always produce the same post-state from the same pre-state.
+///
+/// `R15` is not compared as the sequences may differ in length.
+pub fn equivalence(
+    first: &[(InstructionSize, Operation)],
+    second: &[(InstructionSize, Operation)],
+) -> String {
+    let encode = |prefix: &str, operations: &[(InstructionSize, Operation)]| {
+        let mut encoder = Encoder::with_prefix(prefix);
+        for (size, operation) in operations {
+            encoder.push(operation, &context(*size));
+        }
+        encoder
+    };
+    let (first, second) = (encode("a_", first), encode("b_", second));
+    let (first_post, second_post) = (first.post(), second.post());
+
+    let mut script = declarations() + &first.finish() + &second.finish();
+    let equalities: Vec<String> = first_post
+        .variables()
+        .iter()
+        .zip(second_post.variables())
+        .filter(|(first, _)| **first != first_post.register(Register::PC))
+        .map(|(first, second)| format!("(= {first} {second})"))
+        .collect();
+    let _ = writeln!(script, "(assert (not (and {})))", equalities.join(" "));
+    script.push_str("(check-sat)\n");
+    script
+}
+
+fn context(size: InstructionSize) -> Context {
+    Context {
+        in_it_block: false,
+        size,
+    }
+}
+
+impl Encoder {
+    /// Creates a new encoder that starts from the pre-state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new encoder whose definitions are prefixed with `prefix`.
+    ///
+    /// This allows multiple encoders to be used in the same script.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the shared pre-state.
+    pub fn pre(&self) -> State {
+        State::initial()
+    }
+
+    /// Returns the state after the last encoded instruction.
+    pub fn post(&self) -> State {
+        self.state(self.version)
+    }
+
+    /// Returns the definitions of all of the encoded states.
+    ///
+    /// The definitions refer to the pre-state, see [`declarations`].
+    pub fn finish(self) -> String {
+        self.script
+    }
+
+    fn state(&self, version: usize) -> State {
+        State {
+            prefix: self.prefix.clone(),
+            version,
+        }
+    }
+
+    /// Encodes the transition for the operation.
+    pub fn push(&mut self, operation: &Operation, context: &Context) {
+        let pre = self.post();
+        self.version += 1;
+        let post = self.post();
+        let prefix = format!("{}i{}_", self.prefix, self.version);
+
+        let mut terms = Terms {
+            registers: registers().map(|register| pre.register(register)).collect(),
+            flags: FLAGS.iter().map(|flag| (*flag, pre.flag(*flag))).collect(),
+            memory: pre.memory(),
+        };
+        let address = pre.register(Register::PC);
+        let next = format!(
+            "(bvadd {address} {})",
+            constant(context.size.bytes() as u64, Width::Word)
+        );
+        let mut pc = next.clone();
+
+        let local = |local: &Local| format!("{prefix}l{}", local.id);
+        let value = |value: &Value| match value {
+            Value::Local(value) => local(value),
+            Value::Constant(value, width) => constant(*value, *width),
+        };
+        let mut fresh = 0;
+
+        for statement in operation.lift(context) {
+            match statement {
+                Statement::Assign { dst, expression } => {
+                    let expression = Self::expression(&expression, &value);
+                    self.define(&local(&dst), &sort(dst.width), &expression);
+                }
+                Statement::ReadRegister { dst, register } => {
+                    let term = match register {
+                        Register::PC => {
+                            format!("(bvadd {address} {})", constant(4, Width::Word))
+                        }
+                        register => terms.registers[u8::from(register) as usize].clone(),
+                    };
+                    self.define(&local(&dst), &sort(dst.width), &term);
+                }
+                Statement::WriteRegister { register, value: v } => {
+                    terms.registers[u8::from(register) as usize] = value(&v);
+                }
+                Statement::ReadFlag { dst, flag } => {
+                    let term = terms.flags[&flag].clone();
+                    self.define(&local(&dst), &sort(dst.width), &term);
+                }
+                Statement::WriteFlag { flag, value: v } => {
+                    terms.flags.insert(flag, value(&v));
+                }
+                Statement::Load { dst, address } => {
+                    let address = value(&address);
+                    let bytes: Vec<String> = (0..dst.width.bits() / 8)
+                        .rev()
+                        .map(|byte| {
+                            format!(
+                                "(select {} (bvadd {address} {}))",
+                                terms.memory,
+                                constant(byte as u64, Width::Word)
+                            )
+                        })
+                        .collect();
+                    self.define(&local(&dst), &sort(dst.width), &concat(bytes));
+                }
+                Statement::Store { address, value: v } => {
+                    let memory =
+                        Self::store(&terms.memory, &value(&address), &value(&v), v.width());
+                    fresh += 1;
+                    let name = format!("{prefix}m{fresh}");
+                    self.define(&name, "(Array (_ BitVec 32) (_ BitVec 8))", &memory);
+                    terms.memory = name;
+                }
+                Statement::StoreExclusive {
+                    status,
+                    address,
+                    value: v,
+                } => {
+                    fresh += 1;
+                    let failed = format!("{prefix}x{fresh}");
+                    let _ = writeln!(self.script, "(declare-const {failed} {})", sort(Width::Bit));
+                    self.define(
+                        &local(&status),
+                        &sort(status.width),
+                        &format!("((_ zero_extend {}) {failed})", status.width.bits() - 1),
+                    );
+                    let memory =
+                        Self::store(&terms.memory, &value(&address), &value(&v), v.width());
+                    let memory = format!("(ite (= {failed} #b0) {memory} {})", terms.memory);
+                    let name = format!("{prefix}m{fresh}");
+                    self.define(&name, "(Array (_ BitVec 32) (_ BitVec 8))", &memory);
+                    terms.memory = name;
+                }
+                Statement::ReadSpecial { dst, sysm } => {
+                    fresh += 1;
+                    let unknown = format!("{prefix}x{fresh}");
+                    let _ = writeln!(self.script, "(declare-const {unknown} {})", sort(dst.width));
+                    let sysm = u8::from(sysm);
+                    let term = match sysm {
+                        // The program status registers, the APSR is included if bit 2 is
+                        // cleared and the IPSR if bit 0 is set.
+                        0..=7 => {
+                            let apsr = match sysm & 0b100 {
+                                0 => Self::apsr(&terms),
+                                _ => constant(0, Width::Word),
+                            };
+                            let ipsr = match sysm & 0b1 {
+                                0 => constant(0, Width::Word),
+                                _ => format!("(bvand {unknown} {})", constant(0x1ff, Width::Word)),
+                            };
+                            format!("(bvor {apsr} {ipsr})")
+                        }
+                        _ => unknown,
+                    };
+                    self.define(&local(&dst), &sort(dst.width), &term);
+                }
+                Statement::WriteSpecial {
+                    sysm,
+                    mask,
+                    value: v,
+                } if sysm.has_flags() => {
+                    let v = value(&v);
+                    if mask.nzcvq {
+                        for (flag, bit) in [
+                            (Flag::N, 31),
+                            (Flag::Z, 30),
+                            (Flag::C, 29),
+                            (Flag::V, 28),
+                            (Flag::Q, 27),
+                        ] {
+                            terms
+                                .flags
+                                .insert(flag, format!("((_ extract {bit} {bit}) {v})"));
+                        }
+                    }
+                    if mask.g {
+                        for index in 0..4u8 {
+                            let bit = 16 + index;
+                            terms
+                                .flags
+                                .insert(Flag::Ge(index), format!("((_ extract {bit} {bit}) {v})"));
+                        }
+                    }
+                }
+                Statement::WriteSpecial { .. } | Statement::Intrinsic(_) => {}
+                Statement::Branch { target, .. } => pc = value(&target),
+                Statement::ConditionalBranch { condition, target } => {
+                    pc = format!(
+                        "(ite (= {} #b1) {} {next})",
+                        value(&condition),
+                        value(&target)
+                    )
+                }
+            }
+        }
+        terms.registers[u8::from(Register::PC) as usize] = pc;
+
+        for register in registers() {
+            let term = terms.registers[u8::from(register) as usize].clone();
+            self.define(&post.register(register), &sort(Width::Word), &term);
+        }
+        for flag in FLAGS {
+            let term = terms.flags[&flag].clone();
+            self.define(&post.flag(flag), &sort(Width::Bit), &term);
+        }
+        self.define(
+            &post.memory(),
+            "(Array (_ BitVec 32) (_ BitVec 8))",
+            &terms.memory,
+        );
+    }
+
+    fn define(&mut self, name: &str, sort: &str, term: &str) {
+        let _ = writeln!(self.script, "(define-fun {name} () {sort} {term})");
+    }
+
+    /// Returns the flags in the APSR format.
+    fn apsr(terms: &Terms) -> String {
+        let flag = |flag: Flag| terms.flags[&flag].clone();
+        concat(vec![
+            flag(Flag::N),
+            flag(Flag::Z),
+            flag(Flag::C),
+            flag(Flag::V),
+            flag(Flag::Q),
+            "(_ bv0 7)".to_string(),
+            flag(Flag::Ge(3)),
+            flag(Flag::Ge(2)),
+            flag(Flag::Ge(1)),
+            flag(Flag::Ge(0)),
+            constant(0, Width::Half),
+        ])
+    }
+
+    /// Stores the value in little endian order.
+    fn store(memory: &str, address: &str, value: &str, width: Width) -> String {
+        let mut memory = memory.to_string();
+        for byte in 0..width.bits() / 8 {
+            memory = format!(
+                "(store {memory} (bvadd {address} {}) ((_ extract {} {}) {value}))",
+                constant(byte as u64, Width::Word),
+                byte * 8 + 7,
+                byte * 8
+            );
+        }
+        memory
+    }
+
+    fn expression(expression: &Expression, value: &impl Fn(&Value) -> String) -> String {
+        match expression {
+            Expression::Value(v) => value(v),
+            Expression::Unary(op, v) => match op {
+                UnaryOp::Not => format!("(bvnot {})", value(v)),
+                UnaryOp::Neg => format!("(bvneg {})", value(v)),
+                UnaryOp::Clz => {
+                    let bits = v.width().bits();
+                    let v = value(v);
+                    let mut term = constant(bits as u64, Width::Word);
+                    for bit in 0..bits {
+                        term = format!(
+                            "(ite (= ((_ extract {bit} {bit}) {v}) #b1) {} {term})",
+                            constant((bits - 1 - bit) as u64, Width::Word)
+                        );
+                    }
+                    term
+                }
+            },
+            Expression::Binary(op, lhs, rhs) => {
+                let width = lhs.width();
+                let (lhs, rhs) = (value(lhs), value(rhs));
+                let zero = constant(0, width);
+                match op {
+                    BinaryOp::Add => format!("(bvadd {lhs} {rhs})"),
+                    BinaryOp::Sub => format!("(bvsub {lhs} {rhs})"),
+                    BinaryOp::Mul => format!("(bvmul {lhs} {rhs})"),
+                    BinaryOp::UDiv => format!("(ite (= {rhs} {zero}) {zero} (bvudiv {lhs} {rhs}))"),
+                    BinaryOp::SDiv => format!("(ite (= {rhs} {zero}) {zero} (bvsdiv {lhs} {rhs}))"),
+                    BinaryOp::And => format!("(bvand {lhs} {rhs})"),
+                    BinaryOp::Or => format!("(bvor {lhs} {rhs})"),
+                    BinaryOp::Xor => format!("(bvxor {lhs} {rhs})"),
+                    BinaryOp::Shl => format!("(bvshl {lhs} {rhs})"),
+                    BinaryOp::LShr => format!("(bvlshr {lhs} {rhs})"),
+                    BinaryOp::AShr => format!("(bvashr {lhs} {rhs})"),
+                    BinaryOp::Eq => boolean(format!("(= {lhs} {rhs})")),
+                    BinaryOp::Ne => boolean(format!("(not (= {lhs} {rhs}))")),
+                    BinaryOp::ULt => boolean(format!("(bvult {lhs} {rhs})")),
+                    BinaryOp::ULe => boolean(format!("(bvule {lhs} {rhs})")),
+                    BinaryOp::SLt => boolean(format!("(bvslt {lhs} {rhs})")),
+                    BinaryOp::SLe => boolean(format!("(bvsle {lhs} {rhs})")),
+                }
+            }
+            Expression::Ite(condition, then, otherwise) => format!(
+                "(ite (= {} #b1) {} {})",
+                value(condition),
+                value(then),
+                value(otherwise)
+            ),
+            Expression::Extract {
+                value: v,
+                high,
+                low,
+            } => {
+                format!("((_ extract {high} {low}) {})", value(v))
+            }
+            Expression::ZeroExtend(v, width) => format!(
+                "((_ zero_extend {}) {})",
+                width.bits() - v.width().bits(),
+                value(v)
+            ),
+            Expression::SignExtend(v, width) => format!(
+                "((_ sign_extend {}) {})",
+                width.bits() - v.width().bits(),
+                value(v)
+            ),
+            Expression::Concat(high, low) => format!("(concat {} {})", value(high), value(low)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, io::Write, process::Command};
+
+    use crate::{
+        lift::ir::Flag,
+        prelude::*,
+        smt,
+        InstructionSize::{self, Narrow, Wide},
+        Policy,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Term {
+        Bv(u64, u32),
+        Bool(bool),
+        Array(HashMap<u64, u64>),
+    }
+
+    #[derive(Debug)]
+    enum Sexp {
+        Atom(String),
+        List(Vec<Sexp>),
+    }
+
+    fn parse(script: &str) -> Vec<Sexp> {
+        let spaced = script.replace('(', " ( ").replace(')', " ) ");
+        let mut stack = vec![vec![]];
+        for token in spaced.split_whitespace() {
+            match token {
+                "(" => stack.push(vec![]),
+                ")" => {
+                    let list = stack.pop().expect("Unbalanced parenthesis");
+                    stack
+                        .last_mut()
+                        .expect("Unbalanced parenthesis")
+                        .push(Sexp::List(list));
+                }
+                atom => stack.last_mut().unwrap().push(Sexp::Atom(atom.to_string())),
+            }
+        }
+        assert_eq!(stack.len(), 1, "Unbalanced parenthesis");
+        stack.pop().unwrap()
+    }
+
+    fn mask(width: u32) -> u64 {
+        match width {
+            64 => u64::MAX,
+            width => (1 << width) - 1,
+        }
+    }
+
+    fn signed(value: u64, width: u32) -> i64 {
+        ((value << (64 - width)) as i64) >> (64 - width)
+    }
+
+    /// A small concrete interpreter for the subset of SMT-LIB2 that is
+    /// generated.
+    struct Interpreter {
+        values: HashMap<String, Term>,
+        asserted: Vec<bool>,
+    }
+
+    impl Interpreter {
+        fn new(pre: impl Fn(&str) -> Term) -> Self {
+            let mut values = HashMap::new();
+            for command in parse(&smt::declarations()) {
+                let Sexp::List(command) = command else {
+                    panic!()
+                };
+                let Sexp::Atom(name) = &command[1] else {
+                    panic!()
+                };
+                values.insert(name.clone(), pre(name));
+            }
+            Self {
+                values,
+                asserted: vec![],
+            }
+        }
+
+        fn run(&mut self, script: &str) {
+            for command in parse(script) {
+                let Sexp::List(command) = command else {
+                    panic!("Expected a command")
+                };
+                let Sexp::Atom(keyword) = &command[0] else {
+                    panic!("Expected a keyword")
+                };
+                match keyword.as_str() {
+                    "declare-const" => {
+                        let Sexp::Atom(name) = &command[1] else {
+                            panic!()
+                        };
+                        // Pre-state variables are already known, everything else is
+                        // unconstrained and set to zero.
+                        if !self.values.contains_key(name) {
+                            let width = match &command[2] {
+                                Sexp::List(sort) => match &sort[2] {
+                                    Sexp::Atom(width) => width.parse().unwrap(),
+                                    _ => panic!(),
+                                },
+                                _ => panic!(),
+                            };
+                            self.values.insert(name.clone(), Term::Bv(0, width));
+                        }
+                    }
+                    "define-fun" => {
+                        let Sexp::Atom(name) = &command[1] else {
+                            panic!()
+                        };
+                        let value = self.eval(&command[4]);
+                        assert!(
+                            self.values.insert(name.clone(), value).is_none(),
+                            "{name} redefined"
+                        );
+                    }
+                    "assert" => match self.eval(&command[1]) {
+                        Term::Bool(value) => self.asserted.push(value),
+                        _ => panic!("Assertions must be boolean"),
+                    },
+                    "check-sat" => {}
+                    keyword => panic!("Unexpected command {keyword}"),
+                }
+            }
+        }
+
+        fn bv(&self, value: &Sexp) -> (u64, u32) {
+            match self.eval(value) {
+                Term::Bv(value, width) => (value, width),
+                term => panic!("Expected a bit vector, found {term:?}"),
+            }
+        }
+
+        fn eval(&self, value: &Sexp) -> Term {
+            let list = match value {
+                Sexp::Atom(atom) if atom == "#b0" => return Term::Bv(0, 1),
+                Sexp::Atom(atom) if atom == "#b1" => return Term::Bv(1, 1),
+                Sexp::Atom(atom) => {
+                    return self
+                        .values
+                        .get(atom)
+                        .unwrap_or_else(|| panic!("{atom} is not defined"))
+                        .clone()
+                }
+                Sexp::List(list) => list,
+            };
+            let number = |value: &Sexp| match value {
+                Sexp::Atom(value) => value.parse::<u32>().unwrap(),
+                _ => panic!(),
+            };
+            match &list[0] {
+                Sexp::Atom(op) if op == "_" => {
+                    let Sexp::Atom(value) = &list[1] else {
+                        panic!()
+                    };
+                    return Term::Bv(value[2..].parse().unwrap(), number(&list[2]));
+                }
+                Sexp::List(indexed) => {
+                    let (value, width) = self.bv(&list[1]);
+                    let Sexp::Atom(op) = &indexed[1] else {
+                        panic!()
+                    };
+                    return match op.as_str() {
+                        "extract" => {
+                            let (high, low) = (number(&indexed[2]), number(&indexed[3]));
+                            Term::Bv((value >> low) & mask(high - low + 1), high - low + 1)
+                        }
+                        "zero_extend" => Term::Bv(value, width + number(&indexed[2])),
+                        "sign_extend" => {
+                            let width = width + number(&indexed[2]);
+                            Term::Bv(
+                                signed(value, width - number(&indexed[2])) as u64 & mask(width),
+                                width,
+                            )
+                        }
+                        op => panic!("Unexpected operation {op}"),
+                    };
+                }
+                _ => {}
+            }
+            let Sexp::Atom(op) = &list[0] else { panic!() };
+            let args = &list[1..];
+            match op.as_str() {
+                "ite" => match self.eval(&args[0]) {
+                    Term::Bool(true) => self.eval(&args[1]),
+                    Term::Bool(false) => self.eval(&args[2]),
+                    _ => panic!("Condition must be boolean"),
+                },
+                "=" => Term::Bool(self.eval(&args[0]) == self.eval(&args[1])),
+                "not" => Term::Bool(self.eval(&args[0]) == Term::Bool(false)),
+                "and" => Term::Bool(args.iter().all(|arg| self.eval(arg) == Term::Bool(true))),
+                "select" => {
+                    let Term::Array(memory) = self.eval(&args[0]) else {
+                        panic!()
+                    };
+                    let (address, _) = self.bv(&args[1]);
+                    Term::Bv(*memory.get(&address).unwrap_or(&0), 8)
+                }
+                "store" => {
+                    let Term::Array(mut memory) = self.eval(&args[0]) else {
+                        panic!()
+                    };
+                    let (address, _) = self.bv(&args[1]);
+                    let (value, _) = self.bv(&args[2]);
+                    memory.insert(address, value);
+                    Term::Array(memory)
+                }
+                "bvnot" => {
+                    let (value, width) = self.bv(&args[0]);
+                    Term::Bv(!value & mask(width), width)
+                }
+                "bvneg" => {
+                    let (value, width) = self.bv(&args[0]);
+                    Term::Bv(value.wrapping_neg() & mask(width), width)
+                }
+                "concat" => {
+                    let (high, high_width) = self.bv(&args[0]);
+                    let (low, low_width) = self.bv(&args[1]);
+                    Term::Bv((high << low_width) | low, high_width + low_width)
+                }
+                op => {
+                    let (a, width) = self.bv(&args[0]);
+                    let (b, _) = self.bv(&args[1]);
+                    let (sa, sb) = (signed(a, width), signed(b, width));
+                    let bits = width as u64;
+                    let bv = |value: u64| Term::Bv(value & mask(width), width);
+                    match op {
+                        "bvadd" => bv(a.wrapping_add(b)),
+                        "bvsub" => bv(a.wrapping_sub(b)),
+                        "bvmul" => bv(a.wrapping_mul(b)),
+                        "bvudiv" => bv(a.checked_div(b).unwrap_or(u64::MAX)),
+                        "bvsdiv" => bv(sa.wrapping_div(sb) as u64),
+                        "bvand" => bv(a & b),
+                        "bvor" => bv(a | b),
+                        "bvxor" => bv(a ^ b),
+                        "bvshl" if b >= bits => bv(0),
+                        "bvshl" => bv(a << b),
+                        "bvlshr" if b >= bits => bv(0),
+                        "bvlshr" => bv(a >> b),
+                        "bvashr" => bv((sa >> b.min(63)) as u64),
+                        "bvult" => Term::Bool(a < b),
+                        "bvule" => Term::Bool(a <= b),
+                        "bvslt" => Term::Bool(sa < sb),
+                        "bvsle" => Term::Bool(sa <= sb),
+                        op => panic!("Unexpected operation {op}"),
+                    }
+                }
+            }
+        }
+
+        fn register(&self, state: &smt::State, register: Register) -> u64 {
+            self.bv(&Sexp::Atom(state.register(register))).0
+        }
+
+        fn flag(&self, state: &smt::State, flag: Flag) -> bool {
+            self.bv(&Sexp::Atom(state.flag(flag))).0 == 1
+        }
+    }
+
+    /// Sets `Rn` to `n * 0x0101_0101`, the flags and the memory to zero and the
+    /// PC to `0x1000`.
+    fn pre(name: &str) -> Term {
+        match name {
+            "mem" => Term::Array(HashMap::new()),
+            "r15" => Term::Bv(0x1000, 32),
+            register if register.starts_with('r') => {
+                Term::Bv(register[1..].parse::<u64>().unwrap() * 0x0101_0101, 32)
+            }
+            _ => Term::Bv(0, 1),
+        }
+    }
+
+    fn encode(operations: &[(InstructionSize, Operation)]) -> (Interpreter, smt::State) {
+        let mut encoder = smt::Encoder::new();
+        for (size, operation) in operations {
+            encoder.push(operation, &smt::context(*size));
+        }
+        let post = encoder.post();
+        let mut interpreter = Interpreter::new(pre);
+        interpreter.run(&encoder.finish());
+        (interpreter, post)
+    }
+
+    #[test]
+    fn test_arithmetic_sequence() {
+        let mov: Operation = operation::MovImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Register::R0)
+            .set_imm(0x7fff_ffff)
+            .set_carry(None)
+            .complete()
+            .into();
+        let adds: Operation = operation::AddImmediate::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R2))
+            .set_rn(Register::R0)
+            .set_imm(1)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Wide, mov), (Wide, adds)]);
+        assert_eq!(interpreter.register(&post, Register::R2), 0x8000_0000);
+        assert_eq!(interpreter.register(&post, Register::R1), 0x0101_0101);
+        assert_eq!(interpreter.register(&post, Register::PC), 0x1008);
+        assert!(interpreter.flag(&post, Flag::N));
+        assert!(interpreter.flag(&post, Flag::V));
+        assert!(!interpreter.flag(&post, Flag::C));
+        assert!(!interpreter.flag(&post, Flag::Z));
+    }
+
+    #[test]
+    fn test_memory() {
+        let push: Operation = operation::Push::builder()
+            .set_registers(RegisterList {
+                registers: vec![Register::R4, Register::LR],
+            })
+            .complete()
+            .into();
+        let pop: Operation = operation::Pop::builder()
+            .set_registers(RegisterList {
+                registers: vec![Register::R5, Register::PC],
+            })
+            .complete()
+            .into();
+        let ldrh: Operation = operation::LdrhImmediate::builder()
+            .set_w(Some(false))
+            .set_add(Some(true))
+            .set_index(Some(true))
+            .set_rt(Register::R0)
+            .set_rn(Register::SP)
+            .set_imm(2)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Narrow, push), (Wide, ldrh), (Narrow, pop)]);
+        let sp = interpreter.register(&smt::State::initial(), Register::SP);
+        assert_eq!(interpreter.register(&post, Register::SP), sp);
+        assert_eq!(interpreter.register(&post, Register::R5), 0x0404_0404);
+        assert_eq!(interpreter.register(&post, Register::R0), 0x0404);
+        assert_eq!(interpreter.register(&post, Register::PC), 0x0e0e_0e0e);
+    }
+
+    #[test]
+    fn test_branches() {
+        let beq: Operation = operation::B::builder()
+            .set_condition(Condition::Eq)
+            .set_imm(0x20)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Narrow, beq.clone())]);
+        assert_eq!(interpreter.register(&post, Register::PC), 0x1002);
+
+        let cmp: Operation = operation::CmpRegister::builder()
+            .set_rn(Register::R1)
+            .set_rm(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Narrow, cmp), (Narrow, beq)]);
+        assert_eq!(interpreter.register(&post, Register::PC), 0x1026);
+
+        let bl: Operation = operation::Bl::builder().set_imm(0x100).complete().into();
+        let (interpreter, post) = encode(&[(Wide, bl)]);
+        assert_eq!(interpreter.register(&post, Register::PC), 0x1104);
+        assert_eq!(interpreter.register(&post, Register::LR), 0x1005);
+    }
+
+    #[test]
+    fn test_dsp() {
+        let mov: Operation = operation::MovImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Register::R3)
+            .set_imm(0xff00_ff00)
+            .set_carry(None)
+            .complete()
+            .into();
+        let uadd8: Operation = operation::Uadd8::builder()
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R3)
+            .set_rm(Register::R1)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Wide, mov), (Wide, uadd8)]);
+        assert_eq!(interpreter.register(&post, Register::R0), 0x0001_0001);
+        assert!(!interpreter.flag(&post, Flag::Ge(0)));
+        assert!(interpreter.flag(&post, Flag::Ge(1)));
+        assert!(!interpreter.flag(&post, Flag::Ge(2)));
+        assert!(interpreter.flag(&post, Flag::Ge(3)));
+
+        let mov: Operation = operation::MovImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Register::R3)
+            .set_imm(0x7fff_ffff)
+            .set_carry(None)
+            .complete()
+            .into();
+        let qadd: Operation = operation::Qadd::builder()
+            .set_rd(Some(Register::R0))
+            .set_rm(Register::R3)
+            .set_rn(Register::R3)
+            .complete()
+            .into();
+        let clz: Operation = operation::Clz::builder()
+            .set_rd(Register::R1)
+            .set_rm(Register::R0)
+            .complete()
+            .into();
+        let mrs: Operation = operation::Mrs::builder()
+            .set_rd(Register::R2)
+            .set_sysm(SpecialRegister::Apsr)
+            .complete()
+            .into();
+        let (interpreter, post) = encode(&[(Wide, mov), (Wide, qadd), (Wide, clz), (Wide, mrs)]);
+        assert_eq!(interpreter.register(&post, Register::R0), 0x7fff_ffff);
+        assert!(interpreter.flag(&post, Flag::Q));
+        assert_eq!(interpreter.register(&post, Register::R1), 1);
+        assert_eq!(interpreter.register(&post, Register::R2), 1 << 27);
+    }
+
+    fn equivalent(
+        first: &[(InstructionSize, Operation)],
+        second: &[(InstructionSize, Operation)],
+    ) -> bool {
+        let script = smt::equivalence(first, second);
+        let pres: [fn(&str) -> Term; 2] = [pre, |name| match name {
+            "mem" => Term::Array(HashMap::new()),
+            "c" => Term::Bv(1, 1),
+            register if register.starts_with('r') => {
+                Term::Bv(0x8000_0000 | register[1..].parse::<u64>().unwrap(), 32)
+            }
+            _ => Term::Bv(0, 1),
+        }];
+        pres.into_iter().all(|pre| {
+            let mut interpreter = Interpreter::new(pre);
+            interpreter.run(&script);
+            interpreter.asserted == [false]
+        })
+    }
+
+    #[test]
+    fn test_equivalence() {
+        let lsl: Operation = operation::LslImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Register::R0)
+            .set_rm(Register::R1)
+            .set_imm(1)
+            .complete()
+            .into();
+        let add: Operation = operation::AddRegister::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        let adds: Operation = operation::AddRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        assert!(equivalent(&[(Narrow, lsl.clone())], &[(Wide, add)]));
+        assert!(!equivalent(&[(Narrow, lsl.clone())], &[(Wide, adds.clone())]));
+
+        let script = smt::equivalence(&[(Narrow, lsl)], &[(Wide, adds)]);
+        assert!(script.ends_with("(check-sat)\n"));
+    }
+
+    #[test]
+    #[ignore = "requires an SMT solver, set DISARMV7_SMT_SOLVER to use another solver than z3"]
+    fn test_solver() {
+        let lsl: Operation = operation::LslImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Register::R0)
+            .set_rm(Register::R1)
+            .set_imm(1)
+            .complete()
+            .into();
+        let adds: Operation = operation::AddRegister::builder()
+            .set_s(Some(SetFlags::Literal(true)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_rm(Register::R1)
+            .set_shift(None)
+            .complete()
+            .into();
+        let script = smt::equivalence(&[(Narrow, lsl)], &[(Wide, adds)]);
+        assert!(script.ends_with("(check-sat)\n"));
+
+        let solver = std::env::var("DISARMV7_SMT_SOLVER").unwrap_or("z3".to_string());
+        let mut child = Command::new(&solver)
+            .arg("-in")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap_or_else(|e| panic!("Could not start the solver {solver}: {e}"));
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(script.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "sat");
+    }
+
+    #[test]
+    fn test_decoded_scripts_are_well_formed() {
+        let mut state = 0x1234_5678_u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let mut operations = vec![];
+        while operations.len() < 2_000 {
+            let word = next();
+            let bytes = [
+                (word >> 16) as u8,
+                (word >> 24) as u8,
+                word as u8,
+                (word >> 8) as u8,
+            ];
+            let mut stream = PeekableBuffer::from(bytes.into_iter());
+            if let Ok(decoded) = Operation::decode(&mut stream, Policy::Permissive) {
+                operations.push((decoded.size, decoded.op));
+            }
+        }
+        for chunk in operations.chunks(8) {
+            let mut interpreter = Interpreter::new(pre);
+            interpreter.run(&smt::transition(chunk));
+        }
+    }
+}
@@ -0,0 +1,694 @@
+//! Defines a per instruction cycle count model.
+//!
+//! The main export of this module is [`Operation::cycles`] which returns the
+//! best and worst case number of cycles that an [`Operation`] takes to
+//! execute on a given [`Core`].
+//!
+//! The numbers are derived from the instruction timing tables in the
+//! Cortex-M3 and Cortex-M4 technical reference manuals. The Cortex-M7 model is
+//! an approximation of the dual issue pipeline, where instructions that can be
+//! dual issued have a best case cost of zero cycles.
+//!
+//! ## Usage
+//!
+//! ```
+//! use disarmv7::{
+//!     prelude::*,
+//!     timing::{Core, Cycles, Parameters},
+//! };
+//!
+//! let op: Operation = operation::B::builder()
+//!     .set_condition(Condition::Eq)
+//!     .set_imm(12)
+//!     .complete()
+//!     .into();
+//!
+//! let params = Parameters {
+//!     pipeline_refill: 2,
+//!     wait_states: 0,
+//! };
+//! let timing = op.cycles(&Core::M4, &params).unwrap();
+//!
+//! // Falling through costs a single cycle.
+//! assert_eq!(timing.sequential, Cycles { best: 1, worst: 1 });
+//! // Taking the branch requires the pipeline to be refilled.
+//! assert_eq!(timing.branch, Some(Cycles { best: 3, worst: 3 }));
+//! ```
+
+use crate::{arch::RegisterList, flow::Flow, operation::Operation};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Enumerates the cores that the model supports.
+pub enum Core {
+    /// Cortex-M3, does not implement the DSP extension.
+    M3,
+    /// Cortex-M4.
+    M4,
+    /// Cortex-M7, approximated as a dual issue pipeline.
+    M7,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Parameters of the system that the core is running in.
+pub struct Parameters {
+    /// Number of cycles needed to refill the pipeline after a branch, `P` in
+    /// the technical reference manuals.
+    ///
+    /// This ranges from 1 to 3 cycles depending on the alignment and width of
+    /// the target instruction.
+    pub pipeline_refill: u32,
+
+    /// Number of wait states for accesses to flash memory.
+    ///
+    /// These are added to the worst case of every instruction fetch after a
+    /// branch and every data load.
+    pub wait_states: u32,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            pipeline_refill: 3,
+            wait_states: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A bounded number of cycles.
+pub struct Cycles {
+    /// The least number of cycles the instruction can take.
+    pub best: u32,
+    /// The largest number of cycles the instruction can take.
+    pub worst: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The cost of executing a single instruction.
+pub struct Timing {
+    /// Cost when the instruction does not write to the PC.
+    ///
+    /// This is also the cost of an instruction that fails its condition
+    /// check, i.e. a branch that is not taken.
+    pub sequential: Cycles,
+
+    /// Cost when the instruction writes to the PC, i.e. a branch that is
+    /// taken.
+    ///
+    /// This is None if the instruction can never write to the PC.
+    pub branch: Option<Cycles>,
+}
+
+impl Cycles {
+    const fn exact(cycles: u32) -> Self {
+        Self {
+            best: cycles,
+            worst: cycles,
+        }
+    }
+
+    const fn range(best: u32, worst: u32) -> Self {
+        Self { best, worst }
+    }
+
+    const fn add(self, cycles: u32) -> Self {
+        Self {
+            best: self.best + cycles,
+            worst: self.worst + cycles,
+        }
+    }
+}
+
+impl Operation {
+    /// Returns the best and worst case number of cycles needed to execute
+    /// the instruction on the `core`.
+    ///
+    /// Returns None if the instruction is not available on the `core` or if
+    /// its execution time can not be bounded, such as waiting for an
+    /// interrupt or triggering an exception.
+    pub fn cycles(&self, core: &Core, params: &Parameters) -> Option<Timing> {
+        // The targets do not affect the cost, so the address is irrelevant.
+        let (sequential, branch) = match (self.control_flow(0), self.base_cycles(core, params)?) {
+            (Flow::Sequential | Flow::Stop, cycles) => (cycles, None),
+            (Flow::Branch { .. } | Flow::CompareBranch { .. } | Flow::Call { .. }, cycles) => {
+                match core {
+                    // The branch predictor can fold the branch.
+                    Core::M7 => (cycles, Some(Cycles::range(1, 1 + params.pipeline_refill))),
+                    _ => (cycles, Some(cycles.add(params.pipeline_refill))),
+                }
+            }
+            (Flow::Table, cycles) => (cycles, Some(Cycles::exact(2 + params.pipeline_refill))),
+            // The target is loaded from memory.
+            (Flow::Return | Flow::Indirect, cycles) if self.loads() > 0 => (
+                cycles,
+                Some(Cycles::exact(1 + self.loads() + params.pipeline_refill)),
+            ),
+            (Flow::IndirectCall | Flow::Return | Flow::Indirect, cycles) => {
+                (cycles, Some(cycles.add(params.pipeline_refill)))
+            }
+        };
+
+        let branch = branch.map(|cycles| Cycles {
+            best: cycles.best,
+            worst: cycles.worst + params.wait_states + self.loads() * params.wait_states,
+        });
+        let sequential = Cycles {
+            best: sequential.best,
+            worst: sequential.worst + self.loads() * params.wait_states,
+        };
+        Some(Timing { sequential, branch })
+    }
+
+    /// Number of data loads that the instruction performs.
+    fn loads(&self) -> u32 {
+        match self {
+            Self::Ldm(el) => el.registers.registers.len() as u32,
+            Self::Ldmdb(el) => el.registers.registers.len() as u32,
+            Self::Pop(el) => el.registers.registers.len() as u32,
+            Self::LdrdImmediate(_) | Self::LdrdLiteral(_) => 2,
+            Self::Tb(_)
+            | Self::LdrImmediate(_)
+            | Self::LdrLiteral(_)
+            | Self::LdrRegister(_)
+            | Self::LdrbImmediate(_)
+            | Self::LdrbLiteral(_)
+            | Self::LdrbRegister(_)
+            | Self::Ldrbt(_)
+            | Self::Ldrex(_)
+            | Self::Ldrexb(_)
+            | Self::Ldrexh(_)
+            | Self::LdrhImmediate(_)
+            | Self::LdrhLiteral(_)
+            | Self::LdrhRegister(_)
+            | Self::Ldrht(_)
+            | Self::LdrsbImmediate(_)
+            | Self::LdrsbLiteral(_)
+            | Self::LdrsbRegister(_)
+            | Self::Ldrsbt(_)
+            | Self::LdrshImmediate(_)
+            | Self::LdrshLiteral(_)
+            | Self::LdrshRegister(_)
+            | Self::Ldrsht(_)
+            | Self::Ldrt(_) => 1,
+            _ => 0,
+        }
+    }
+
+    /// The number of cycles the instruction takes when it does not write to
+    /// the PC.
+    fn base_cycles(&self, core: &Core, params: &Parameters) -> Option<Cycles> {
+        // Instructions that the M7 can dual issue with the previous
+        // instruction.
+        let alu = match core {
+            Core::M7 => Cycles::range(0, 1),
+            _ => Cycles::exact(1),
+        };
+        // Neighbouring loads and stores can pipeline their address and data
+        // phases.
+        let load = Cycles::range(1, 2);
+        let dsp = match core {
+            Core::M3 => None,
+            _ => Some(Cycles::exact(1)),
+        };
+        let multiple = |list: &RegisterList| Cycles::exact(1 + list.registers.len() as u32);
+        Some(match self {
+            Self::AdcImmediate(_)
+            | Self::AdcRegister(_)
+            | Self::AddImmediate(_)
+            | Self::AddRegister(_)
+            | Self::AddSPImmediate(_)
+            | Self::AddSPRegister(_)
+            | Self::Adr(_)
+            | Self::AndImmediate(_)
+            | Self::AndRegister(_)
+            | Self::AsrImmediate(_)
+            | Self::AsrRegister(_)
+            | Self::Bfc(_)
+            | Self::Bfi(_)
+            | Self::BicImmediate(_)
+            | Self::BicRegister(_)
+            | Self::Clz(_)
+            | Self::CmnImmediate(_)
+            | Self::CmnRegister(_)
+            | Self::CmpImmediate(_)
+            | Self::CmpRegister(_)
+            | Self::EorImmediate(_)
+            | Self::EorRegister(_)
+            | Self::LslImmediate(_)
+            | Self::LslRegister(_)
+            | Self::LsrImmediate(_)
+            | Self::LsrRegister(_)
+            | Self::MovImmediate(_)
+            | Self::MovRegister(_)
+            | Self::Movt(_)
+            | Self::Mul(_)
+            | Self::MvnImmediate(_)
+            | Self::MvnRegister(_)
+            | Self::OrnImmediate(_)
+            | Self::OrnRegister(_)
+            | Self::OrrImmediate(_)
+            | Self::OrrRegister(_)
+            | Self::Rbit(_)
+            | Self::Rev(_)
+            | Self::Rev16(_)
+            | Self::Revsh(_)
+            | Self::RorImmediate(_)
+            | Self::RorRegister(_)
+            | Self::Rrx(_)
+            | Self::RsbImmediate(_)
+            | Self::RsbRegister(_)
+            | Self::SbcImmediate(_)
+            | Self::SbcRegister(_)
+            | Self::Sbfx(_)
+            | Self::Ssat(_)
+            | Self::SubImmediate(_)
+            | Self::SubRegister(_)
+            | Self::SubSpMinusImmediate(_)
+            | Self::SubSpMinusRegister(_)
+            | Self::Sxtb(_)
+            | Self::Sxth(_)
+            | Self::TeqImmediate(_)
+            | Self::TeqRegister(_)
+            | Self::TstImmediate(_)
+            | Self::TstRegister(_)
+            | Self::Ubfx(_)
+            | Self::Usat(_)
+            | Self::Uxtb(_)
+            | Self::Uxth(_)
+            | Self::Nop(_)
+            | Self::UnallocatedHint(_)
+            | Self::It(_) => alu,
+
+            Self::Clrex(_)
+            | Self::Dbg(_)
+            | Self::Sev(_)
+            | Self::Yield(_)
+            | Self::Cps(_)
+            | Self::PldImmediate(_)
+            | Self::PldLiteral(_)
+            | Self::PldRegister(_)
+            | Self::PliImmediate(_)
+            | Self::PliRegister(_) => Cycles::exact(1),
+
+            Self::B(_) | Self::Bl(_) | Self::Bx(_) | Self::Blx(_) | Self::Cbz(_) | Self::Tb(_) => {
+                Cycles::exact(1)
+            }
+
+            Self::Mrs(_) | Self::Msr(_) => Cycles::exact(2),
+            Self::Isb(_) | Self::Dmb(_) | Self::Dsb(_) => {
+                Cycles::range(1, 1 + params.pipeline_refill)
+            }
+
+            Self::Mla(_) | Self::Mls(_) => match core {
+                Core::M3 => Cycles::exact(2),
+                _ => Cycles::exact(1),
+            },
+            Self::Smull(_) | Self::Umull(_) => match core {
+                Core::M3 => Cycles::range(3, 5),
+                Core::M4 => Cycles::exact(1),
+                Core::M7 => Cycles::range(1, 2),
+            },
+            Self::Smlal(_) | Self::Umlal(_) => match core {
+                Core::M3 => Cycles::range(4, 7),
+                Core::M4 => Cycles::exact(1),
+                Core::M7 => Cycles::range(1, 2),
+            },
+            Self::Sdiv(_) | Self::Udiv(_) => Cycles::range(2, 12),
+
+            Self::LdrImmediate(_)
+            | Self::LdrLiteral(_)
+            | Self::LdrRegister(_)
+            | Self::LdrbImmediate(_)
+            | Self::LdrbLiteral(_)
+            | Self::LdrbRegister(_)
+            | Self::Ldrbt(_)
+            | Self::Ldrex(_)
+            | Self::Ldrexb(_)
+            | Self::Ldrexh(_)
+            | Self::LdrhImmediate(_)
+            | Self::LdrhLiteral(_)
+            | Self::LdrhRegister(_)
+            | Self::Ldrht(_)
+            | Self::LdrsbImmediate(_)
+            | Self::LdrsbLiteral(_)
+            | Self::LdrsbRegister(_)
+            | Self::Ldrsbt(_)
+            | Self::LdrshImmediate(_)
+            | Self::LdrshLiteral(_)
+            | Self::LdrshRegister(_)
+            | Self::Ldrsht(_)
+            | Self::Ldrt(_)
+            | Self::StrImmediate(_)
+            | Self::StrRegister(_)
+            | Self::StrbImmediate(_)
+            | Self::StrbRegister(_)
+            | Self::Strbt(_)
+            | Self::Strex(_)
+            | Self::Strexb(_)
+            | Self::Strexh(_)
+            | Self::StrhImmediate(_)
+            | Self::StrhRegister(_)
+            | Self::Strht(_)
+            | Self::Strt(_) => load,
+
+            Self::LdrdImmediate(_) | Self::LdrdLiteral(_) | Self::StrdImmediate(_) => {
+                Cycles::exact(3)
+            }
+
+            Self::Ldm(el) => multiple(&el.registers),
+            Self::Ldmdb(el) => multiple(&el.registers),
+            Self::Pop(el) => multiple(&el.registers),
+            Self::Stm(el) => multiple(&el.registers),
+            Self::Stmdb(el) => multiple(&el.registers),
+            Self::Push(el) => multiple(&el.registers),
+
+            // DSP extension
+            Self::Pkh(_)
+            | Self::Qadd(_)
+            | Self::Qadd16(_)
+            | Self::Qadd8(_)
+            | Self::Qasx(_)
+            | Self::Qdadd(_)
+            | Self::Qdsub(_)
+            | Self::Qsax(_)
+            | Self::Qsub(_)
+            | Self::Qsub16(_)
+            | Self::Qsub8(_)
+            | Self::Sadd16(_)
+            | Self::Sadd8(_)
+            | Self::Sasx(_)
+            | Self::Sel(_)
+            | Self::Shadd16(_)
+            | Self::Shadd8(_)
+            | Self::Shasx(_)
+            | Self::Shsax(_)
+            | Self::Shsub16(_)
+            | Self::Shsub8(_)
+            | Self::Smla(_)
+            | Self::Smlad(_)
+            | Self::SmlalSelective(_)
+            | Self::Smlald(_)
+            | Self::Smlaw(_)
+            | Self::Smlsd(_)
+            | Self::Smlsld(_)
+            | Self::Smmla(_)
+            | Self::Smmls(_)
+            | Self::Smmul(_)
+            | Self::Smuad(_)
+            | Self::Smul(_)
+            | Self::Smulw(_)
+            | Self::Smusd(_)
+            | Self::Ssat16(_)
+            | Self::Ssax(_)
+            | Self::Ssub16(_)
+            | Self::Ssub8(_)
+            | Self::Sxtab(_)
+            | Self::Sxtab16(_)
+            | Self::Sxtah(_)
+            | Self::Sxtb16(_)
+            | Self::Uadd16(_)
+            | Self::Uadd8(_)
+            | Self::Uasx(_)
+            | Self::Uhadd16(_)
+            | Self::Uhadd8(_)
+            | Self::Uhasx(_)
+            | Self::Uhsax(_)
+            | Self::Uhsub16(_)
+            | Self::Uhsub8(_)
+            | Self::Umaal(_)
+            | Self::Uqadd16(_)
+            | Self::Uqadd8(_)
+            | Self::Uqasx(_)
+            | Self::Uqsax(_)
+            | Self::Uqsub16(_)
+            | Self::Uqsub8(_)
+            | Self::Uqsad8(_)
+            | Self::Usada8(_)
+            | Self::Usad8(_)
+            | Self::Usat16(_)
+            | Self::Usax(_)
+            | Self::Usub16(_)
+            | Self::Usub8(_)
+            | Self::Uxtab(_)
+            | Self::Uxtab16(_)
+            | Self::Uxtah(_)
+            | Self::Uxtb16(_) => dsp?,
+
+            // These either trap, wait for an event or depend on the
+            // co processor.
+            Self::Bkpt(_)
+            | Self::Svc(_)
+            | Self::Udf(_)
+            | Self::Wfe(_)
+            | Self::Wfi(_)
+            | Self::Cdp(_)
+            | Self::LdcImmediate(_)
+            | Self::LdcLiteral(_)
+            | Self::Mcr(_)
+            | Self::Mcrr(_)
+            | Self::Mrc(_)
+            | Self::Mrrc(_)
+            | Self::Stc(_) => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Core, Cycles, Parameters, Timing};
+    use crate::prelude::*;
+
+    fn params() -> Parameters {
+        Parameters {
+            pipeline_refill: 2,
+            wait_states: 0,
+        }
+    }
+
+    #[test]
+    fn test_alu() {
+        let op: Operation = operation::AddImmediate::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Some(Register::R0))
+            .set_rn(Register::R1)
+            .set_imm(1)
+            .complete()
+            .into();
+        assert_eq!(
+            op.cycles(&Core::M3, &params()),
+            Some(Timing {
+                sequential: Cycles { best: 1, worst: 1 },
+                branch: None
+            })
+        );
+        assert_eq!(
+            op.cycles(&Core::M7, &params()).unwrap().sequential,
+            Cycles { best: 0, worst: 1 }
+        );
+    }
+
+    #[test]
+    fn test_conditional_branch() {
+        let op: Operation = operation::B::builder()
+            .set_condition(Condition::Ne)
+            .set_imm(4)
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M3, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 1, worst: 1 });
+        assert_eq!(timing.branch, Some(Cycles { best: 3, worst: 3 }));
+
+        let params = Parameters {
+            pipeline_refill: 2,
+            wait_states: 2,
+        };
+        let timing = op.cycles(&Core::M4, &params).unwrap();
+        assert_eq!(timing.branch, Some(Cycles { best: 3, worst: 5 }));
+    }
+
+    #[test]
+    fn test_pop_pc() {
+        let op: Operation = operation::Pop::builder()
+            .set_registers(RegisterList::try_from(0b1000_0000_0001_0000u16).unwrap())
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M4, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 3, worst: 3 });
+        assert_eq!(timing.branch, Some(Cycles { best: 5, worst: 5 }));
+
+        let op: Operation = operation::Push::builder()
+            .set_registers(RegisterList::try_from(0b0100_0000_1111_0000u16).unwrap())
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M4, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 6, worst: 6 });
+        assert_eq!(timing.branch, None);
+    }
+
+    #[test]
+    fn test_load_wait_states() {
+        let op: Operation = operation::LdrLiteral::builder()
+            .set_add(true)
+            .set_rt(Register::R0)
+            .set_imm(8)
+            .complete()
+            .into();
+        let params = Parameters {
+            pipeline_refill: 1,
+            wait_states: 3,
+        };
+        let timing = op.cycles(&Core::M3, &params).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 1, worst: 5 });
+    }
+
+    #[test]
+    fn test_dsp_availability() {
+        let op: Operation = operation::Qadd::builder()
+            .set_rd(Some(Register::R0))
+            .set_rm(Register::R1)
+            .set_rn(Register::R2)
+            .complete()
+            .into();
+        assert_eq!(op.cycles(&Core::M3, &params()), None);
+        assert!(op.cycles(&Core::M4, &params()).is_some());
+    }
+
+    #[test]
+    fn test_long_multiply() {
+        let op: Operation = operation::Umlal::builder()
+            .set_rdlo(Register::R0)
+            .set_rdhi(Register::R1)
+            .set_rn(Register::R2)
+            .set_rm(Register::R3)
+            .complete()
+            .into();
+        assert_eq!(
+            op.cycles(&Core::M3, &params()).unwrap().sequential,
+            Cycles { best: 4, worst: 7 }
+        );
+        assert_eq!(
+            op.cycles(&Core::M4, &params()).unwrap().sequential,
+            Cycles { best: 1, worst: 1 }
+        );
+    }
+
+    #[test]
+    fn test_load_store_multiple() {
+        let params = Parameters {
+            pipeline_refill: 2,
+            wait_states: 1,
+        };
+        let op: Operation = operation::Ldm::builder()
+            .set_w(Some(true))
+            .set_rn(Register::R0)
+            .set_registers(RegisterList::try_from(0b0000_0000_0000_1110u16).unwrap())
+            .complete()
+            .into();
+        assert_eq!(
+            op.cycles(&Core::M3, &params),
+            Some(Timing {
+                sequential: Cycles { best: 4, worst: 7 },
+                branch: None
+            })
+        );
+
+        // Stores are not affected by the wait states.
+        let op: Operation = operation::Stm::builder()
+            .set_w(Some(true))
+            .set_rn(Register::R0)
+            .set_registers(RegisterList::try_from(0b0000_0000_0001_1110u16).unwrap())
+            .complete()
+            .into();
+        assert_eq!(
+            op.cycles(&Core::M3, &params),
+            Some(Timing {
+                sequential: Cycles { best: 5, worst: 5 },
+                branch: None
+            })
+        );
+
+        // Loading the PC from a register other than SP is an indirect branch.
+        let op: Operation = operation::Ldm::builder()
+            .set_w(None)
+            .set_rn(Register::R0)
+            .set_registers(RegisterList::try_from(0b1000_0000_0001_0000u16).unwrap())
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M4, &params).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 3, worst: 5 });
+        assert_eq!(timing.branch, Some(Cycles { best: 5, worst: 8 }));
+    }
+
+    #[test]
+    fn test_divide() {
+        for op in [
+            operation::Sdiv::builder()
+                .set_rd(Some(Register::R0))
+                .set_rn(Register::R1)
+                .set_rm(Register::R2)
+                .complete()
+                .into(),
+            operation::Udiv::builder()
+                .set_rd(Some(Register::R0))
+                .set_rn(Register::R1)
+                .set_rm(Register::R2)
+                .complete()
+                .into(),
+        ] as [Operation; 2]
+        {
+            for core in [Core::M3, Core::M4, Core::M7] {
+                assert_eq!(
+                    op.cycles(&core, &params()),
+                    Some(Timing {
+                        sequential: Cycles { best: 2, worst: 12 },
+                        branch: None
+                    })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_branch_taken() {
+        // Not taken costs a single cycle, taken refills the pipeline unless
+        // the M7 folds the branch.
+        let op: Operation = operation::B::builder()
+            .set_condition(Condition::Eq)
+            .set_imm(4)
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M7, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 1, worst: 1 });
+        assert_eq!(timing.branch, Some(Cycles { best: 1, worst: 3 }));
+
+        let op: Operation = operation::Cbz::builder()
+            .set_non(Some(true))
+            .set_rn(Register::R0)
+            .set_imm(4)
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M3, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 1, worst: 1 });
+        assert_eq!(timing.branch, Some(Cycles { best: 3, worst: 3 }));
+
+        let op: Operation = operation::Bx::builder()
+            .set_rm(Register::R0)
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M4, &params()).unwrap();
+        assert_eq!(timing.branch, Some(Cycles { best: 3, worst: 3 }));
+
+        let op: Operation = operation::LdrImmediate::builder()
+            .set_w(Some(false))
+            .set_add(true)
+            .set_index(true)
+            .set_rt(Register::PC)
+            .set_rn(Register::R0)
+            .set_imm(0)
+            .complete()
+            .into();
+        let timing = op.cycles(&Core::M4, &params()).unwrap();
+        assert_eq!(timing.sequential, Cycles { best: 1, worst: 2 });
+        assert_eq!(timing.branch, Some(Cycles { best: 4, worst: 4 }));
+    }
+}
@@ -0,0 +1,636 @@
+//! Defines how UNPREDICTABLE encodings are decoded.
+//!
+//! The ARM ARM marks many encodings as UNPREDICTABLE, most often when SP or
+//! PC is used as an operand, but also when a register list can not be
+//! transferred, a base register that is written back is also loaded or
+//! stored, a store exclusive writes its status to one of its operands or an
+//! `msr` writes nothing. The processor still executes these, so fuzzed or
+//! obfuscated code can contain them. The decode tables decode these
+//! encodings as any other, and [`Reason::of`] classifies the decoded
+//! [`Operation`] afterwards, the [`Policy`] then decides what to do with it.
+//!
+//! Only the encoding itself is classified. Cases that depend on the
+//! surrounding code, such as a branch that is not the last instruction in an
+//! IT block, are decoded without a [`Reason`].
+//!
+//! UNDEFINED encodings are always rejected with
+//! [`ErrorKind::Undefined`](crate::ErrorKind::Undefined), apart from `udf`
+//! which is decoded as [`Udf`](operation::Udf).
+
+use std::fmt::Display;
+
+use crate::{
+    arch::{ApsrMask, Register, RegisterList, SpecialRegister},
+    operation::{self, Operation},
+    Raw,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Enumerates the ways that UNPREDICTABLE encodings can be decoded.
+pub enum Policy {
+    /// Rejects the encoding with
+    /// [`ErrorKind::Unpredictable`](crate::ErrorKind::Unpredictable).
+    Strict,
+    /// Decodes the encoding as written and tags it with the [`Reason`].
+    ///
+    /// This is the default, and is how the encodings were decoded before the
+    /// policy was introduced.
+    #[default]
+    Permissive,
+    /// Decodes the encoding as Cortex-M3/M4 execute it and tags it with the
+    /// [`Reason`].
+    ///
+    /// Reads of reserved special registers return zero and `msr`
+    /// instructions that write nothing are ignored, all other encodings
+    /// execute as written.
+    MatchHardware,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Enumerates the reasons that an encoding is UNPREDICTABLE.
+pub enum Reason {
+    /// The register can not be used as an operand in the encoding, this is
+    /// SP or PC in most cases.
+    Register(Register),
+    /// The register list is empty, or holds a single register in a 32 bit
+    /// encoding.
+    RegisterCount,
+    /// The register can not be in the register list, i.e. SP, PC in a store
+    /// or PC along with LR in a load.
+    RegisterList(Register),
+    /// The base register is written back and is also in the register list,
+    /// or is also the register that is loaded or stored.
+    Writeback(Register),
+    /// The same register is used for both halves of a 64 bit result or
+    /// load.
+    SameDestination(Register),
+    /// The status register of a store exclusive is also the register that
+    /// is stored or the base register.
+    Status(Register),
+    /// The SYSm value is reserved.
+    SpecialRegister(u8),
+    /// The `msr` writes no flags, or flags that the register does not have.
+    ApsrMask(ApsrMask),
+    /// The encoding is UNPREDICTABLE in the decode table.
+    Encoding(&'static str),
+}
+
+/// Returns the register if it is SP or PC.
+fn bad_reg(registers: &[Register]) -> Option<Register> {
+    registers
+        .iter()
+        .find(|register| matches!(register, Register::SP | Register::PC))
+        .copied()
+}
+
+/// Checks the register list of a 32 bit load or store multiple.
+fn register_list(
+    registers: &RegisterList,
+    rn: Option<Register>,
+    writeback: bool,
+    load: bool,
+) -> Option<Reason> {
+    let registers = &registers.registers;
+    let contains = |register: Register| registers.contains(&register);
+    if registers.len() < 2 {
+        return Some(Reason::RegisterCount);
+    }
+    if contains(Register::SP) {
+        return Some(Reason::RegisterList(Register::SP));
+    }
+    if contains(Register::PC) && (!load || contains(Register::LR)) {
+        return Some(Reason::RegisterList(Register::PC));
+    }
+    match rn {
+        Some(Register::PC) => Some(Reason::Register(Register::PC)),
+        Some(rn) if writeback && contains(rn) => Some(Reason::Writeback(rn)),
+        _ => None,
+    }
+}
+
+impl Reason {
+    /// Returns the reason that the decoded operation is UNPREDICTABLE, if
+    /// it is.
+    ///
+    /// The raw bits select between the 16 and 32 bit encodings of the
+    /// operation.
+    pub(crate) fn of(operation: &Operation, raw: Raw) -> Option<Self> {
+        let wide = matches!(raw, Raw::Word(_));
+        let register = |registers: &[Register]| bad_reg(registers).map(Self::Register);
+        match operation {
+            Operation::Mrs(el) => match el.sysm {
+                SpecialRegister::Reserved(sysm) => Some(Self::SpecialRegister(sysm)),
+                _ => register(&[el.rd]),
+            },
+            Operation::Msr(el) => {
+                let mask = u8::from(el.mask);
+                match el.sysm {
+                    SpecialRegister::Reserved(sysm) => Some(Self::SpecialRegister(sysm)),
+                    _ if mask == 0 || (mask != 0b10 && !el.sysm.has_flags()) => {
+                        Some(Self::ApsrMask(el.mask))
+                    }
+                    _ => register(&[el.rn]),
+                }
+            }
+            // The 16 bit compare of two high registers, both low registers
+            // are marked as UNPREDICTABLE in A5.4.
+            Operation::CmpRegister(el) => match raw {
+                Raw::Halfword(halfword) if halfword & 0xff00 == 0x4500 => {
+                    match (u8::from(el.rn) < 8 && u8::from(el.rm) < 8, el.rn, el.rm) {
+                        (true, ..) => Some(Self::Encoding("A5.4")),
+                        (_, Register::PC, _) | (_, _, Register::PC) => {
+                            Some(Self::Register(Register::PC))
+                        }
+                        _ => None,
+                    }
+                }
+                Raw::Word(_) => match el.rn {
+                    Register::PC => Some(Self::Register(Register::PC)),
+                    _ => register(&[el.rm]),
+                },
+                _ => None,
+            },
+            // BLX through the PC, A7.7.19.
+            Operation::Blx(el) if el.rm == Register::PC => Some(Self::Register(Register::PC)),
+            _ if !wide => match operation {
+                Operation::Ldm(el) if el.registers.registers.is_empty() => {
+                    Some(Self::RegisterCount)
+                }
+                Operation::Stm(el) if el.registers.registers.is_empty() => {
+                    Some(Self::RegisterCount)
+                }
+                Operation::Push(el) if el.registers.registers.is_empty() => {
+                    Some(Self::RegisterCount)
+                }
+                Operation::Pop(el) if el.registers.registers.is_empty() => {
+                    Some(Self::RegisterCount)
+                }
+                _ => None,
+            },
+            Operation::AndRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::BicRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::OrrRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::OrnRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::EorRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::AdcRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::SbcRegister(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::AndImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::BicImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::OrrImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::OrnImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::EorImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::AdcImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::SbcImmediate(el) => register(&[el.rd.unwrap_or(el.rn), el.rn]),
+            Operation::AddRegister(el) => {
+                add_sub(el.rd.unwrap_or(el.rn), el.rn).or_else(|| register(&[el.rm]))
+            }
+            Operation::SubRegister(el) => {
+                add_sub(el.rd.unwrap_or(el.rn), el.rn).or_else(|| register(&[el.rm]))
+            }
+            Operation::AddImmediate(el) => add_sub(el.rd.unwrap_or(el.rn), el.rn),
+            Operation::SubImmediate(el) => add_sub(el.rd.unwrap_or(el.rn), el.rn),
+            Operation::Mul(el) => register(&[el.rd.unwrap_or(el.rm), el.rn, el.rm]),
+            Operation::Mla(el) => register(&[el.rd, el.rn, el.rm, el.ra]),
+            Operation::Mls(el) => register(&[el.rd, el.rn, el.rm, el.ra]),
+            Operation::Sdiv(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::Udiv(el) => register(&[el.rd.unwrap_or(el.rn), el.rn, el.rm]),
+            Operation::Clz(el) => register(&[el.rd, el.rm]),
+            Operation::Rbit(el) => register(&[el.rd, el.rm]),
+            Operation::Rev(el) => register(&[el.rd, el.rm]),
+            Operation::Rev16(el) => register(&[el.rd, el.rm]),
+            Operation::Revsh(el) => register(&[el.rd, el.rm]),
+            Operation::Smull(el) => long(el.rdlo, el.rdhi, el.rn, el.rm),
+            Operation::Umull(el) => long(el.rdlo, el.rdhi, el.rn, el.rm),
+            Operation::Smlal(el) => long(el.rdlo, el.rdhi, el.rn, el.rm),
+            Operation::Umlal(el) => long(el.rdlo, el.rdhi, el.rn, el.rm),
+            Operation::Ldm(el) => {
+                register_list(&el.registers, Some(el.rn), el.w.unwrap_or(false), true)
+            }
+            Operation::Ldmdb(el) => {
+                register_list(&el.registers, Some(el.rn), el.w.unwrap_or(false), true)
+            }
+            Operation::Stm(el) => {
+                register_list(&el.registers, Some(el.rn), el.w.unwrap_or(false), false)
+            }
+            Operation::Stmdb(el) => {
+                register_list(&el.registers, Some(el.rn), el.w.unwrap_or(false), false)
+            }
+            Operation::Pop(el) => register_list(&el.registers, None, false, true),
+            Operation::Push(el) => register_list(&el.registers, None, false, false),
+            // Loads of a byte in to the PC are memory hints, or marked as
+            // UNPREDICTABLE in A5.20.
+            Operation::LdrbImmediate(el) if el.rt == Register::PC => {
+                Some(Self::Register(Register::PC))
+            }
+            Operation::Ldrbt(el) if el.rt == Register::PC => Some(Self::Register(Register::PC)),
+            Operation::LslRegister(el) => register(&[el.rd, el.rn, el.rm]),
+            Operation::LsrRegister(el) => register(&[el.rd, el.rn, el.rm]),
+            Operation::AsrRegister(el) => register(&[el.rd, el.rn, el.rm]),
+            Operation::RorRegister(el) => register(&[el.rd, el.rn, el.rm]),
+            Operation::LslImmediate(el) => register(&[el.rd, el.rm]),
+            Operation::LsrImmediate(el) => register(&[el.rd, el.rm]),
+            Operation::AsrImmediate(el) => register(&[el.rd, el.rm]),
+            Operation::RorImmediate(el) => register(&[el.rd, el.rm]),
+            Operation::Rrx(el) => register(&[el.rd, el.rm]),
+            // Without flags SP may be moved to or from any other register,
+            // A7.7.77.
+            Operation::MovRegister(el) => match (el.s.unwrap_or(false), el.rd, el.rm) {
+                (true, rd, rm) => register(&[rd, rm]),
+                (false, Register::PC, _) | (false, _, Register::PC) => {
+                    Some(Self::Register(Register::PC))
+                }
+                (false, Register::SP, Register::SP) => Some(Self::Register(Register::SP)),
+                _ => None,
+            },
+            Operation::MovImmediate(el) => register(&[el.rd]),
+            Operation::Movt(el) => register(&[el.rd]),
+            Operation::MvnRegister(el) => register(&[el.rd, el.rm]),
+            Operation::MvnImmediate(el) => register(&[el.rd]),
+            Operation::TstRegister(el) => register(&[el.rn, el.rm]),
+            Operation::TeqRegister(el) => register(&[el.rn, el.rm]),
+            Operation::TstImmediate(el) => register(&[el.rn]),
+            Operation::TeqImmediate(el) => register(&[el.rn]),
+            Operation::CmnRegister(el) => match el.rn {
+                Register::PC => Some(Self::Register(Register::PC)),
+                _ => register(&[el.rm]),
+            },
+            Operation::CmpImmediate(el) => pc(el.rn),
+            Operation::CmnImmediate(el) => pc(el.rn),
+            Operation::Ubfx(el) => register(&[el.rd, el.rn]),
+            Operation::Sbfx(el) => register(&[el.rd, el.rn]),
+            // A PC operand is decoded as `bfc`.
+            Operation::Bfi(el) => register(&[el.rd, el.rn]),
+            Operation::Bfc(el) => register(&[el.rd]),
+            Operation::Ssat(el) => register(&[el.rd, el.rn]),
+            Operation::Usat(el) => register(&[el.rd, el.rn]),
+            Operation::Ssat16(el) => register(&[el.rd, el.rn]),
+            Operation::Usat16(el) => register(&[el.rd, el.rn]),
+            // The table may be read relative to the PC, A7.7.182.
+            Operation::Tb(el) => match el.rn {
+                Register::SP => Some(Self::Register(Register::SP)),
+                _ => register(&[el.rm]),
+            },
+            Operation::Ldrex(el) => register(&[el.rt]).or_else(|| pc(el.rn)),
+            Operation::Ldrexb(el) => register(&[el.rt]).or_else(|| pc(el.rn)),
+            Operation::Ldrexh(el) => register(&[el.rt]).or_else(|| pc(el.rn)),
+            Operation::Strex(el) => exclusive(el.rd, el.rt, el.rn),
+            Operation::Strexb(el) => exclusive(el.rd, el.rt, el.rn),
+            Operation::Strexh(el) => exclusive(el.rd, el.rt, el.rn),
+            Operation::LdrImmediate(el) => writeback(el.w.unwrap_or(false), el.rn, &[el.rt]),
+            Operation::LdrbImmediate(el) => sp(el.rt)
+                .or_else(|| writeback(el.w.unwrap_or(false), el.rn, &[el.rt])),
+            Operation::LdrhImmediate(el) => sp(el.rt)
+                .or_else(|| writeback(el.w.unwrap_or(false), el.rn, &[el.rt])),
+            Operation::LdrsbImmediate(el) => {
+                sp(el.rt).or_else(|| writeback(el.wback, el.rn, &[el.rt]))
+            }
+            Operation::LdrshImmediate(el) => {
+                sp(el.rt).or_else(|| writeback(el.wback, el.rn, &[el.rt]))
+            }
+            Operation::StrImmediate(el) => {
+                pc(el.rt).or_else(|| writeback(el.w.unwrap_or(false), el.rn, &[el.rt]))
+            }
+            Operation::StrbImmediate(el) => register(&[el.rt])
+                .or_else(|| writeback(el.w.unwrap_or(false), el.rn, &[el.rt])),
+            Operation::StrhImmediate(el) => {
+                register(&[el.rt]).or_else(|| writeback(el.w, el.rn, &[el.rt]))
+            }
+            Operation::LdrdImmediate(el) => {
+                dual(el.w.unwrap_or(false), el.rn, el.rt, el.rt2)
+                    .or_else(|| (el.rt == el.rt2).then_some(Self::SameDestination(el.rt)))
+            }
+            Operation::StrdImmediate(el) => {
+                pc(el.rn).or_else(|| dual(el.w.unwrap_or(false), el.rn, el.rt, el.rt2))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checks the destination of a 32 bit addition or subtraction, SP is only
+/// allowed as the destination when it is also the first operand.
+fn add_sub(rd: Register, rn: Register) -> Option<Reason> {
+    match (rd, rn) {
+        (Register::PC, _) | (_, Register::PC) => Some(Reason::Register(Register::PC)),
+        (Register::SP, rn) if rn != Register::SP => Some(Reason::Register(Register::SP)),
+        _ => None,
+    }
+}
+
+/// Returns a reason if the register is the PC.
+fn pc(register: Register) -> Option<Reason> {
+    (register == Register::PC).then_some(Reason::Register(Register::PC))
+}
+
+/// Returns a reason if the register is SP.
+fn sp(register: Register) -> Option<Reason> {
+    (register == Register::SP).then_some(Reason::Register(Register::SP))
+}
+
+/// Checks that a base register which is written back is not also
+/// transferred.
+fn writeback(writeback: bool, rn: Register, transferred: &[Register]) -> Option<Reason> {
+    (writeback && transferred.contains(&rn)).then_some(Reason::Writeback(rn))
+}
+
+/// Checks the operands of a load or store of two registers.
+fn dual(w: bool, rn: Register, rt: Register, rt2: Register) -> Option<Reason> {
+    bad_reg(&[rt, rt2])
+        .map(Reason::Register)
+        .or_else(|| match w {
+            true => pc(rn),
+            false => None,
+        })
+        .or_else(|| writeback(w, rn, &[rt, rt2]))
+}
+
+/// Checks the operands of a store exclusive, the status register can not
+/// be one of the other operands.
+fn exclusive(rd: Register, rt: Register, rn: Register) -> Option<Reason> {
+    bad_reg(&[rd, rt])
+        .map(Reason::Register)
+        .or_else(|| pc(rn))
+        .or_else(|| (rd == rt || rd == rn).then_some(Reason::Status(rd)))
+}
+
+/// Checks the operands of a long multiply.
+fn long(rdlo: Register, rdhi: Register, rn: Register, rm: Register) -> Option<Reason> {
+    bad_reg(&[rdlo, rdhi, rn, rm])
+        .map(Reason::Register)
+        .or_else(|| (rdlo == rdhi).then_some(Reason::SameDestination(rdlo)))
+}
+
+impl Policy {
+    /// Returns the operation that is executed for an UNPREDICTABLE
+    /// encoding under this policy.
+    pub(crate) fn apply(&self, operation: Operation, reason: Reason) -> Operation {
+        if *self != Self::MatchHardware {
+            return operation;
+        }
+        match (operation, reason) {
+            (Operation::Mrs(el), Reason::SpecialRegister(_)) => operation::MovImmediate::builder()
+                .set_s(None)
+                .set_rd(el.rd)
+                .set_imm(0)
+                .set_carry(None)
+                .complete()
+                .into(),
+            (Operation::Msr(_), Reason::SpecialRegister(_) | Reason::ApsrMask(_)) => {
+                operation::Nop::builder().complete().into()
+            }
+            (operation, _) => operation,
+        }
+    }
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Register(register) => write!(f, "{register:?} is not allowed as an operand"),
+            Self::RegisterCount => write!(f, "too few registers in the register list"),
+            Self::RegisterList(register) => {
+                write!(f, "{register:?} is not allowed in the register list")
+            }
+            Self::Writeback(register) => {
+                write!(f, "{register:?} is written back and in the register list")
+            }
+            Self::SameDestination(register) => {
+                write!(f, "{register:?} is used for both halves of the result")
+            }
+            Self::Status(register) => {
+                write!(f, "{register:?} is both the status and an operand of the store")
+            }
+            Self::SpecialRegister(sysm) => write!(f, "reserved special register {sysm:#x}"),
+            Self::ApsrMask(mask) => write!(f, "invalid mask {:#04b}", u8::from(*mask)),
+            Self::Encoding(table) => write!(f, "unpredictable encoding in {table}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Policy, Reason};
+    use crate::{helpers::test::code, prelude::*, ErrorKind, Raw};
+
+    fn decode(
+        halfwords: &[u16],
+        policy: Policy,
+    ) -> Result<(usize, Operation, Option<Reason>), crate::ParseError> {
+        let bin = code(halfwords);
+        let mut stream = PeekableBuffer::from(bin.into_iter());
+        Operation::decode(&mut stream, policy)
+            .map(|decoded| (decoded.size.bits(), decoded.op, decoded.unpredictable))
+    }
+
+    #[test]
+    fn test_strict() {
+        // and.w pc, r1, r2
+        let error = decode(&[0xea01, 0x0f02], Policy::Strict).expect_err("Unpredictable accepted");
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Unpredictable(Reason::Register(Register::PC))
+        ));
+        assert_eq!(error.raw, Some(Raw::Word(0xea01_0f02)));
+        // cmp r1, r2 encoded as T2
+        let error = decode(&[0x4511], Policy::Strict).expect_err("Unpredictable accepted");
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Unpredictable(Reason::Encoding("A5.4"))
+        ));
+        // ldm.w r0!, {r0, r1}
+        let error = decode(&[0xe8b0, 0x0003], Policy::Strict).expect_err("Unpredictable accepted");
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Unpredictable(Reason::Writeback(Register::R0))
+        ));
+        // The narrow encodings allow the PC.
+        // mov pc, r0
+        assert!(decode(&[0x4687], Policy::Strict).is_ok());
+    }
+
+    /// Returns the reason that the encoding is rejected with by
+    /// [`Policy::Strict`], after checking that [`Policy::Permissive`] tags it
+    /// with the same reason.
+    fn reason(halfwords: &[u16]) -> Reason {
+        let error = decode(halfwords, Policy::Strict).expect_err("Unpredictable accepted");
+        let ErrorKind::Unpredictable(reason) = error.kind else {
+            panic!("Expected an unpredictable instruction, got {error}");
+        };
+        let (_, _, tagged) = decode(halfwords, Policy::Permissive).unwrap();
+        assert_eq!(tagged, Some(reason));
+        reason
+    }
+
+    #[test]
+    fn test_shift() {
+        // lsl.w pc, r0, r1
+        assert_eq!(reason(&[0xfa00, 0xff01]), Reason::Register(Register::PC));
+        // lsl.w r0, sp, #1
+        assert_eq!(reason(&[0xea4f, 0x004d]), Reason::Register(Register::SP));
+        // lsl.w r0, r1, r2
+        assert!(decode(&[0xfa01, 0xf002], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_move() {
+        // mov.w pc, r0
+        assert_eq!(reason(&[0xea4f, 0x0f00]), Reason::Register(Register::PC));
+        // movw sp, #1
+        assert_eq!(reason(&[0xf240, 0x0d01]), Reason::Register(Register::SP));
+        // movt pc, #1
+        assert_eq!(reason(&[0xf2c0, 0x0f01]), Reason::Register(Register::PC));
+        // mvn.w r0, sp
+        assert_eq!(reason(&[0xea6f, 0x000d]), Reason::Register(Register::SP));
+        // mov.w sp, r0 and mov.w r0, sp are allowed without flags.
+        assert!(decode(&[0xea4f, 0x0d00], Policy::Strict).is_ok());
+        assert!(decode(&[0xea4f, 0x000d], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_compare() {
+        // tst.w sp, r0
+        assert_eq!(reason(&[0xea1d, 0x0f00]), Reason::Register(Register::SP));
+        // teq.w r0, pc
+        assert_eq!(reason(&[0xea90, 0x0f0f]), Reason::Register(Register::PC));
+        // cmn.w r0, sp
+        assert_eq!(reason(&[0xeb10, 0x0f0d]), Reason::Register(Register::SP));
+        // cmp.w pc, #1
+        assert_eq!(reason(&[0xf1bf, 0x0f01]), Reason::Register(Register::PC));
+        // cmp.w sp, #1
+        assert!(decode(&[0xf1bd, 0x0f01], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_bitfield() {
+        // ubfx pc, r0, #0, #1
+        assert_eq!(reason(&[0xf3c0, 0x0f00]), Reason::Register(Register::PC));
+        // bfi r0, sp, #0, #1
+        assert_eq!(reason(&[0xf36d, 0x0000]), Reason::Register(Register::SP));
+        // ubfx r0, r1, #0, #1
+        assert!(decode(&[0xf3c1, 0x0000], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_saturate() {
+        // ssat pc, #1, r0
+        assert_eq!(reason(&[0xf300, 0x0f00]), Reason::Register(Register::PC));
+        // usat r0, #1, sp
+        assert_eq!(reason(&[0xf38d, 0x0001]), Reason::Register(Register::SP));
+        // ssat r0, #1, r1
+        assert!(decode(&[0xf301, 0x0000], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_table_branch() {
+        // tbb [pc, sp]
+        assert_eq!(reason(&[0xe8df, 0xf00d]), Reason::Register(Register::SP));
+        // tbh [sp, r0]
+        assert_eq!(reason(&[0xe8dd, 0xf010]), Reason::Register(Register::SP));
+        // tbb [pc, r0]
+        assert!(decode(&[0xe8df, 0xf000], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_blx() {
+        // blx pc
+        assert_eq!(reason(&[0x47f8]), Reason::Register(Register::PC));
+        // blx r3
+        assert!(decode(&[0x4798], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_exclusive() {
+        // strex r0, r0, [r1]
+        assert_eq!(reason(&[0xe841, 0x0000]), Reason::Status(Register::R0));
+        // strex r1, r0, [r1]
+        assert_eq!(reason(&[0xe841, 0x0100]), Reason::Status(Register::R1));
+        // ldrex pc, [r0]
+        assert_eq!(reason(&[0xe850, 0xff00]), Reason::Register(Register::PC));
+        // strex r2, r0, [r1]
+        assert!(decode(&[0xe841, 0x0200], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_writeback() {
+        // ldr r0, [r0], #4
+        assert_eq!(reason(&[0xf850, 0x0b04]), Reason::Writeback(Register::R0));
+        // str r0, [r0, #4]!
+        assert_eq!(reason(&[0xf840, 0x0f04]), Reason::Writeback(Register::R0));
+        // ldrd r0, r1, [r0], #8
+        assert_eq!(reason(&[0xe8f0, 0x0102]), Reason::Writeback(Register::R0));
+        // ldrd r0, r0, [r1]
+        assert_eq!(reason(&[0xe9d1, 0x0000]), Reason::SameDestination(Register::R0));
+        // ldr r0, [r0, #4] does not write back.
+        assert!(decode(&[0xf8d0, 0x0004], Policy::Strict).is_ok());
+        // ldr r0, [r1], #4
+        assert!(decode(&[0xf851, 0x0b04], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_empty_push_pop() {
+        // push {} and pop {}, A7.7.101 and A7.7.99
+        for halfword in [0xb400, 0xbc00] {
+            let error = decode(&[halfword], Policy::Strict).expect_err("Unpredictable accepted");
+            assert!(matches!(
+                error.kind,
+                ErrorKind::Unpredictable(Reason::RegisterCount)
+            ));
+            let (size, _, reason) = decode(&[halfword], Policy::Permissive).unwrap();
+            assert_eq!(size, 16);
+            assert_eq!(reason, Some(Reason::RegisterCount));
+        }
+        // push {lr} and pop {pc}
+        assert!(decode(&[0xb500], Policy::Strict).is_ok());
+        assert!(decode(&[0xbd00], Policy::Strict).is_ok());
+    }
+
+    #[test]
+    fn test_permissive() {
+        // and.w pc, r1, r2
+        let (size, operation, reason) = decode(&[0xea01, 0x0f02], Policy::Permissive).unwrap();
+        assert_eq!(size, 32);
+        assert_eq!(reason, Some(Reason::Register(Register::PC)));
+        let target: Operation = operation::AndRegister::builder()
+            .set_s(Some(SetFlags::Literal(false)))
+            .set_rd(Some(Register::PC))
+            .set_rn(Register::R1)
+            .set_rm(Register::R2)
+            .set_shift(Some(ImmShift::from((Shift::Lsl, 0))))
+            .complete()
+            .into();
+        assert_eq!(operation, target);
+        // cmp r1, r2 encoded as T2
+        let (_, operation, reason) = decode(&[0x4511], Policy::Permissive).unwrap();
+        assert_eq!(reason, Some(Reason::Encoding("A5.4")));
+        assert!(matches!(operation, Operation::CmpRegister(_)));
+    }
+
+    #[test]
+    fn test_match_hardware() {
+        // mrs r2, <reserved>
+        let (_, operation, reason) = decode(&[0xf3ef, 0x8281], Policy::MatchHardware).unwrap();
+        assert_eq!(reason, Some(Reason::SpecialRegister(0x81)));
+        let target: Operation = operation::MovImmediate::builder()
+            .set_s(None)
+            .set_rd(Register::R2)
+            .set_imm(0)
+            .set_carry(None)
+            .complete()
+            .into();
+        assert_eq!(operation, target);
+        // msr PSP, r0 with an empty mask
+        let (_, operation, reason) = decode(&[0xf380, 0x8009], Policy::MatchHardware).unwrap();
+        assert!(matches!(reason, Some(Reason::ApsrMask(_))));
+        assert!(matches!(operation, Operation::Nop(_)));
+        let (_, operation, _) = decode(&[0xf380, 0x8009], Policy::Permissive).unwrap();
+        assert!(matches!(operation, Operation::Msr(_)));
+    }
+
+    #[test]
+    fn test_udf() {
+        // udf #0 is UNDEFINED rather than UNPREDICTABLE.
+        let (_, operation, reason) = decode(&[0xde00], Policy::Strict).unwrap();
+        assert_eq!(reason, None);
+        assert!(matches!(operation, Operation::Udf(_)));
+    }
+}